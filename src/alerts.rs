@@ -1,28 +1,695 @@
 use llio::{Llio, VibePattern};
+use ticktimer_server::Ticktimer;
+
+use crate::eventlog::EventSource;
+
+/// Buzz used for alerts. Our own copy of the llio patterns we offer, so
+/// the stored byte and the mapping can be tested without the hardware.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AlertVibe {
+    Single,
+    Double,
+    Long,
+}
+
+impl AlertVibe {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlertVibe::Single => "Single",
+            AlertVibe::Double => "Double",
+            AlertVibe::Long => "Long",
+        }
+    }
+
+    /// Next option, for cycling through them in settings.
+    pub fn next(self) -> Self {
+        match self {
+            AlertVibe::Single => AlertVibe::Double,
+            AlertVibe::Double => AlertVibe::Long,
+            AlertVibe::Long => AlertVibe::Single,
+        }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        match self {
+            AlertVibe::Single => 0,
+            AlertVibe::Double => 1,
+            AlertVibe::Long => 2,
+        }
+    }
+
+    /// Unknown values fall back to `Double`, the original buzz.
+    pub fn from_byte(b: u8) -> Self {
+        match b {
+            0 => AlertVibe::Single,
+            2 => AlertVibe::Long,
+            _ => AlertVibe::Double,
+        }
+    }
+
+    pub fn pattern(self) -> VibePattern {
+        match self {
+            AlertVibe::Single => VibePattern::Short,
+            AlertVibe::Double => VibePattern::Double,
+            AlertVibe::Long => VibePattern::Long,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct AlertConfig {
     pub vibration: bool,
     pub audio: bool,
     pub notification: bool,
+    /// Flash the backlight, a visible-only alert.
+    pub led: bool,
+    /// Ramp the vibration up instead of a single strong buzz.
+    pub soft_start_vibe: bool,
+    /// Keep repeating an expiry alert until a key is pressed.
+    pub escalate: bool,
+    pub vibe: AlertVibe,
+    /// How long a snoozed countdown runs again; 0 turns snoozing off.
+    pub snooze_ms: u64,
+    /// Quiet hours window in minutes since local midnight; vibration and
+    /// audio stay off inside it. Equal ends mean no quiet hours.
+    pub quiet_start_min: u16,
+    pub quiet_end_min: u16,
 }
 
+/// Longest snooze the settings accept.
+pub const MAX_SNOOZE_MS: u64 = 60 * 60 * 1000;
+
 impl AlertConfig {
     pub fn default() -> Self {
         Self {
             vibration: true,
             audio: false,
             notification: true,
+            led: false,
+            soft_start_vibe: false,
+            escalate: false,
+            vibe: AlertVibe::Double,
+            snooze_ms: 5 * 60 * 1000,
+            quiet_start_min: 0,
+            quiet_end_min: 0,
+        }
+    }
+
+    pub fn in_quiet_hours(&self, now_min: u16) -> bool {
+        in_quiet_hours(now_min, self.quiet_start_min, self.quiet_end_min)
+    }
+
+    /// Set the snooze length, refusing anything over `MAX_SNOOZE_MS`.
+    pub fn set_snooze_ms(&mut self, ms: u64) -> bool {
+        if ms > MAX_SNOOZE_MS {
+            return false;
         }
+        self.snooze_ms = ms;
+        true
+    }
+
+    /// Compact "V A N L" style summary of enabled channels, with "-" for
+    /// disabled ones, for timer screen headers.
+    pub fn channel_summary(&self) -> String {
+        let flag = |on: bool, c: char| if on { c } else { '-' };
+        [
+            flag(self.vibration, 'V'),
+            flag(self.audio, 'A'),
+            flag(self.notification, 'N'),
+            flag(self.led, 'L'),
+        ]
+        .iter()
+        .collect()
     }
 }
 
-pub fn fire_alert(config: &AlertConfig, llio: &Llio, modals: &modals::Modals, message: &str) {
-    if config.vibration {
-        llio.vibe(VibePattern::Double).ok();
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// Whether `now_min` falls in the quiet window from `start` (inclusive) to
+/// `end` (exclusive), all minutes since midnight. A window whose start is
+/// after its end wraps past midnight; `start == end` is no window at all.
+pub fn in_quiet_hours(now_min: u16, start: u16, end: u16) -> bool {
+    if start <= end {
+        start <= now_min && now_min < end
+    } else {
+        now_min >= start || now_min < end
+    }
+}
+
+/// Parse a quiet hours window typed as "HH:MM-HH:MM". Blank input turns
+/// quiet hours off, giving `(0, 0)`.
+pub fn parse_quiet_hours(text: &str) -> Option<(u16, u16)> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Some((0, 0));
+    }
+    let (start, end) = text.split_once('-')?;
+    Some((parse_clock(start)?, parse_clock(end)?))
+}
+
+fn parse_clock(text: &str) -> Option<u16> {
+    let (hours, minutes) = text.trim().split_once(':')?;
+    let hours: u16 = hours.parse().ok()?;
+    let minutes: u16 = minutes.parse().ok()?;
+    if hours >= 24 || minutes >= 60 {
+        return None;
+    }
+    Some(hours * 60 + minutes)
+}
+
+/// "HH:MM-HH:MM" for a quiet hours window.
+pub fn format_quiet_hours(start: u16, end: u16) -> String {
+    format!("{:02}:{:02}-{:02}:{:02}", start / 60, start % 60, end / 60, end % 60)
+}
+
+/// Expirations that happened while their timer's screen wasn't showing.
+/// Drives the "timer finished" badge until that screen is visited.
+#[derive(Default)]
+pub struct UnackedExpiries {
+    pomodoro: u16,
+    countdown: u16,
+    interval: u16,
+}
+
+impl UnackedExpiries {
+    fn slot(&mut self, source: EventSource) -> Option<&mut u16> {
+        match source {
+            EventSource::Pomodoro => Some(&mut self.pomodoro),
+            EventSource::Countdown => Some(&mut self.countdown),
+            EventSource::Interval => Some(&mut self.interval),
+            // Stopwatches never expire
+            EventSource::Stopwatch => None,
+        }
+    }
+
+    /// Count an expiry of `source` unless the user is looking at it.
+    pub fn record(&mut self, source: EventSource, viewing: Option<EventSource>) {
+        if viewing == Some(source) {
+            return;
+        }
+        if let Some(count) = self.slot(source) {
+            *count = count.saturating_add(1);
+        }
+    }
+
+    /// Clear expiries for the timer now on screen.
+    pub fn acknowledge(&mut self, viewing: Option<EventSource>) {
+        if let Some(count) = viewing.and_then(|source| self.slot(source)) {
+            *count = 0;
+        }
+    }
+
+    /// Unacknowledged expiries, not counting the timer on screen.
+    pub fn pending(&self, viewing: Option<EventSource>) -> u32 {
+        let mut total = 0;
+        if viewing != Some(EventSource::Pomodoro) {
+            total += self.pomodoro as u32;
+        }
+        if viewing != Some(EventSource::Countdown) {
+            total += self.countdown as u32;
+        }
+        if viewing != Some(EventSource::Interval) {
+            total += self.interval as u32;
+        }
+        total
+    }
+}
+
+/// Gap before the next repeat of an unacknowledged expiry alert,
+/// `since_expiry_ms` after it went off: every 5 s for the first half
+/// minute, every 10 s up to two minutes, then every 30 s. After ten
+/// minutes it gives up (None).
+pub fn escalation_delay_ms(since_expiry_ms: u64) -> Option<u64> {
+    match since_expiry_ms {
+        t if t < 30_000 => Some(5_000),
+        t if t < 2 * 60_000 => Some(10_000),
+        t if t < 10 * 60_000 => Some(30_000),
+        _ => None,
+    }
+}
+
+/// An expiry alert being repeated until it's acknowledged.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Escalation {
+    expired_ms: u64,
+    next_ms: u64,
+}
+
+impl Escalation {
+    pub fn start(now_ms: u64) -> Self {
+        Self { expired_ms: now_ms, next_ms: now_ms + escalation_delay_ms(0).unwrap_or(0) }
+    }
+
+    /// Whether the alert should repeat at `now_ms`, scheduling the one
+    /// after. None once escalation has given up.
+    pub fn tick(&mut self, now_ms: u64) -> Option<bool> {
+        if now_ms < self.next_ms {
+            return Some(false);
+        }
+        let delay = escalation_delay_ms(now_ms.saturating_sub(self.expired_ms))?;
+        self.next_ms = now_ms + delay;
+        Some(true)
+    }
+}
+
+/// One step of a vibration sequence: wait `delay_ms`, then buzz.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VibeStep {
+    pub delay_ms: u32,
+    pub strong: bool,
+}
+
+const RAMP_GROUPS: u32 = 3;
+const RAMP_BUZZ_GAP_MS: u32 = 80;
+const RAMP_GROUP_GAP_MS: u32 = 250;
+
+/// Light-to-strong vibration ramp lasting about a second. llio has no
+/// intensity control, so groups of short buzzes grow in count (1, 2, 3)
+/// before finishing with the normal strong buzz.
+pub fn soft_start_ramp() -> Vec<VibeStep> {
+    let mut steps = Vec::new();
+    for group in 1..=RAMP_GROUPS {
+        for buzz in 0..group {
+            let delay_ms = match (group, buzz) {
+                (1, 0) => 0,
+                (_, 0) => RAMP_GROUP_GAP_MS,
+                _ => RAMP_BUZZ_GAP_MS,
+            };
+            steps.push(VibeStep { delay_ms, strong: false });
+        }
+    }
+    steps.push(VibeStep { delay_ms: RAMP_GROUP_GAP_MS, strong: true });
+    steps
+}
+
+/// One note of the alert tone: `freq_hz` for `duration_ms`; 0 Hz rests.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ToneStep {
+    pub freq_hz: u32,
+    pub duration_ms: u32,
+}
+
+const TONE_LOW_HZ: u32 = 880;
+const TONE_HIGH_HZ: u32 = 1320;
+const BEEP_MS: u32 = 120;
+const BEEP_GAP_MS: u32 = 80;
+const FINAL_BEEP_MS: u32 = 240;
+/// The codec's stream rate.
+const TONE_SAMPLE_RATE_HZ: u32 = 8000;
+/// Peak sample value; well under full scale so the beep isn't harsh.
+const TONE_AMPLITUDE: i16 = 6000;
+const FRAME_POLL_MS: usize = 10;
+
+/// Two short beeps and a longer, higher one.
+pub fn alert_tone_pattern() -> Vec<ToneStep> {
+    let beep = ToneStep { freq_hz: TONE_LOW_HZ, duration_ms: BEEP_MS };
+    let gap = ToneStep { freq_hz: 0, duration_ms: BEEP_GAP_MS };
+    vec![beep, gap, beep, gap, ToneStep { freq_hz: TONE_HIGH_HZ, duration_ms: FINAL_BEEP_MS }]
+}
+
+/// Square-wave samples for `steps` at `rate_hz`.
+pub fn tone_samples(steps: &[ToneStep], rate_hz: u32) -> Vec<i16> {
+    let mut samples = Vec::new();
+    for step in steps {
+        let count = rate_hz as u64 * step.duration_ms as u64 / 1000;
+        for i in 0..count {
+            // Half periods elapsed decide the sign, so odd rates don't drift
+            let sample = match step.freq_hz {
+                0 => 0,
+                freq if (i * 2 * freq as u64 / rate_hz as u64) & 1 == 0 => TONE_AMPLITUDE,
+                _ => -TONE_AMPLITUDE,
+            };
+            samples.push(sample);
+        }
+    }
+    samples
+}
+
+/// One stretch of the backlight flash.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FlashStep {
+    pub on: bool,
+    pub duration_ms: u32,
+}
+
+const FLASH_COUNT: u32 = 3;
+const FLASH_ON_MS: u32 = 150;
+const FLASH_OFF_MS: u32 = 150;
+/// Full brightness for both backlight channels.
+const BACKLIGHT_FULL: u8 = 255;
+
+/// Three quick blinks, ending dark. The com service can't report the
+/// brightness to restore, and the memory LCD reads fine unlit, so the
+/// backlight goes back to off, its resting state, as the system would
+/// leave it after its own timeout.
+pub fn flash_pattern() -> Vec<FlashStep> {
+    let mut steps = Vec::new();
+    for _ in 0..FLASH_COUNT {
+        steps.push(FlashStep { on: true, duration_ms: FLASH_ON_MS });
+        steps.push(FlashStep { on: false, duration_ms: FLASH_OFF_MS });
+    }
+    // Nothing to wait for after the last switch-off
+    if let Some(last) = steps.last_mut() {
+        last.duration_ms = 0;
+    }
+    steps
+}
+
+/// Blink the backlight, blocking until it's done. Failures are logged and
+/// the rest of the alert goes on.
+pub fn flash_backlight(tt: &Ticktimer) {
+    if let Err(e) = play_flash_steps(tt, &flash_pattern()) {
+        log::warn!("Backlight flash unavailable: {:?}", e);
+    }
+}
+
+fn play_flash_steps(tt: &Ticktimer, steps: &[FlashStep]) -> Result<(), xous::Error> {
+    let xns = xous_names::XousNames::new()?;
+    let com = com::Com::new(&xns)?;
+    for step in steps {
+        let level = if step.on { BACKLIGHT_FULL } else { 0 };
+        com.set_backlight(level, level)?;
+        if step.duration_ms > 0 {
+            tt.sleep_ms(step.duration_ms as usize).ok();
+        }
+    }
+    Ok(())
+}
+
+/// Play the alert tone through the codec, blocking until it's done. The
+/// codec may be missing or busy; that is logged and the alert goes on
+/// without sound.
+pub fn play_alert_tone(tt: &Ticktimer) {
+    let samples = tone_samples(&alert_tone_pattern(), TONE_SAMPLE_RATE_HZ);
+    if let Err(e) = stream_tone(tt, &samples) {
+        log::warn!("Alert tone unavailable: {:?}", e);
+    }
+}
+
+fn stream_tone(tt: &Ticktimer, samples: &[i16]) -> Result<(), xous::Error> {
+    let xns = xous_names::XousNames::new()?;
+    let mut codec = codec::Codec::new(&xns)?;
+    codec.setup_8k_stream()?;
+
+    // Same sample on both channels, left in the low half
+    let mut frames = samples
+        .chunks(codec::FIFO_DEPTH)
+        .map(|chunk| {
+            let mut frame = [0u32; codec::FIFO_DEPTH];
+            for (slot, &sample) in frame.iter_mut().zip(chunk) {
+                let bits = sample as u16 as u32;
+                *slot = bits | bits << 16;
+            }
+            frame
+        })
+        .peekable();
+    let mut started = false;
+    while frames.peek().is_some() {
+        let (play_free, _) = codec.free_frames()?;
+        if play_free > 0 {
+            let mut ring = codec::FrameRing::new();
+            for frame in frames.by_ref().take(play_free) {
+                ring.nq_frame(frame).ok();
+            }
+            codec.swap_frames(&mut ring)?;
+            if !started {
+                codec.resume()?;
+                started = true;
+            }
+        }
+        tt.sleep_ms(FRAME_POLL_MS).ok();
+    }
+    // Let the queued frames drain before powering down
+    let queued_ms = (codec::FIFO_DEPTH as u32 * 1000 / TONE_SAMPLE_RATE_HZ) as usize;
+    tt.sleep_ms(queued_ms * 2).ok();
+    codec.pause()?;
+    codec.power_off()
+}
+
+/// Play a ramp, finishing with `strong` for its strong steps.
+fn play_vibe_steps(llio: &Llio, tt: &Ticktimer, steps: &[VibeStep], strong: VibePattern) {
+    for step in steps {
+        if step.delay_ms > 0 {
+            tt.sleep_ms(step.delay_ms as usize).ok();
+        }
+        let pattern = if step.strong { strong } else { VibePattern::Short };
+        llio.vibe(pattern).ok();
+    }
+}
+
+pub fn fire_alert(config: &AlertConfig, llio: &Llio, tt: &Ticktimer, modals: &modals::Modals, message: &str, now_min: u16) {
+    let quiet = config.in_quiet_hours(now_min);
+    if config.vibration && !quiet {
+        if config.soft_start_vibe {
+            play_vibe_steps(llio, tt, &soft_start_ramp(), config.vibe.pattern());
+        } else {
+            llio.vibe(config.vibe.pattern()).ok();
+        }
+    }
+    if config.led {
+        flash_backlight(tt);
+    }
+    // The notification blocks until dismissed, so everything meant to get
+    // attention goes first
+    if config.audio && !quiet {
+        play_alert_tone(tt);
     }
     if config.notification {
         modals.show_notification(message, None).ok();
     }
-    // Audio tone generation could be added here with codec support
+}
+
+/// Repeat an escalating alert: a plain buzz, the flash and the tone,
+/// without another notification. Quiet hours leave only the flash.
+pub fn repeat_alert(config: &AlertConfig, llio: &Llio, tt: &Ticktimer, now_min: u16) {
+    if config.led {
+        flash_backlight(tt);
+    }
+    if config.in_quiet_hours(now_min) {
+        return;
+    }
+    if config.vibration {
+        llio.vibe(config.vibe.pattern()).ok();
+    }
+    if config.audio {
+        play_alert_tone(tt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_summary() {
+        assert_eq!(AlertConfig::default().channel_summary(), "V-N-");
+        let all = AlertConfig {
+            vibration: true,
+            audio: true,
+            notification: true,
+            led: true,
+            soft_start_vibe: true,
+            escalate: true,
+            vibe: AlertVibe::Long,
+            snooze_ms: 0,
+            quiet_start_min: 0,
+            quiet_end_min: 0,
+        };
+        assert_eq!(all.channel_summary(), "VANL");
+        let none = AlertConfig { vibration: false, audio: false, notification: false, ..AlertConfig::default() };
+        assert_eq!(none.channel_summary(), "----");
+    }
+
+    #[test]
+    fn test_flash_pattern() {
+        let steps = flash_pattern();
+        assert_eq!(steps.len() as u32, FLASH_COUNT * 2);
+        // Alternates, starting lit
+        for (i, step) in steps.iter().enumerate() {
+            assert_eq!(step.on, i % 2 == 0);
+        }
+        // Ends dark, not left at full brightness, with nothing to wait for
+        assert_eq!(steps.last(), Some(&FlashStep { on: false, duration_ms: 0 }));
+        let total_ms: u32 = steps.iter().map(|s| s.duration_ms).sum();
+        assert_eq!(total_ms, FLASH_COUNT * FLASH_ON_MS + (FLASH_COUNT - 1) * FLASH_OFF_MS);
+        assert!(total_ms <= 1000);
+    }
+
+    #[test]
+    fn test_in_quiet_hours_same_day() {
+        // 13:00-14:30
+        let (start, end) = (13 * 60, 14 * 60 + 30);
+        assert!(!in_quiet_hours(12 * 60 + 59, start, end));
+        assert!(in_quiet_hours(13 * 60, start, end));
+        assert!(in_quiet_hours(14 * 60 + 29, start, end));
+        assert!(!in_quiet_hours(14 * 60 + 30, start, end));
+        assert!(!in_quiet_hours(0, start, end));
+    }
+
+    #[test]
+    fn test_in_quiet_hours_wraps_midnight() {
+        // 22:00-06:00
+        let (start, end) = (22 * 60, 6 * 60);
+        assert!(!in_quiet_hours(21 * 60 + 59, start, end));
+        assert!(in_quiet_hours(22 * 60, start, end));
+        assert!(in_quiet_hours(MINUTES_PER_DAY - 1, start, end));
+        assert!(in_quiet_hours(0, start, end));
+        assert!(in_quiet_hours(5 * 60 + 59, start, end));
+        assert!(!in_quiet_hours(6 * 60, start, end));
+        assert!(!in_quiet_hours(12 * 60, start, end));
+    }
+
+    #[test]
+    fn test_in_quiet_hours_edges() {
+        // Equal ends: no quiet hours at all
+        for now in [0, 600, MINUTES_PER_DAY - 1] {
+            assert!(!in_quiet_hours(now, 600, 600));
+            assert!(!in_quiet_hours(now, 0, 0));
+        }
+        // Midnight to midnight-minus-one covers all but the last minute
+        assert!(in_quiet_hours(0, 0, MINUTES_PER_DAY - 1));
+        assert!(!in_quiet_hours(MINUTES_PER_DAY - 1, 0, MINUTES_PER_DAY - 1));
+        // Starting a minute before midnight and ending at it
+        assert!(in_quiet_hours(MINUTES_PER_DAY - 1, MINUTES_PER_DAY - 1, 0));
+        assert!(!in_quiet_hours(0, MINUTES_PER_DAY - 1, 0));
+
+        let config = AlertConfig { quiet_start_min: 22 * 60, quiet_end_min: 6 * 60, ..AlertConfig::default() };
+        assert!(config.in_quiet_hours(23 * 60));
+        assert!(!AlertConfig::default().in_quiet_hours(23 * 60));
+    }
+
+    #[test]
+    fn test_parse_quiet_hours() {
+        assert_eq!(parse_quiet_hours("22:00-06:00"), Some((1320, 360)));
+        assert_eq!(parse_quiet_hours(" 9:05 - 17:30 "), Some((545, 1050)));
+        assert_eq!(parse_quiet_hours(""), Some((0, 0)));
+        assert_eq!(parse_quiet_hours("24:00-06:00"), None);
+        assert_eq!(parse_quiet_hours("22:60-06:00"), None);
+        assert_eq!(parse_quiet_hours("22:00"), None);
+        assert_eq!(parse_quiet_hours("ten-six"), None);
+        assert_eq!(format_quiet_hours(1320, 360), "22:00-06:00");
+    }
+
+    #[test]
+    fn test_set_snooze_ms() {
+        let mut config = AlertConfig::default();
+        assert!(config.set_snooze_ms(10 * 60 * 1000));
+        assert_eq!(config.snooze_ms, 10 * 60 * 1000);
+        assert!(config.set_snooze_ms(0));
+        assert!(!config.set_snooze_ms(MAX_SNOOZE_MS + 1));
+        assert_eq!(config.snooze_ms, 0);
+    }
+
+    #[test]
+    fn test_soft_start_ramp() {
+        let steps = soft_start_ramp();
+        // 1 + 2 + 3 light buzzes, then one strong
+        assert_eq!(steps.len(), 7);
+        assert_eq!(steps.iter().filter(|s| s.strong).count(), 1);
+        assert!(steps.last().unwrap().strong);
+        assert_eq!(steps[0].delay_ms, 0);
+
+        // Groups separated by the longer gap
+        let group_starts: Vec<usize> = steps.iter().enumerate()
+            .filter(|(_, s)| s.delay_ms == RAMP_GROUP_GAP_MS)
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(group_starts, vec![1, 3, 6]);
+
+        let total: u32 = steps.iter().map(|s| s.delay_ms).sum();
+        assert!(total <= 1200, "ramp too long: {}ms", total);
+    }
+
+    #[test]
+    fn test_alert_vibe_bytes() {
+        for vibe in [AlertVibe::Single, AlertVibe::Double, AlertVibe::Long] {
+            assert_eq!(AlertVibe::from_byte(vibe.to_byte()), vibe);
+        }
+        assert_eq!(AlertVibe::from_byte(9), AlertVibe::Double);
+        assert!(matches!(AlertVibe::Single.pattern(), VibePattern::Short));
+        assert!(matches!(AlertVibe::Double.pattern(), VibePattern::Double));
+        assert!(matches!(AlertVibe::Long.pattern(), VibePattern::Long));
+        // Cycling visits every option and comes back round
+        assert_eq!(AlertVibe::Single.next().next().next(), AlertVibe::Single);
+    }
+
+    #[test]
+    fn test_escalation_delay() {
+        assert_eq!(escalation_delay_ms(0), Some(5_000));
+        assert_eq!(escalation_delay_ms(29_999), Some(5_000));
+        assert_eq!(escalation_delay_ms(30_000), Some(10_000));
+        assert_eq!(escalation_delay_ms(119_999), Some(10_000));
+        assert_eq!(escalation_delay_ms(120_000), Some(30_000));
+        assert_eq!(escalation_delay_ms(599_999), Some(30_000));
+        assert_eq!(escalation_delay_ms(600_000), None);
+    }
+
+    #[test]
+    fn test_escalation_schedule() {
+        let mut escalation = Escalation::start(1_000);
+        assert_eq!(escalation.tick(5_999), Some(false));
+        assert_eq!(escalation.tick(6_000), Some(true));
+        assert_eq!(escalation.tick(6_000), Some(false));
+        // A late tick repeats once and schedules from then
+        assert_eq!(escalation.tick(40_000), Some(true));
+        assert_eq!(escalation.tick(49_999), Some(false));
+        assert_eq!(escalation.tick(50_000), Some(true));
+        assert_eq!(escalation.tick(601_000), None);
+    }
+
+    #[test]
+    fn test_alert_tone_pattern() {
+        let steps = alert_tone_pattern();
+        let beeps: Vec<u32> = steps.iter().filter(|s| s.freq_hz > 0).map(|s| s.freq_hz).collect();
+        assert_eq!(beeps, vec![TONE_LOW_HZ, TONE_LOW_HZ, TONE_HIGH_HZ]);
+        // Every beep is separated by a rest
+        assert_eq!(steps[1].freq_hz, 0);
+        assert_eq!(steps[3].freq_hz, 0);
+
+        let total: u32 = steps.iter().map(|s| s.duration_ms).sum();
+        assert!(total <= 1000, "tone too long: {}ms", total);
+    }
+
+    #[test]
+    fn test_tone_samples() {
+        // 1 kHz at 8 kHz: four high samples, four low, repeating
+        let samples = tone_samples(&[ToneStep { freq_hz: 1000, duration_ms: 2 }], 8000);
+        assert_eq!(samples.len(), 16);
+        assert_eq!(&samples[..8], &[TONE_AMPLITUDE, TONE_AMPLITUDE, TONE_AMPLITUDE, TONE_AMPLITUDE,
+            -TONE_AMPLITUDE, -TONE_AMPLITUDE, -TONE_AMPLITUDE, -TONE_AMPLITUDE]);
+        assert_eq!(&samples[..8], &samples[8..]);
+
+        let rest = tone_samples(&[ToneStep { freq_hz: 0, duration_ms: 10 }], 8000);
+        assert_eq!(rest.len(), 80);
+        assert!(rest.iter().all(|&s| s == 0));
+
+        let pattern = tone_samples(&alert_tone_pattern(), TONE_SAMPLE_RATE_HZ);
+        let total_ms: u32 = alert_tone_pattern().iter().map(|s| s.duration_ms).sum();
+        assert_eq!(pattern.len() as u32, total_ms * TONE_SAMPLE_RATE_HZ / 1000);
+    }
+
+    #[test]
+    fn test_unacked_expiries() {
+        let mut unacked = UnackedExpiries::default();
+
+        // Expiry on the visible screen doesn't count
+        unacked.record(EventSource::Countdown, Some(EventSource::Countdown));
+        assert_eq!(unacked.pending(None), 0);
+
+        unacked.record(EventSource::Countdown, Some(EventSource::Pomodoro));
+        unacked.record(EventSource::Countdown, None);
+        unacked.record(EventSource::Pomodoro, None);
+        assert_eq!(unacked.pending(None), 3);
+        assert_eq!(unacked.pending(Some(EventSource::Countdown)), 1);
+
+        // Visiting another screen leaves them alone
+        unacked.acknowledge(Some(EventSource::Stopwatch));
+        unacked.acknowledge(None);
+        assert_eq!(unacked.pending(None), 3);
+
+        unacked.acknowledge(Some(EventSource::Countdown));
+        assert_eq!(unacked.pending(None), 1);
+        unacked.acknowledge(Some(EventSource::Pomodoro));
+        assert_eq!(unacked.pending(None), 0);
+    }
 }
@@ -0,0 +1,80 @@
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Local seconds since midnight for a Unix time and a timezone offset.
+pub fn seconds_of_day(unix_s: u64, tz_offset_s: i64) -> u32 {
+    let local_s = unix_s as i64 + tz_offset_s;
+    local_s.rem_euclid(SECONDS_PER_DAY) as u32
+}
+
+/// Local minute of the day for a Unix time and a timezone offset.
+pub fn minute_of_day(unix_s: u64, tz_offset_s: i64) -> u16 {
+    (seconds_of_day(unix_s, tz_offset_s) / 60) as u16
+}
+
+/// Local day number (days since 1970-01-01) for a Unix time and a
+/// timezone offset; it changes at local midnight.
+pub fn day_number(unix_s: u64, tz_offset_s: i64) -> u32 {
+    (unix_s as i64 + tz_offset_s).div_euclid(SECONDS_PER_DAY).max(0) as u32
+}
+
+/// "HH:MM:SS" on a 24-hour clock. Values past the end of a day wrap.
+pub fn format_time_of_day(seconds: u32) -> String {
+    let seconds = seconds % SECONDS_PER_DAY as u32;
+    format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_midnight_and_noon() {
+        assert_eq!(format_time_of_day(seconds_of_day(0, 0)), "00:00:00");
+        // 2024-01-01 00:00:00 UTC
+        let new_year = 1_704_067_200;
+        assert_eq!(format_time_of_day(seconds_of_day(new_year, 0)), "00:00:00");
+        assert_eq!(format_time_of_day(seconds_of_day(new_year + 12 * 3600, 0)), "12:00:00");
+        assert_eq!(format_time_of_day(seconds_of_day(new_year + 12 * 3600 + 34 * 60 + 56, 0)), "12:34:56");
+    }
+
+    #[test]
+    fn test_rollover() {
+        let new_year = 1_704_067_200;
+        assert_eq!(format_time_of_day(seconds_of_day(new_year - 1, 0)), "23:59:59");
+        assert_eq!(format_time_of_day(seconds_of_day(new_year, 0)), "00:00:00");
+        assert_eq!(format_time_of_day(seconds_of_day(new_year + 1, 0)), "00:00:01");
+        // Out-of-range input wraps rather than showing hour 24
+        assert_eq!(format_time_of_day(24 * 3600), "00:00:00");
+        assert_eq!(format_time_of_day(24 * 3600 + 61), "00:01:01");
+    }
+
+    #[test]
+    fn test_timezone_offsets() {
+        // UTC+2 and UTC-8 shift across midnight both ways
+        assert_eq!(seconds_of_day(23 * 3600, 2 * 3600), 3600);
+        assert_eq!(seconds_of_day(3600, -8 * 3600), 17 * 3600);
+        // Half-hour zones
+        assert_eq!(format_time_of_day(seconds_of_day(0, 5 * 3600 + 1800)), "05:30:00");
+    }
+
+    #[test]
+    fn test_day_number() {
+        let new_year = 1_704_067_200;
+        assert_eq!(day_number(new_year, 0), 19_723);
+        assert_eq!(day_number(new_year - 1, 0), 19_722);
+        assert_eq!(day_number(new_year + 86_399, 0), 19_723);
+        // Local midnight comes earlier east of UTC
+        assert_eq!(day_number(new_year - 3600, 2 * 3600), 19_723);
+        assert_eq!(day_number(new_year + 3600, -8 * 3600), 19_722);
+        assert_eq!(day_number(0, -3600), 0);
+    }
+
+    #[test]
+    fn test_minute_of_day() {
+        assert_eq!(minute_of_day(0, 0), 0);
+        // 1970-01-02 01:30 UTC
+        assert_eq!(minute_of_day(86_400 + 5_400, 0), 90);
+        assert_eq!(minute_of_day(86_399, 0), 24 * 60 - 1);
+        assert_eq!(minute_of_day(3600, -8 * 3600), 17 * 60);
+    }
+}
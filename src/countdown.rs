@@ -1,18 +1,157 @@
-use timer_core::TimerCore;
+use timer_core::{format_auto, format_ms, TimerCore, TimerState};
 
 const MAX_COUNTDOWNS: usize = 20;
+const DEFAULT_COUNTDOWN_MS: u64 = 5 * 60 * 1000;
+/// Step for the +/- keys on a running countdown.
+pub const ADJUST_STEP_MS: u64 = 60 * 1000;
+const MAX_NAME_CHARS: usize = 20;
+const MIN_DURATION_MS: u64 = 1000;
+/// 99:59:59, the longest duration the HH:MM:SS field can express.
+const MAX_DURATION_MS: u64 = (99 * 3600 + 59 * 60 + 59) * 1000;
+const FALLBACK_NAME: &str = "Timer";
+const COPY_SUFFIX: &str = " copy";
+const MAX_CHAINS: usize = 8;
+const MAX_CHAIN_STEPS: usize = 20;
 
 #[derive(Clone)]
 pub struct CountdownEntry {
     pub name: String,
     pub duration_ms: u64,
+    /// Index of another entry to start automatically when this one expires.
+    pub on_expiry_start: Option<usize>,
+    /// Start over on expiry instead of stopping, e.g. for intervals.
+    pub repeat: bool,
+}
+
+impl CountdownEntry {
+    /// A zero duration (from damaged stored data) would expire the moment
+    /// it started, so such entries are shown as invalid and never run.
+    pub fn is_valid(&self) -> bool {
+        self.duration_ms > 0
+    }
+
+    /// Bring the entry within limits before it is stored: trim and shorten
+    /// the name, clamp the duration, and drop a follow-on link that points
+    /// past the `entry_count` entries in the list. A zero duration is left
+    /// as is so the entry stays visibly invalid rather than silently
+    /// becoming one second.
+    pub fn normalize(&mut self, entry_count: usize) {
+        let trimmed = self.name.trim();
+        self.name = if trimmed.is_empty() {
+            FALLBACK_NAME.to_string()
+        } else {
+            trimmed.chars().take(MAX_NAME_CHARS).collect()
+        };
+        if self.is_valid() {
+            self.duration_ms = self.duration_ms.clamp(MIN_DURATION_MS, MAX_DURATION_MS);
+        }
+        if self.on_expiry_start.is_some_and(|next| next >= entry_count) {
+            self.on_expiry_start = None;
+        }
+    }
+}
+
+/// Saved timers run back-to-back, e.g. exercise, rest, exercise, rest.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CountdownChain {
+    /// Entry indices in running order; an entry may appear more than once.
+    pub steps: Vec<usize>,
+    /// Step running now.
+    pub position: usize,
+}
+
+impl CountdownChain {
+    pub fn new(steps: Vec<usize>) -> Self {
+        Self { steps, position: 0 }
+    }
+
+    /// Entry of the step running now.
+    pub fn current(&self) -> Option<usize> {
+        self.steps.get(self.position).copied()
+    }
+
+    /// Rewrite entry indices after the list changed; `map` returns None
+    /// for an entry that is gone, dropping its steps.
+    fn remap(&mut self, map: impl Fn(usize) -> Option<usize>) {
+        self.steps = self.steps.iter().filter_map(|&idx| map(idx)).collect();
+    }
+}
+
+/// Parse a chain typed as list numbers, e.g. "1 2 1 2" or "1,2,3", into
+/// entry indices. None if empty, too long, or a number isn't in the list.
+pub fn parse_chain(text: &str, entry_count: usize) -> Option<Vec<usize>> {
+    let mut steps = Vec::new();
+    for part in text.split(|c: char| c == ',' || c.is_whitespace()).filter(|p| !p.is_empty()) {
+        match part.parse::<usize>() {
+            Ok(n) if n >= 1 && n <= entry_count => steps.push(n - 1),
+            _ => return None,
+        }
+    }
+    if steps.is_empty() || steps.len() > MAX_CHAIN_STEPS {
+        return None;
+    }
+    Some(steps)
+}
+
+/// Chain steps as the list numbers `parse_chain` reads, e.g. "1 2 1 2".
+pub fn format_chain(steps: &[usize]) -> String {
+    steps.iter().map(|idx| (idx + 1).to_string()).collect::<Vec<_>>().join(" ")
+}
+
+/// Rows `start..end` of a `total`-row list to show in `max_visible` rows,
+/// keeping `cursor` on screen and near the middle once the list scrolls.
+pub fn visible_window(cursor: usize, total: usize, max_visible: usize) -> (usize, usize) {
+    if total <= max_visible {
+        return (0, total);
+    }
+    let start = cursor.saturating_sub(max_visible / 2).min(total - max_visible);
+    (start, start + max_visible)
+}
+
+/// Persisted countdown preferences.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CountdownSettings {
+    pub default_ms: u64,
+    pub show_elapsed: bool,
+}
+
+/// The timer of an entry that has been started.
+pub struct ArmedCountdown {
+    pub timer: TimerCore,
+    /// Times a repeating entry has run out and started over.
+    pub loops: u32,
 }
 
 pub struct CountdownState {
     pub entries: Vec<CountdownEntry>,
     pub cursor: usize,
-    pub active_timer: Option<TimerCore>,
+    /// Per-entry timers, parallel to `entries`; None for idle entries.
+    pub armed: Vec<Option<ArmedCountdown>>,
+    /// Entry shown on the run screen.
     pub active_index: Option<usize>,
+    /// Duration prefilled when creating a new countdown.
+    pub default_countdown_ms: u64,
+    /// Show the running countdown as a shrinking bar without digits.
+    pub bar_only: bool,
+    /// Show time elapsed under the remaining time while running.
+    pub show_elapsed: bool,
+    /// Saved chains, most recently used last.
+    pub chains: Vec<CountdownChain>,
+    /// The chain running now, if any.
+    pub chain: Option<CountdownChain>,
+}
+
+/// What happens when a countdown runs out.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ExpiryAction {
+    /// Run the same timer again.
+    Repeat,
+    /// Hand over to the linked entry.
+    StartNext(usize),
+    /// Move the running chain on to its next step, this entry.
+    NextStep(usize),
+    /// Stop and go back to the list.
+    Stop,
 }
 
 impl CountdownState {
@@ -20,24 +159,75 @@ impl CountdownState {
         Self {
             entries: Vec::new(),
             cursor: 0,
-            active_timer: None,
+            armed: Vec::new(),
             active_index: None,
+            default_countdown_ms: DEFAULT_COUNTDOWN_MS,
+            bar_only: false,
+            show_elapsed: true,
+            chains: Vec::new(),
+            chain: None,
+        }
+    }
+
+    pub fn settings(&self) -> CountdownSettings {
+        CountdownSettings {
+            default_ms: self.default_countdown_ms,
+            show_elapsed: self.show_elapsed,
         }
     }
 
+    /// Change the new-countdown prefill. Zero is rejected.
+    pub fn set_default_countdown(&mut self, duration_ms: u64) -> bool {
+        if duration_ms == 0 {
+            return false;
+        }
+        self.default_countdown_ms = duration_ms;
+        true
+    }
+
     pub fn add_entry(&mut self, name: String, duration_ms: u64) -> bool {
         if self.entries.len() >= MAX_COUNTDOWNS {
             return false;
         }
-        self.entries.push(CountdownEntry { name, duration_ms });
+        let mut entry = CountdownEntry { name, duration_ms, on_expiry_start: None, repeat: false };
+        entry.normalize(self.entries.len() + 1);
+        self.entries.push(entry);
+        true
+    }
+
+    /// Insert a copy of the entry under the cursor right after it, named
+    /// "<name> copy" (the name is shortened so the suffix still fits) and
+    /// moves the cursor to it. Returns false if there is no selection or
+    /// the list is full.
+    pub fn duplicate_selected(&mut self) -> bool {
+        if !self.has_selection() || self.entries.len() >= MAX_COUNTDOWNS {
+            return false;
+        }
+        let at = self.cursor + 1;
+        self.fit_armed();
+        self.armed.insert(at, None);
+        // Everything after the original moves down one
+        let shift = |idx: usize| if idx >= at { idx + 1 } else { idx };
+        self.active_index = self.active_index.map(shift);
+        for entry in self.entries.iter_mut() {
+            entry.on_expiry_start = entry.on_expiry_start.map(shift);
+        }
+        self.remap_chains(|idx| Some(shift(idx)));
+
+        let mut copy = self.entries[self.cursor].clone();
+        let keep = MAX_NAME_CHARS - COPY_SUFFIX.chars().count();
+        copy.name = copy.name.chars().take(keep).collect::<String>() + COPY_SUFFIX;
+        self.entries.insert(at, copy);
+        self.cursor = at;
         true
     }
 
     pub fn delete_selected(&mut self) {
         if self.cursor < self.entries.len() {
-            // If the active timer is the one being deleted, stop it
+            self.fit_armed();
+            self.armed.remove(self.cursor);
+            // If the shown timer is the one being deleted, stop showing it
             if self.active_index == Some(self.cursor) {
-                self.active_timer = None;
                 self.active_index = None;
             } else if let Some(idx) = self.active_index {
                 // Adjust active index if needed
@@ -45,48 +235,1068 @@ impl CountdownState {
                     self.active_index = Some(idx - 1);
                 }
             }
-            self.entries.remove(self.cursor);
+            let removed = self.cursor;
+            self.entries.remove(removed);
+            // Drop links to the removed entry and shift links past it
+            for entry in self.entries.iter_mut() {
+                entry.on_expiry_start = match entry.on_expiry_start {
+                    Some(next) if next == removed => None,
+                    Some(next) if next > removed => Some(next - 1),
+                    other => other,
+                };
+            }
+            let remove = |idx: usize| match idx {
+                idx if idx == removed => None,
+                idx if idx > removed => Some(idx - 1),
+                idx => Some(idx),
+            };
+            // A running chain can't skip a step it was counting on
+            if self.chain.as_ref().is_some_and(|c| c.steps.contains(&removed)) {
+                self.chain = None;
+            }
+            self.remap_chains(remove);
             if self.cursor >= self.entries.len() && self.cursor > 0 {
                 self.cursor = self.entries.len() - 1;
             }
         }
     }
 
-    pub fn start_selected(&mut self) {
-        if self.cursor < self.entries.len() {
-            let duration = self.entries[self.cursor].duration_ms;
-            self.active_timer = Some(TimerCore::new_countdown(duration));
-            self.active_index = Some(self.cursor);
+    /// Move the entry under the cursor one slot up, taking the cursor
+    /// with it. Returns false at the top.
+    pub fn move_up(&mut self) -> bool {
+        if self.cursor == 0 || self.cursor >= self.entries.len() {
+            return false;
+        }
+        self.swap_entries(self.cursor, self.cursor - 1);
+        self.cursor -= 1;
+        true
+    }
+
+    /// Move the entry under the cursor one slot down, taking the cursor
+    /// with it. Returns false at the bottom.
+    pub fn move_down(&mut self) -> bool {
+        if self.cursor + 1 >= self.entries.len() {
+            return false;
+        }
+        self.swap_entries(self.cursor, self.cursor + 1);
+        self.cursor += 1;
+        true
+    }
+
+    /// Swap two entries, keeping timers and links pointing at the same
+    /// entries.
+    fn swap_entries(&mut self, a: usize, b: usize) {
+        self.entries.swap(a, b);
+        self.fit_armed();
+        self.armed.swap(a, b);
+        let remap = |idx: usize| if idx == a { b } else if idx == b { a } else { idx };
+        self.active_index = self.active_index.map(remap);
+        for entry in self.entries.iter_mut() {
+            entry.on_expiry_start = entry.on_expiry_start.map(remap);
+        }
+        self.remap_chains(|idx| Some(remap(idx)));
+    }
+
+    /// Rewrite chain steps after the list changed, dropping saved chains
+    /// left with no steps.
+    fn remap_chains(&mut self, map: impl Fn(usize) -> Option<usize>) {
+        for chain in self.chains.iter_mut().chain(self.chain.as_mut()) {
+            chain.remap(&map);
+        }
+        self.chains.retain(|c| !c.steps.is_empty());
+        if self.chain.as_ref().is_some_and(|c| c.current().is_none()) {
+            self.chain = None;
+        }
+    }
+
+    /// Save a chain as the most recent one, returning its index. Saving
+    /// one that's already there moves it to the end; the oldest is
+    /// dropped once there are `MAX_CHAINS`.
+    pub fn save_chain(&mut self, steps: Vec<usize>) -> Option<usize> {
+        if steps.is_empty() || steps.len() > MAX_CHAIN_STEPS || steps.iter().any(|&idx| idx >= self.entries.len()) {
+            return None;
+        }
+        self.chains.retain(|c| c.steps != steps);
+        if self.chains.len() >= MAX_CHAINS {
+            self.chains.remove(0);
+        }
+        self.chains.push(CountdownChain::new(steps));
+        Some(self.chains.len() - 1)
+    }
+
+    /// Arm (stopped) and show the first step of saved chain `idx`, making
+    /// it the running chain. Invalid steps are skipped.
+    pub fn start_chain(&mut self, idx: usize) -> bool {
+        let mut chain = match self.chains.get(idx) {
+            Some(chain) => chain.clone(),
+            None => return false,
+        };
+        chain.position = match self.next_valid_step(&chain, 0) {
+            Some(position) => position,
+            None => return false,
+        };
+        let first = chain.steps[chain.position];
+        self.arm(first);
+        self.active_index = Some(first);
+        self.chain = Some(chain);
+        true
+    }
+
+    /// First step at or after `from` whose entry can run.
+    fn next_valid_step(&self, chain: &CountdownChain, from: usize) -> Option<usize> {
+        (from..chain.steps.len()).find(|&pos| self.entries.get(chain.steps[pos]).is_some_and(|e| e.is_valid()))
+    }
+
+    /// Entry the running chain moves to once `idx` expires, if `idx` is
+    /// its current step and another step follows.
+    fn chain_next(&self, idx: usize) -> Option<(usize, usize)> {
+        let chain = self.chain.as_ref().filter(|c| c.current() == Some(idx))?;
+        let position = self.next_valid_step(chain, chain.position + 1)?;
+        Some((position, chain.steps[position]))
+    }
+
+    /// Is `idx` the running chain's current step?
+    pub fn in_chain(&self, idx: usize) -> bool {
+        self.chain.as_ref().is_some_and(|c| c.current() == Some(idx))
+    }
+
+    /// Step number (from 1) and step count of the chain, if the shown
+    /// timer is its current step.
+    pub fn active_chain_step(&self) -> Option<(usize, usize)> {
+        let chain = self.chain.as_ref()?;
+        if chain.current() != self.active_index {
+            return None;
+        }
+        Some((chain.position + 1, chain.steps.len()))
+    }
+
+    /// Move the running chain from expired entry `idx` to its next step,
+    /// armed (stopped). Returns the entry now running the chain.
+    pub fn advance_chain(&mut self, idx: usize) -> Option<usize> {
+        let (position, next) = self.chain_next(idx)?;
+        if let Some(chain) = self.chain.as_mut() {
+            chain.position = position;
+        }
+        self.hand_over(idx, next);
+        Some(next)
+    }
+
+    /// Is the cursor on an existing entry?
+    pub fn has_selection(&self) -> bool {
+        self.cursor < self.entries.len()
+    }
+
+    /// Is the cursor on an entry that can't be started?
+    pub fn selection_invalid(&self) -> bool {
+        self.entries.get(self.cursor).is_some_and(|e| !e.is_valid())
+    }
+
+    /// Keep `armed` the same length as `entries`, which callers may
+    /// replace wholesale (e.g. on load).
+    fn fit_armed(&mut self) {
+        self.armed.resize_with(self.entries.len(), || None);
+    }
+
+    /// Arm a fresh (stopped) timer for the entry under the cursor and show
+    /// it. Other entries' timers keep going. Returns false, changing
+    /// nothing, if there is no selection or the selected entry is invalid.
+    pub fn start_selected(&mut self) -> bool {
+        if !self.arm(self.cursor) {
+            return false;
+        }
+        self.active_index = Some(self.cursor);
+        true
+    }
+
+    /// Arm a fresh (stopped) timer for entry `idx`, replacing any it had.
+    pub fn arm(&mut self, idx: usize) -> bool {
+        let timer = match self.entries.get(idx).and_then(|e| TimerCore::try_new_countdown(e.duration_ms)) {
+            Some(timer) => timer,
+            None => return false,
+        };
+        self.fit_armed();
+        self.armed[idx] = Some(ArmedCountdown { timer, loops: 0 });
+        true
+    }
+
+    /// Drop entry `idx`'s timer. The run screen stops showing it, and a
+    /// chain on that step ends.
+    pub fn disarm(&mut self, idx: usize) {
+        if self.in_chain(idx) {
+            self.chain = None;
+        }
+        if let Some(slot) = self.armed.get_mut(idx) {
+            *slot = None;
+        }
+        if self.active_index == Some(idx) {
+            self.active_index = None;
+        }
+    }
+
+    pub fn armed(&self, idx: usize) -> Option<&ArmedCountdown> {
+        self.armed.get(idx).and_then(|a| a.as_ref())
+    }
+
+    pub fn timer(&self, idx: usize) -> Option<&TimerCore> {
+        self.armed(idx).map(|a| &a.timer)
+    }
+
+    pub fn timer_mut(&mut self, idx: usize) -> Option<&mut TimerCore> {
+        self.armed.get_mut(idx).and_then(|a| a.as_mut()).map(|a| &mut a.timer)
+    }
+
+    /// Timer shown on the run screen.
+    pub fn active_timer(&self) -> Option<&TimerCore> {
+        self.active_index.and_then(|idx| self.timer(idx))
+    }
+
+    pub fn active_timer_mut(&mut self) -> Option<&mut TimerCore> {
+        let idx = self.active_index?;
+        self.timer_mut(idx)
+    }
+
+    /// Loops completed by the shown timer.
+    pub fn active_loops(&self) -> u32 {
+        self.active_index.and_then(|idx| self.armed(idx)).map_or(0, |a| a.loops)
+    }
+
+    /// Tick every running timer, returning the entries that just expired.
+    pub fn tick_all(&mut self, now_ms: u64) -> Vec<usize> {
+        let mut expired = Vec::new();
+        for (idx, armed) in self.armed.iter_mut().enumerate() {
+            if let Some(armed) = armed {
+                if armed.timer.state.is_active()
+                    && armed.timer.tick(now_ms).is_finished()
+                {
+                    expired.push(idx);
+                }
+            }
+        }
+        expired
+    }
+
+    /// Pause every running timer.
+    pub fn pause_all(&mut self, now_ms: u64) {
+        for armed in self.armed.iter_mut().flatten() {
+            if armed.timer.state.is_active() {
+                armed.timer.pause(now_ms);
+            }
+        }
+    }
+
+    /// Flip the repeat flag on the entry under the cursor.
+    pub fn toggle_repeat_selected(&mut self) -> bool {
+        match self.entries.get_mut(self.cursor) {
+            Some(entry) => {
+                entry.repeat = !entry.repeat;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Decide what entry `idx` does when its timer runs out. A running
+    /// chain decides for its own step; otherwise repeating wins over a
+    /// link, which would otherwise never be reached.
+    pub fn expiry_action(&self, idx: usize) -> ExpiryAction {
+        if self.in_chain(idx) {
+            return match self.chain_next(idx) {
+                Some((_, next)) => ExpiryAction::NextStep(next),
+                None => ExpiryAction::Stop,
+            };
+        }
+        let repeat = self.entries.get(idx).is_some_and(|e| e.repeat && e.is_valid());
+        if repeat {
+            return ExpiryAction::Repeat;
+        }
+        match self.follow_on_index(idx) {
+            Some(next) => ExpiryAction::StartNext(next),
+            None => ExpiryAction::Stop,
+        }
+    }
+
+    /// Re-arm a repeating timer (stopped) and count the loop.
+    pub fn repeat(&mut self, idx: usize) -> bool {
+        let loops = match self.armed(idx) {
+            Some(armed) => armed.loops,
+            None => return false,
+        };
+        if !self.arm(idx) {
+            return false;
+        }
+        if let Some(Some(armed)) = self.armed.get_mut(idx) {
+            armed.loops = loops.saturating_add(1);
+        }
+        true
+    }
+
+    /// Snooze entry `idx` after it ran out: arm it again for `snooze_ms`
+    /// rather than its own duration, start it at `now_ms` and show it.
+    pub fn snooze(&mut self, idx: usize, now_ms: u64, snooze_ms: u64) -> bool {
+        if snooze_ms == 0 || idx >= self.entries.len() {
+            return false;
+        }
+        self.fit_armed();
+        let mut timer = TimerCore::new_countdown(snooze_ms);
+        timer.start(now_ms);
+        self.armed[idx] = Some(ArmedCountdown { timer, loops: 0 });
+        self.active_index = Some(idx);
+        true
+    }
+
+    /// Hand an expired timer over to its linked entry: `from` is dropped
+    /// and `to` armed (stopped), and the run screen follows if it was
+    /// showing `from`.
+    pub fn hand_over(&mut self, from: usize, to: usize) -> bool {
+        if !self.arm(to) {
+            return false;
+        }
+        if from == to {
+            return true;
+        }
+        let shown = self.active_index == Some(from);
+        self.disarm(from);
+        if shown {
+            self.active_index = Some(to);
+        }
+        true
+    }
+
+    /// Re-arm the shown timer at its entry's full duration (stopped).
+    /// Returns false if there is no shown timer or its entry is gone.
+    pub fn reset_active(&mut self) -> bool {
+        match self.active_index {
+            Some(idx) if self.armed(idx).is_some() => self.arm(idx),
+            _ => false,
+        }
+    }
+
+    /// Pause the shown timer if running or resume it if paused.
+    /// Returns Some(true) if it is now running, Some(false) if now paused,
+    /// and None if there was nothing to toggle.
+    pub fn toggle_active(&mut self, now_ms: u64) -> Option<bool> {
+        let timer = self.active_timer_mut()?;
+        match timer.state {
+            TimerState::Running => {
+                timer.pause(now_ms);
+                Some(false)
+            }
+            TimerState::Paused => {
+                timer.resume(now_ms);
+                Some(true)
+            }
+            _ => None,
         }
     }
 
+    /// Countdowns currently running.
+    pub fn running_count(&self) -> usize {
+        self.armed
+            .iter()
+            .flatten()
+            .filter(|a| a.timer.state.is_active())
+            .count()
+    }
+
+    /// Time left on whichever running countdown finishes first.
+    pub fn soonest_remaining_ms(&self, now_ms: u64) -> Option<u64> {
+        self.armed
+            .iter()
+            .flatten()
+            .filter(|a| a.timer.state.is_active())
+            .filter_map(|a| a.timer.remaining_ms(now_ms))
+            .min()
+    }
+
     pub fn active_name(&self) -> Option<&str> {
         self.active_index
             .and_then(|idx| self.entries.get(idx))
             .map(|e| e.name.as_str())
     }
 
-    pub fn active_duration_ms(&self) -> Option<u64> {
-        self.active_index
-            .and_then(|idx| self.entries.get(idx))
-            .map(|e| e.duration_ms)
+    /// Shown timer progress in thousandths, for the progress bar.
+    pub fn progress_permille(&self, now_ms: u64) -> u16 {
+        self.active_timer()
+            .map(|t| t.elapsed_permille(now_ms))
+            .unwrap_or(0)
+    }
+
+    pub fn set_on_expiry_start(&mut self, from: usize, to: Option<usize>) -> bool {
+        if from >= self.entries.len() {
+            return false;
+        }
+        if let Some(next) = to {
+            if next >= self.entries.len() || self.creates_cycle(from, next) {
+                return false;
+            }
+        }
+        self.entries[from].on_expiry_start = to;
+        true
+    }
+
+    /// Would linking `from` -> `to` lead back to `from`?
+    pub fn creates_cycle(&self, from: usize, to: usize) -> bool {
+        let mut current = Some(to);
+        // A chain can visit each entry at most once before it must repeat
+        for _ in 0..=self.entries.len() {
+            match current {
+                Some(idx) if idx == from => return true,
+                Some(idx) => current = self.entries.get(idx).and_then(|e| e.on_expiry_start),
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Normalize every entry and its links, e.g. after loading stored data.
+    pub fn normalize_entries(&mut self) {
+        let count = self.entries.len();
+        for entry in self.entries.iter_mut() {
+            entry.normalize(count);
+        }
+        self.sanitize_links();
+        self.fit_armed();
+        self.remap_chains(|idx| Some(idx).filter(|&idx| idx < count));
     }
 
-    pub fn progress_fraction(&self, now_ms: u64) -> f32 {
-        if let (Some(timer), Some(duration)) = (&self.active_timer, self.active_duration_ms()) {
-            if duration == 0 {
-                return 1.0;
+    /// Clear any links that point nowhere or form loops, e.g. after loading
+    /// stored data.
+    pub fn sanitize_links(&mut self) {
+        for i in 0..self.entries.len() {
+            if let Some(next) = self.entries[i].on_expiry_start {
+                self.entries[i].on_expiry_start = None;
+                if next < self.entries.len() && !self.creates_cycle(i, next) {
+                    self.entries[i].on_expiry_start = Some(next);
+                }
             }
-            let elapsed = timer.elapsed_ms(now_ms);
-            let frac = elapsed as f32 / duration as f32;
-            if frac > 1.0 { 1.0 } else { frac }
+        }
+    }
+
+    /// Entry that should start now that entry `idx`'s timer has expired.
+    /// Invalid entries are skipped, ending the chain.
+    pub fn follow_on_index(&self, idx: usize) -> Option<usize> {
+        self.entries
+            .get(idx)
+            .and_then(|e| e.on_expiry_start)
+            .filter(|&next| self.entries.get(next).is_some_and(|e| e.is_valid()))
+    }
+
+    /// Countdown list row for entry `idx`, e.g. "> Tea            03:00 [1:12]":
+    /// cursor marker, name, duration, link and loop marks, and what's left
+    /// on a started timer (with its state unless it's running).
+    pub fn list_line(&self, idx: usize, now_ms: u64) -> Option<String> {
+        let entry = self.entries.get(idx)?;
+        let marker = if idx == self.cursor { "> " } else { "  " };
+        let duration_str = if entry.is_valid() {
+            format_ms(entry.duration_ms)
         } else {
-            0.0
+            "invalid".to_string()
+        };
+        let mut line = format!("{}{:<14} {}", marker, entry.name, duration_str);
+        if let Some(next) = entry.on_expiry_start {
+            line.push_str(&format!(" ->{}", next + 1));
+        }
+        if entry.repeat {
+            line.push_str(" (loop)");
+        }
+        // Started timers show what's left, running or not
+        if let Some(timer) = self.timer(idx) {
+            let left = format_auto(timer.remaining_ms(now_ms).unwrap_or(0));
+            match timer.state {
+                TimerState::Running => line.push_str(&format!(" [{}]", left)),
+                _ => line.push_str(&format!(" [{} {}]", left, timer.state.label().to_lowercase())),
+            }
+        }
+        Some(line)
+    }
+
+    /// One-line plain-text status, e.g. "Tea, 2:10 remaining, running."
+    pub fn status_line(&self, now_ms: u64) -> String {
+        let (name, timer) = match (self.active_name(), self.active_timer()) {
+            (Some(name), Some(timer)) => (name, timer),
+            _ => return "No countdown active.".to_string(),
+        };
+        match timer.overtime_ms(now_ms) {
+            Some(over) if timer.state.is_finished() => {
+                format!("{}, expired {} ago.", name, format_auto(over))
+            }
+            _ => format!(
+                "{}, {} remaining, {}.",
+                name,
+                format_auto(timer.remaining_ms(now_ms).unwrap_or(0)),
+                timer.state.label().to_lowercase()
+            ),
         }
     }
 
+    /// Drop the shown timer.
     pub fn stop_active(&mut self) {
-        self.active_timer = None;
-        self.active_index = None;
+        if let Some(idx) = self.active_index {
+            self.disarm(idx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with(n: usize) -> CountdownState {
+        let mut state = CountdownState::new();
+        for i in 0..n {
+            state.add_entry(format!("T{}", i), 1000 * (i as u64 + 1));
+        }
+        state
+    }
+
+    fn entry(name: &str, duration_ms: u64, on_expiry_start: Option<usize>) -> CountdownEntry {
+        CountdownEntry { name: name.to_string(), duration_ms, on_expiry_start, repeat: false }
+    }
+
+    fn names(state: &CountdownState) -> Vec<&str> {
+        state.entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_duplicate_last_entry() {
+        let mut state = CountdownState::new();
+        state.entries = vec![entry("Coffee", 240_000, None), entry("Tea", 180_000, Some(0))];
+        state.cursor = 1;
+        state.entries[1].repeat = true;
+        assert!(state.duplicate_selected());
+        assert_eq!(names(&state), vec!["Coffee", "Tea", "Tea copy"]);
+        assert_eq!(state.cursor, 2);
+        let copy = &state.entries[2];
+        assert_eq!(copy.duration_ms, 180_000);
+        assert_eq!(copy.on_expiry_start, Some(0));
+        assert!(copy.repeat);
+    }
+
+    #[test]
+    fn test_duplicate_shifts_active_and_links() {
+        let mut state = CountdownState::new();
+        state.entries = vec![entry("A", 1000, Some(2)), entry("B", 2000, None), entry("C", 3000, Some(1))];
+        state.cursor = 2;
+        state.start_selected();
+        state.cursor = 0;
+        assert!(state.duplicate_selected());
+        assert_eq!(names(&state), vec!["A", "A copy", "B", "C"]);
+        assert_eq!(state.active_name(), Some("C"));
+        assert_eq!(state.entries[0].on_expiry_start, Some(3));
+        assert_eq!(state.entries[1].on_expiry_start, Some(3));
+        assert_eq!(state.entries[3].on_expiry_start, Some(2));
+    }
+
+    #[test]
+    fn test_duplicate_respects_cap() {
+        let mut state = state_with(MAX_COUNTDOWNS);
+        assert!(!state.duplicate_selected());
+        assert_eq!(state.entries.len(), MAX_COUNTDOWNS);
+
+        let mut state = state_with(MAX_COUNTDOWNS - 1);
+        assert!(state.duplicate_selected());
+        assert!(!state.duplicate_selected());
+
+        assert!(!CountdownState::new().duplicate_selected());
+    }
+
+    #[test]
+    fn test_duplicate_long_name() {
+        let mut state = CountdownState::new();
+        state.add_entry("Extra strong tea pot".to_string(), 240_000);
+        assert_eq!(state.entries[0].name.chars().count(), MAX_NAME_CHARS);
+        assert!(state.duplicate_selected());
+        assert_eq!(state.entries[1].name, "Extra strong te copy");
+        assert_eq!(state.entries[1].name.chars().count(), MAX_NAME_CHARS);
+
+        // Copying the copy doesn't grow it either
+        assert!(state.duplicate_selected());
+        assert_eq!(state.entries[2].name, "Extra strong te copy");
+
+        // Short multi-byte names are left alone
+        state.add_entry("Thé".to_string(), 60_000);
+        state.cursor = 3;
+        assert!(state.duplicate_selected());
+        assert_eq!(state.entries[4].name, "Thé copy");
+    }
+
+    #[test]
+    fn test_move_middle_entry() {
+        let mut state = CountdownState::new();
+        state.entries = vec![entry("A", 1000, None), entry("B", 2000, None), entry("C", 3000, None)];
+        state.cursor = 1;
+        assert!(state.move_up());
+        assert_eq!(names(&state), vec!["B", "A", "C"]);
+        assert_eq!(state.cursor, 0);
+
+        assert!(state.move_down());
+        assert!(state.move_down());
+        assert_eq!(names(&state), vec!["A", "C", "B"]);
+        assert_eq!(state.cursor, 2);
+    }
+
+    #[test]
+    fn test_move_at_ends_is_noop() {
+        let mut state = CountdownState::new();
+        state.entries = vec![entry("A", 1000, None), entry("B", 2000, None)];
+        assert!(!state.move_up());
+        state.cursor = 1;
+        assert!(!state.move_down());
+        assert_eq!(names(&state), vec!["A", "B"]);
+        assert_eq!(state.cursor, 1);
+
+        let mut empty = CountdownState::new();
+        assert!(!empty.move_up());
+        assert!(!empty.move_down());
+    }
+
+    #[test]
+    fn test_move_keeps_active_and_links() {
+        let mut state = CountdownState::new();
+        state.entries = vec![entry("A", 1000, Some(2)), entry("B", 2000, None), entry("C", 3000, Some(1))];
+        state.cursor = 1;
+        state.start_selected();
+
+        // Move the active entry B down past C
+        assert!(state.move_down());
+        assert_eq!(names(&state), vec!["A", "C", "B"]);
+        assert_eq!(state.active_name(), Some("B"));
+        assert_eq!(state.active_index, Some(2));
+        // A still links to C, and C to B
+        assert_eq!(state.entries[0].on_expiry_start, Some(1));
+        assert_eq!(state.entries[1].on_expiry_start, Some(2));
+
+        // Moving another entry past the active one shifts it too
+        state.cursor = 0;
+        assert!(state.move_down());
+        assert_eq!(names(&state), vec!["C", "A", "B"]);
+        assert_eq!(state.active_name(), Some("B"));
+    }
+
+    #[test]
+    fn test_expiry_action() {
+        let mut state = CountdownState::new();
+        state.entries = vec![entry("Sprint", 30_000, Some(1)), entry("Rest", 10_000, None)];
+        assert_eq!(state.expiry_action(1), ExpiryAction::Stop);
+        assert_eq!(state.expiry_action(0), ExpiryAction::StartNext(1));
+
+        // Repeat takes over from the link
+        assert!(state.toggle_repeat_selected());
+        assert_eq!(state.expiry_action(0), ExpiryAction::Repeat);
+        state.toggle_repeat_selected();
+        assert_eq!(state.expiry_action(0), ExpiryAction::StartNext(1));
+    }
+
+    #[test]
+    fn test_expiry_action_out_of_range() {
+        let mut state = CountdownState::new();
+        state.entries = vec![entry("Sprint", 30_000, None)];
+        state.entries[0].repeat = true;
+        assert_eq!(state.expiry_action(1), ExpiryAction::Stop);
+    }
+
+    #[test]
+    fn test_repeat_counts_loops() {
+        let mut state = CountdownState::new();
+        state.entries = vec![entry("Sprint", 30_000, None)];
+        state.entries[0].repeat = true;
+        state.start_selected();
+        state.active_timer_mut().unwrap().start(0);
+        assert_eq!(state.active_timer_mut().unwrap().tick(30_000), TimerState::Expired);
+
+        assert!(state.repeat(0));
+        assert!(state.repeat(0));
+        assert_eq!(state.active_loops(), 2);
+        let timer = state.active_timer().unwrap();
+        assert_eq!(timer.state, TimerState::Stopped);
+        assert_eq!(timer.remaining_ms(0), Some(30_000));
+
+        // Starting it by hand begins a new count
+        state.start_selected();
+        assert_eq!(state.active_loops(), 0);
+    }
+
+    #[test]
+    fn test_snooze_rearms_for_snooze_length() {
+        let mut state = CountdownState::new();
+        state.entries = vec![entry("Tea", 180_000, None), entry("Eggs", 420_000, None)];
+        state.start_selected();
+        state.active_timer_mut().unwrap().start(0);
+        assert_eq!(state.active_timer_mut().unwrap().tick(180_000), TimerState::Expired);
+        state.disarm(0);
+
+        assert!(state.snooze(0, 200_000, 60_000));
+        assert_eq!(state.active_index, Some(0));
+        let timer = state.active_timer().unwrap();
+        assert_eq!(timer.state, TimerState::Running);
+        assert_eq!(timer.remaining_ms(230_000), Some(30_000));
+        assert_eq!(state.active_timer_mut().unwrap().tick(260_000), TimerState::Expired);
+
+        // The entry itself keeps its duration
+        assert_eq!(state.entries[0].duration_ms, 180_000);
+        assert!(!state.snooze(0, 0, 0));
+        assert!(!state.snooze(2, 0, 60_000));
+    }
+
+    #[test]
+    fn test_list_line_shows_time_left() {
+        let mut state = CountdownState::new();
+        state.entries = vec![entry("Tea", 180_000, None), entry("Eggs", 420_000, Some(0))];
+        state.entries[1].repeat = true;
+        assert_eq!(state.list_line(0, 0).unwrap(), "> Tea            03:00");
+        assert_eq!(state.list_line(1, 0).unwrap(), "  Eggs           07:00 ->1 (loop)");
+        assert!(state.list_line(2, 0).is_none());
+
+        state.start_selected();
+        state.active_timer_mut().unwrap().start(0);
+        assert_eq!(state.list_line(0, 108_000).unwrap(), "> Tea            03:00 [1:12]");
+        state.active_timer_mut().unwrap().pause(108_000);
+        assert_eq!(state.list_line(0, 200_000).unwrap(), "> Tea            03:00 [1:12 paused]");
+    }
+
+    #[test]
+    fn test_normalize_name() {
+        let mut e = entry("  Tea  ", 60_000, None);
+        e.normalize(1);
+        assert_eq!(e.name, "Tea");
+
+        let mut e = entry("A very long countdown timer name", 60_000, None);
+        e.normalize(1);
+        assert_eq!(e.name, "A very long countdow");
+
+        // Counted in characters, so multi-byte names don't split a char
+        let mut e = entry(&"é".repeat(30), 60_000, None);
+        e.normalize(1);
+        assert_eq!(e.name.chars().count(), 20);
+
+        let mut e = entry("   ", 60_000, None);
+        e.normalize(1);
+        assert_eq!(e.name, "Timer");
+    }
+
+    #[test]
+    fn test_normalize_duration() {
+        let mut e = entry("T", 0, None);
+        e.normalize(1);
+        assert_eq!(e.duration_ms, 0);
+        assert!(!e.is_valid());
+
+        let mut e = entry("T", 300, None);
+        e.normalize(1);
+        assert_eq!(e.duration_ms, MIN_DURATION_MS);
+
+        let mut e = entry("T", 500 * 3600 * 1000, None);
+        e.normalize(1);
+        assert_eq!(e.duration_ms, MAX_DURATION_MS);
+
+        let mut e = entry("T", 90_000, None);
+        e.normalize(1);
+        assert_eq!(e.duration_ms, 90_000);
+    }
+
+    #[test]
+    fn test_normalize_link() {
+        let mut e = entry("T", 60_000, Some(3));
+        e.normalize(3);
+        assert_eq!(e.on_expiry_start, None);
+
+        let mut e = entry("T", 60_000, Some(2));
+        e.normalize(3);
+        assert_eq!(e.on_expiry_start, Some(2));
+    }
+
+    #[test]
+    fn test_add_and_load_paths_normalize() {
+        let mut state = CountdownState::new();
+        assert!(state.add_entry(" Pasta water ".to_string(), 10));
+        assert_eq!(state.entries[0].name, "Pasta water");
+        assert_eq!(state.entries[0].duration_ms, MIN_DURATION_MS);
+
+        state.entries.push(entry("", u64::MAX, Some(9)));
+        state.normalize_entries();
+        assert_eq!(state.entries[1].name, "Timer");
+        assert_eq!(state.entries[1].duration_ms, MAX_DURATION_MS);
+        assert_eq!(state.entries[1].on_expiry_start, None);
+    }
+
+    #[test]
+    fn test_status_line() {
+        let mut state = CountdownState::new();
+        assert_eq!(state.status_line(0), "No countdown active.");
+
+        state.add_entry("Tea".to_string(), 3 * 60_000);
+        state.start_selected();
+        assert_eq!(state.status_line(0), "Tea, 3:00 remaining, stopped.");
+
+        let timer = state.active_timer_mut().unwrap();
+        timer.start(0);
+        assert_eq!(state.status_line(50_000), "Tea, 2:10 remaining, running.");
+
+        state.active_timer_mut().unwrap().tick(180_000);
+        assert_eq!(state.status_line(210_000), "Tea, expired 0:30 ago.");
+    }
+
+    #[test]
+    fn test_default_countdown() {
+        let mut state = CountdownState::new();
+        assert_eq!(state.default_countdown_ms, 5 * 60 * 1000);
+        assert!(state.set_default_countdown(3 * 60 * 1000));
+        assert_eq!(state.default_countdown_ms, 3 * 60 * 1000);
+        assert!(!state.set_default_countdown(0));
+        assert_eq!(state.default_countdown_ms, 3 * 60 * 1000);
+    }
+
+    #[test]
+    fn test_elapsed_holds_while_paused() {
+        let mut state = state_with(1);
+        state.entries[0].duration_ms = 60_000;
+        assert!(state.start_selected());
+        state.active_timer_mut().unwrap().start(0);
+        assert_eq!(state.toggle_active(12_000), Some(false));
+        let frame = state.active_timer().unwrap().frame(40_000);
+        assert_eq!(frame.elapsed_ms, 12_000);
+        assert_eq!(frame.remaining_ms, Some(48_000));
+    }
+
+    #[test]
+    fn test_empty_selection_is_noop() {
+        let mut state = CountdownState::new();
+        assert!(!state.has_selection());
+        assert!(!state.start_selected());
+        assert!(state.active_timer().is_none());
+        assert!(state.active_index.is_none());
+        assert!(!state.reset_active());
+        assert_eq!(state.toggle_active(1000), None);
+    }
+
+    #[test]
+    fn test_zero_duration_refuses_to_start() {
+        let mut state = state_with(2);
+        state.entries[1].duration_ms = 0;
+        state.normalize_entries();
+        assert_eq!(state.entries.len(), 2);
+
+        state.cursor = 1;
+        assert!(state.selection_invalid());
+        assert!(!state.start_selected());
+        assert!(state.active_timer().is_none());
+
+        state.cursor = 0;
+        assert!(!state.selection_invalid());
+        assert!(state.start_selected());
+    }
+
+    #[test]
+    fn test_chain_skips_zero_duration() {
+        let mut state = state_with(2);
+        state.entries[1].duration_ms = 0;
+        assert!(state.set_on_expiry_start(0, Some(1)));
+        assert_eq!(state.follow_on_index(0), None);
+    }
+
+    #[test]
+    fn test_cursor_out_of_range_is_noop() {
+        let mut state = state_with(2);
+        state.cursor = 5;
+        assert!(!state.start_selected());
+        assert!(state.active_timer().is_none());
+    }
+
+    #[test]
+    fn test_toggle_and_reset_active() {
+        let mut state = state_with(1);
+        assert!(state.start_selected());
+        // A freshly armed timer is stopped; toggling is reserved for running/paused
+        assert_eq!(state.toggle_active(0), None);
+        state.active_timer_mut().unwrap().start(0);
+        assert_eq!(state.toggle_active(400), Some(false));
+        assert_eq!(state.toggle_active(500), Some(true));
+        assert!(state.reset_active());
+        let timer = state.active_timer().unwrap();
+        assert_eq!(timer.state, TimerState::Stopped);
+        assert_eq!(timer.remaining_ms(900), Some(1000));
+
+        // Active entry deleted out from under the timer
+        state.cursor = 0;
+        state.delete_selected();
+        assert!(!state.reset_active());
+    }
+
+    #[test]
+    fn test_timers_run_side_by_side() {
+        let mut state = state_with(3);
+        for idx in [0, 2] {
+            state.cursor = idx;
+            assert!(state.start_selected());
+            state.timer_mut(idx).unwrap().start(0);
+        }
+        assert_eq!(state.running_count(), 2);
+        assert_eq!(state.active_index, Some(2));
+        assert!(state.timer(1).is_none());
+
+        assert_eq!(state.tick_all(1000), vec![0]);
+        assert_eq!(state.running_count(), 1);
+        assert_eq!(state.tick_all(2000), Vec::<usize>::new());
+        assert_eq!(state.tick_all(3000), vec![2]);
+
+        state.pause_all(3000);
+        assert_eq!(state.running_count(), 0);
+    }
+
+    #[test]
+    fn test_list_edits_carry_timers() {
+        let mut state = state_with(3);
+        state.cursor = 2;
+        state.start_selected();
+
+        state.cursor = 0;
+        state.delete_selected();
+        assert!(state.timer(1).is_some());
+        assert_eq!(state.active_name(), Some("T2"));
+
+        state.cursor = 1;
+        assert!(state.move_up());
+        assert!(state.timer(0).is_some());
+        assert!(state.timer(1).is_none());
+
+        assert!(state.duplicate_selected());
+        assert!(state.timer(0).is_some());
+        assert!(state.timer(1).is_none());
+        assert_eq!(state.armed.len(), state.entries.len());
+    }
+
+    #[test]
+    fn test_hand_over_moves_shown_timer() {
+        let mut state = state_with(3);
+        assert!(state.set_on_expiry_start(0, Some(1)));
+        state.start_selected();
+        assert!(state.hand_over(0, 1));
+        assert!(state.timer(0).is_none());
+        assert_eq!(state.active_name(), Some("T1"));
+
+        // A timer that isn't shown hands over without moving the screen
+        state.cursor = 2;
+        state.start_selected();
+        state.arm(0);
+        assert!(state.hand_over(0, 1));
+        assert_eq!(state.active_name(), Some("T2"));
+    }
+
+    #[test]
+    fn test_visible_window() {
+        // Short lists show everything
+        assert_eq!(visible_window(0, 0, 6), (0, 0));
+        assert_eq!(visible_window(3, 6, 6), (0, 6));
+
+        // Top of a long list
+        assert_eq!(visible_window(0, 20, 6), (0, 6));
+        assert_eq!(visible_window(3, 20, 6), (0, 6));
+        // Middle: the cursor sits mid-window
+        assert_eq!(visible_window(4, 20, 6), (1, 7));
+        assert_eq!(visible_window(10, 20, 6), (7, 13));
+        // Bottom: the window stops at the last row
+        assert_eq!(visible_window(17, 20, 6), (14, 20));
+        assert_eq!(visible_window(19, 20, 6), (14, 20));
+
+        for cursor in 0..20 {
+            let (start, end) = visible_window(cursor, 20, 5);
+            assert!(start <= cursor && cursor < end, "cursor {} outside {}..{}", cursor, start, end);
+            assert_eq!(end - start, 5);
+        }
+    }
+
+    #[test]
+    fn test_parse_chain() {
+        assert_eq!(parse_chain("1 2 1 2", 2), Some(vec![0, 1, 0, 1]));
+        assert_eq!(parse_chain(" 3,1 ", 3), Some(vec![2, 0]));
+        assert_eq!(parse_chain("1 4", 3), None);
+        assert_eq!(parse_chain("0", 3), None);
+        assert_eq!(parse_chain("", 3), None);
+        assert_eq!(parse_chain(&"1 ".repeat(MAX_CHAIN_STEPS + 1), 3), None);
+        assert_eq!(format_chain(&[0, 1, 0, 1]), "1 2 1 2");
+    }
+
+    #[test]
+    fn test_saved_chains_follow_list_edits() {
+        let mut state = state_with(3);
+        assert_eq!(state.save_chain(vec![0, 2, 1]), Some(0));
+        assert_eq!(state.save_chain(vec![1]), Some(1));
+        assert_eq!(state.save_chain(vec![3]), None);
+        // Saving an existing chain again makes it the latest
+        assert_eq!(state.save_chain(vec![0, 2, 1]), Some(1));
+        assert_eq!(state.chains[0].steps, vec![1]);
+
+        state.cursor = 1;
+        state.delete_selected();
+        assert_eq!(state.chains.len(), 1);
+        assert_eq!(state.chains[0].steps, vec![0, 1]);
+
+        state.cursor = 0;
+        state.move_down();
+        assert_eq!(state.chains[0].steps, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_chain_skips_invalid_steps() {
+        let mut state = state_with(3);
+        state.entries[0].duration_ms = 0;
+        state.entries[2].duration_ms = 0;
+        let chain = state.save_chain(vec![0, 1, 2]).unwrap();
+        assert!(state.start_chain(chain));
+        assert_eq!(state.active_name(), Some("T1"));
+        assert_eq!(state.active_chain_step(), Some((2, 3)));
+        assert_eq!(state.expiry_action(1), ExpiryAction::Stop);
+
+        // Stopping the step by hand ends the chain
+        state.stop_active();
+        assert!(state.chain.is_none());
+    }
+
+    #[test]
+    fn test_chain_start() {
+        let mut state = state_with(2);
+        assert!(state.set_on_expiry_start(0, Some(1)));
+
+        assert_eq!(state.follow_on_index(0), Some(1));
+        assert_eq!(state.follow_on_index(1), None);
+    }
+
+    #[test]
+    fn test_cycle_rejected() {
+        let mut state = state_with(3);
+        assert!(state.set_on_expiry_start(0, Some(1)));
+        assert!(state.set_on_expiry_start(1, Some(2)));
+        assert!(!state.set_on_expiry_start(1, Some(0)));
+        assert!(!state.set_on_expiry_start(2, Some(0)));
+        assert!(!state.set_on_expiry_start(0, Some(0)));
+        assert_eq!(state.entries[2].on_expiry_start, None);
+        // Clearing a link is always allowed
+        assert!(state.set_on_expiry_start(0, None));
+        assert!(state.set_on_expiry_start(2, Some(0)));
+    }
+
+    #[test]
+    fn test_sanitize_links() {
+        let mut state = state_with(2);
+        state.entries[0].on_expiry_start = Some(1);
+        state.entries[1].on_expiry_start = Some(0);
+        state.sanitize_links();
+        assert_eq!(state.entries[0].on_expiry_start, None);
+        assert_eq!(state.entries[1].on_expiry_start, Some(0));
+
+        state.entries[0].on_expiry_start = Some(7);
+        state.sanitize_links();
+        assert_eq!(state.entries[0].on_expiry_start, None);
+    }
+
+    #[test]
+    fn test_delete_fixes_links() {
+        let mut state = state_with(3);
+        state.set_on_expiry_start(0, Some(2));
+        state.set_on_expiry_start(2, Some(1));
+        state.cursor = 1;
+        state.delete_selected();
+        assert_eq!(state.entries[0].on_expiry_start, Some(1));
+        assert_eq!(state.entries[1].on_expiry_start, None);
     }
 }
@@ -0,0 +1,125 @@
+use crate::AppMode;
+
+/// Number of events kept; older ones are overwritten.
+pub const EVENT_LOG_LEN: usize = 32;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EventKind {
+    Start,
+    Pause,
+    Reset,
+    Expire,
+    Alert,
+}
+
+impl EventKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EventKind::Start => "start",
+            EventKind::Pause => "pause",
+            EventKind::Reset => "reset",
+            EventKind::Expire => "expire",
+            EventKind::Alert => "alert",
+        }
+    }
+}
+
+/// Which timer an event belongs to.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EventSource {
+    Pomodoro,
+    Stopwatch,
+    Countdown,
+    Interval,
+}
+
+impl EventSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EventSource::Pomodoro => "pomodoro",
+            EventSource::Stopwatch => "stopwatch",
+            EventSource::Countdown => "countdown",
+            EventSource::Interval => "interval",
+        }
+    }
+
+    /// Timer shown in the given mode, if any.
+    pub fn for_mode(mode: AppMode) -> Option<Self> {
+        match mode {
+            AppMode::Pomodoro => Some(EventSource::Pomodoro),
+            AppMode::Stopwatch => Some(EventSource::Stopwatch),
+            AppMode::CountdownList | AppMode::CountdownRun => Some(EventSource::Countdown),
+            AppMode::Interval => Some(EventSource::Interval),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Event {
+    pub kind: EventKind,
+    pub source: EventSource,
+    pub now_ms: u64,
+}
+
+/// Fixed-size ring buffer of recent timer events, for the debug screen.
+pub struct EventLog {
+    events: [Option<Event>; EVENT_LOG_LEN],
+    next: usize,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        Self {
+            events: [None; EVENT_LOG_LEN],
+            next: 0,
+        }
+    }
+
+    /// Append an event, overwriting the oldest once the log is full.
+    pub fn push(&mut self, kind: EventKind, source: EventSource, now_ms: u64) {
+        self.events[self.next] = Some(Event { kind, source, now_ms });
+        self.next = (self.next + 1) % EVENT_LOG_LEN;
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.iter().filter(|e| e.is_some()).count()
+    }
+
+    /// Logged events, newest first.
+    pub fn recent(&self) -> impl Iterator<Item = &Event> {
+        (1..=EVENT_LOG_LEN)
+            .map(move |back| &self.events[(self.next + EVENT_LOG_LEN - back) % EVENT_LOG_LEN])
+            .map_while(|e| e.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_is_newest_first() {
+        let mut log = EventLog::new();
+        assert_eq!(log.len(), 0);
+        assert_eq!(log.recent().count(), 0);
+
+        log.push(EventKind::Start, EventSource::Stopwatch, 10);
+        log.push(EventKind::Pause, EventSource::Stopwatch, 20);
+        let kinds: Vec<EventKind> = log.recent().map(|e| e.kind).collect();
+        assert_eq!(kinds, vec![EventKind::Pause, EventKind::Start]);
+    }
+
+    #[test]
+    fn test_wraparound_drops_oldest() {
+        let mut log = EventLog::new();
+        for t in 0..(EVENT_LOG_LEN as u64 + 5) {
+            log.push(EventKind::Alert, EventSource::Countdown, t);
+        }
+        assert_eq!(log.len(), EVENT_LOG_LEN);
+        let times: Vec<u64> = log.recent().map(|e| e.now_ms).collect();
+        assert_eq!(times.len(), EVENT_LOG_LEN);
+        assert_eq!(times[0], EVENT_LOG_LEN as u64 + 4);
+        assert_eq!(times[EVENT_LOG_LEN - 1], 5);
+    }
+}
@@ -0,0 +1,288 @@
+use timer_core::{format_auto, format_ms, TimerCore};
+
+/// Tabata defaults: 20 s on, 10 s off, 8 rounds.
+const DEFAULT_WORK_MS: u64 = 20 * 1000;
+const DEFAULT_REST_MS: u64 = 10 * 1000;
+const DEFAULT_ROUNDS: u8 = 8;
+pub const MAX_ROUNDS: u8 = 99;
+/// Longest work or rest stretch: an hour.
+pub const MAX_INTERVAL_MS: u64 = 60 * 60 * 1000;
+const MIN_WORK_MS: u64 = 1000;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum IntervalPhase {
+    Work,
+    Rest,
+}
+
+impl IntervalPhase {
+    pub fn label(&self) -> &'static str {
+        match self {
+            IntervalPhase::Work => "WORK",
+            IntervalPhase::Rest => "REST",
+        }
+    }
+}
+
+/// Where `advance` moved the workout.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum IntervalStep {
+    /// Into the rest after a work stretch.
+    Rest,
+    /// Into the work stretch of the next round.
+    Work,
+    /// The last round's work is done.
+    Done,
+}
+
+/// Persisted interval configuration.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct IntervalSettings {
+    pub work_ms: u64,
+    pub rest_ms: u64,
+    pub rounds: u8,
+}
+
+impl IntervalSettings {
+    /// Work of at least a second, rest of zero (none) or more, both up to
+    /// `MAX_INTERVAL_MS`, and 1 to `MAX_ROUNDS` rounds.
+    pub fn is_valid(&self) -> bool {
+        (MIN_WORK_MS..=MAX_INTERVAL_MS).contains(&self.work_ms)
+            && self.rest_ms <= MAX_INTERVAL_MS
+            && (1..=MAX_ROUNDS).contains(&self.rounds)
+    }
+}
+
+impl Default for IntervalSettings {
+    fn default() -> Self {
+        Self { work_ms: DEFAULT_WORK_MS, rest_ms: DEFAULT_REST_MS, rounds: DEFAULT_ROUNDS }
+    }
+}
+
+/// Round-based work/rest timer. Each round is a work stretch followed by
+/// a rest; the last round ends after its work.
+pub struct IntervalState {
+    pub timer: TimerCore,
+    pub phase: IntervalPhase,
+    pub work_ms: u64,
+    pub rest_ms: u64,
+    pub rounds: u8,
+    /// Round in progress, counting from 1.
+    pub round: u8,
+    /// The last round finished; the next start begins a new workout.
+    pub finished: bool,
+}
+
+impl IntervalState {
+    pub fn new() -> Self {
+        Self::from_settings(IntervalSettings::default())
+    }
+
+    /// Invalid settings give the defaults.
+    pub fn from_settings(settings: IntervalSettings) -> Self {
+        let settings = if settings.is_valid() { settings } else { IntervalSettings::default() };
+        Self {
+            timer: TimerCore::new_countdown(settings.work_ms),
+            phase: IntervalPhase::Work,
+            work_ms: settings.work_ms,
+            rest_ms: settings.rest_ms,
+            rounds: settings.rounds,
+            round: 1,
+            finished: false,
+        }
+    }
+
+    pub fn settings(&self) -> IntervalSettings {
+        IntervalSettings { work_ms: self.work_ms, rest_ms: self.rest_ms, rounds: self.rounds }
+    }
+
+    /// Apply new settings and start the workout over. Returns false (and
+    /// changes nothing) if they're invalid.
+    pub fn set_settings(&mut self, settings: IntervalSettings) -> bool {
+        if !settings.is_valid() {
+            return false;
+        }
+        self.work_ms = settings.work_ms;
+        self.rest_ms = settings.rest_ms;
+        self.rounds = settings.rounds;
+        self.reset();
+        true
+    }
+
+    /// Back to round 1's work, stopped.
+    pub fn reset(&mut self) {
+        self.phase = IntervalPhase::Work;
+        self.round = 1;
+        self.finished = false;
+        self.timer = TimerCore::new_countdown(self.work_ms);
+    }
+
+    /// Move on once the current stretch has run out: work to rest, rest to
+    /// the next round's work, and the last round's work to done. A zero
+    /// rest goes straight to the next round. The new stretch's timer is
+    /// armed but not started.
+    pub fn advance(&mut self) -> IntervalStep {
+        if self.finished {
+            return IntervalStep::Done;
+        }
+        match self.phase {
+            IntervalPhase::Work if self.round >= self.rounds => {
+                self.finished = true;
+                IntervalStep::Done
+            }
+            IntervalPhase::Work if self.rest_ms > 0 => {
+                self.phase = IntervalPhase::Rest;
+                self.timer = TimerCore::new_countdown(self.rest_ms);
+                IntervalStep::Rest
+            }
+            IntervalPhase::Work | IntervalPhase::Rest => {
+                self.round += 1;
+                self.phase = IntervalPhase::Work;
+                self.timer = TimerCore::new_countdown(self.work_ms);
+                IntervalStep::Work
+            }
+        }
+    }
+
+    /// Rounds not yet started.
+    pub fn rounds_left(&self) -> u8 {
+        self.rounds.saturating_sub(self.round)
+    }
+
+    /// Alert text for the stretch `advance` just moved into.
+    pub fn message(&self, step: IntervalStep) -> String {
+        match step {
+            IntervalStep::Rest => "Rest!".to_string(),
+            IntervalStep::Work => format!("Round {}/{}: work!", self.round, self.rounds),
+            IntervalStep::Done => "Workout done!".to_string(),
+        }
+    }
+
+    /// "Round 3/8 — WORK 00:25", or "Done — 8 rounds" once finished.
+    pub fn header(&self, now_ms: u64) -> String {
+        if self.finished {
+            return format!("Done — {} rounds", self.rounds);
+        }
+        let remaining = self.timer.remaining_ms(now_ms).unwrap_or(0);
+        format!("Round {}/{} — {} {}", self.round, self.rounds, self.phase.label(), format_ms(remaining))
+    }
+
+    /// One-line plain-text status, e.g. "Interval, round 3/8 work, 0:25
+    /// remaining, running."
+    pub fn status_line(&self, now_ms: u64) -> String {
+        if self.finished {
+            return format!("Interval, done after {} rounds.", self.rounds);
+        }
+        format!(
+            "Interval, round {}/{} {}, {} remaining, {}.",
+            self.round,
+            self.rounds,
+            self.phase.label().to_lowercase(),
+            format_auto(self.timer.remaining_ms(now_ms).unwrap_or(0)),
+            self.timer.state.label().to_lowercase()
+        )
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.timer.state.is_active()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use timer_core::TimerState;
+
+    fn state(work_s: u64, rest_s: u64, rounds: u8) -> IntervalState {
+        IntervalState::from_settings(IntervalSettings { work_ms: work_s * 1000, rest_ms: rest_s * 1000, rounds })
+    }
+
+    #[test]
+    fn test_advance_alternates_work_and_rest() {
+        let mut s = state(30, 10, 3);
+        assert_eq!((s.round, s.phase), (1, IntervalPhase::Work));
+        assert_eq!(s.rounds_left(), 2);
+
+        assert_eq!(s.advance(), IntervalStep::Rest);
+        assert_eq!((s.round, s.phase), (1, IntervalPhase::Rest));
+        assert_eq!(s.timer.remaining_ms(0), Some(10_000));
+
+        assert_eq!(s.advance(), IntervalStep::Work);
+        assert_eq!((s.round, s.phase), (2, IntervalPhase::Work));
+        assert_eq!(s.timer.remaining_ms(0), Some(30_000));
+        assert_eq!(s.rounds_left(), 1);
+        // New stretches wait for the caller to start them
+        assert_eq!(s.timer.state, TimerState::Stopped);
+    }
+
+    #[test]
+    fn test_last_round_skips_its_rest() {
+        let mut s = state(30, 10, 2);
+        let steps: Vec<_> = (0..3).map(|_| s.advance()).collect();
+        assert_eq!(steps, vec![IntervalStep::Rest, IntervalStep::Work, IntervalStep::Done]);
+        assert!(s.finished);
+        assert_eq!(s.round, 2);
+        assert_eq!(s.rounds_left(), 0);
+        // Nothing further once done
+        assert_eq!(s.advance(), IntervalStep::Done);
+        assert_eq!(s.round, 2);
+
+        let mut single = state(30, 10, 1);
+        assert_eq!(single.advance(), IntervalStep::Done);
+    }
+
+    #[test]
+    fn test_zero_rest_goes_straight_to_next_round() {
+        let mut s = state(30, 0, 3);
+        assert_eq!(s.advance(), IntervalStep::Work);
+        assert_eq!((s.round, s.phase), (2, IntervalPhase::Work));
+        assert_eq!(s.advance(), IntervalStep::Work);
+        assert_eq!(s.advance(), IntervalStep::Done);
+    }
+
+    #[test]
+    fn test_reset_and_new_settings() {
+        let mut s = state(30, 10, 2);
+        s.advance();
+        s.advance();
+        s.advance();
+        s.reset();
+        assert_eq!((s.round, s.phase, s.finished), (1, IntervalPhase::Work, false));
+
+        assert!(s.set_settings(IntervalSettings { work_ms: 45_000, rest_ms: 15_000, rounds: 5 }));
+        assert_eq!(s.timer.remaining_ms(0), Some(45_000));
+        assert!(!s.set_settings(IntervalSettings { work_ms: 0, rest_ms: 15_000, rounds: 5 }));
+        assert!(!s.set_settings(IntervalSettings { work_ms: 45_000, rest_ms: 15_000, rounds: 0 }));
+        assert!(!s.set_settings(IntervalSettings { work_ms: 45_000, rest_ms: MAX_INTERVAL_MS + 1, rounds: 5 }));
+        assert_eq!(s.settings(), IntervalSettings { work_ms: 45_000, rest_ms: 15_000, rounds: 5 });
+
+        let fallback = IntervalState::from_settings(IntervalSettings { work_ms: 0, rest_ms: 0, rounds: 0 });
+        assert_eq!(fallback.settings(), IntervalSettings::default());
+    }
+
+    #[test]
+    fn test_header_and_messages() {
+        let mut s = state(30, 10, 8);
+        s.round = 3;
+        s.timer.start(0);
+        assert_eq!(s.header(5_000), "Round 3/8 — WORK 00:25");
+        assert_eq!(s.advance(), IntervalStep::Rest);
+        assert_eq!(s.header(0), "Round 3/8 — REST 00:10");
+        assert_eq!(s.message(IntervalStep::Rest), "Rest!");
+        s.advance();
+        assert_eq!(s.message(IntervalStep::Work), "Round 4/8: work!");
+        s.finished = true;
+        assert_eq!(s.header(0), "Done — 8 rounds");
+        assert_eq!(s.message(IntervalStep::Done), "Workout done!");
+    }
+
+    #[test]
+    fn test_status_line() {
+        let mut s = state(30, 10, 8);
+        assert_eq!(s.status_line(0), "Interval, round 1/8 work, 0:30 remaining, stopped.");
+        s.timer.start(0);
+        assert_eq!(s.status_line(5_000), "Interval, round 1/8 work, 0:25 remaining, running.");
+        s.finished = true;
+        assert_eq!(s.status_line(5_000), "Interval, done after 8 rounds.");
+    }
+}
@@ -2,19 +2,35 @@
 #![cfg_attr(target_os = "none", no_main)]
 
 mod alerts;
+mod clock;
 mod countdown;
+mod eventlog;
+mod interval;
+mod modes;
 mod pomodoro;
+mod pump;
 mod stopwatch;
 mod storage;
 mod ui;
 
 use num_traits::{FromPrimitive, ToPrimitive};
-use timer_core::TimerState;
-
-use crate::alerts::{AlertConfig, fire_alert};
-use crate::countdown::CountdownState;
-use crate::pomodoro::PomodoroState;
-use crate::stopwatch::StopwatchState;
+use timer_core::{format_auto, format_hms, format_ms, parse_hms, TimerCore, TimerState};
+
+use crate::alerts::{
+    format_quiet_hours, parse_quiet_hours, AlertConfig, Escalation, UnackedExpiries, fire_alert,
+    repeat_alert,
+};
+use crate::countdown::{format_chain, parse_chain, ADJUST_STEP_MS, CountdownState};
+use crate::eventlog::{EventKind, EventLog, EventSource};
+use crate::interval::{IntervalSettings, IntervalState};
+use crate::modes::{
+    auto_resume_needed, background_pause_needed, digit_to_index, resume_prompt_needed, ModeEntry, ModeOrder, ResumeChoice,
+};
+use crate::pomodoro::{
+    format_sequence, parse_minutes, parse_sequence, PomodoroState, MAX_CYCLES, MAX_PHASE_MINS, MAX_SEQUENCE_PAIRS,
+};
+use crate::pump::{Effect, PausedTimers};
+use crate::stopwatch::{StopwatchState, StopwatchStats};
 use crate::storage::TimerStorage;
 
 const SERVER_NAME: &str = "_Timers_";
@@ -35,7 +51,7 @@ enum AppOp {
     Quit,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum AppMode {
     ModeSelect,
     Pomodoro,
@@ -43,8 +59,64 @@ pub enum AppMode {
     CountdownList,
     CountdownRun,
     Settings,
+    Stats,
+    EventLog,
+    Clock,
+    Interval,
+}
+
+/// Rows of the settings screen, in display order.
+#[derive(Clone, Copy, PartialEq)]
+enum SettingsRow {
+    Vibration,
+    Notification,
+    Audio,
+    Led,
+    VibePattern,
+    SoftVibe,
+    Escalate,
+    Snooze,
+    QuietHours,
+    ConfigurePomodoro,
+    PomodoroSequence,
+    ExtendBreaks,
+    FlexibleBreak,
+    AutoStart,
+    SetComplete,
+    StopwatchReminder,
+    LapAlert,
+    LapOnStop,
+    DefaultCountdown,
+    ShowElapsed,
+    ResumePrompt,
+    BackgroundPause,
 }
 
+const SETTINGS_ROWS: [SettingsRow; 22] = [
+    SettingsRow::Vibration,
+    SettingsRow::Notification,
+    SettingsRow::Audio,
+    SettingsRow::Led,
+    SettingsRow::VibePattern,
+    SettingsRow::SoftVibe,
+    SettingsRow::Escalate,
+    SettingsRow::Snooze,
+    SettingsRow::QuietHours,
+    SettingsRow::ConfigurePomodoro,
+    SettingsRow::PomodoroSequence,
+    SettingsRow::ExtendBreaks,
+    SettingsRow::FlexibleBreak,
+    SettingsRow::AutoStart,
+    SettingsRow::SetComplete,
+    SettingsRow::StopwatchReminder,
+    SettingsRow::LapAlert,
+    SettingsRow::LapOnStop,
+    SettingsRow::DefaultCountdown,
+    SettingsRow::ShowElapsed,
+    SettingsRow::ResumePrompt,
+    SettingsRow::BackgroundPause,
+];
+
 struct TimersApp {
     gam: gam::Gam,
     #[allow(dead_code)]
@@ -55,24 +127,48 @@ struct TimersApp {
     llio: llio::Llio,
     modals: modals::Modals,
     storage: TimerStorage,
+    /// System timezone offset, read from PDDB on launch and focus regain.
+    tz_offset_s: i64,
 
     mode: AppMode,
     mode_cursor: usize,
+    mode_order: ModeOrder,
+    // Remembered across visits within a session
     settings_cursor: usize,
     alert_config: AlertConfig,
+    /// Ask "resume or restart?" when entering a mode with a paused timer.
+    resume_prompt: bool,
+    /// Pause the shown timer while the app is in the background.
+    background_pause: bool,
+    /// The shown timer was paused by `background_pause`, not the user.
+    paused_by_system: bool,
+    /// Timers stopped by "pause all", for "resume all".
+    held_timers: PausedTimers,
 
     pomodoro: PomodoroState,
     stopwatch: StopwatchState,
+    stopwatch_stats: StopwatchStats,
     countdown: CountdownState,
+    interval: IntervalState,
+    events: EventLog,
+    unacked_expiries: UnackedExpiries,
+    /// Expiry alert repeating until a key is pressed.
+    escalation: Option<Escalation>,
 
     pump_conn: xous::CID,
     pump_running: bool,
+    /// Rate last sent to the pump thread.
+    pump_interval_ms: u64,
     allow_redraw: bool,
     // Menu overlay state
     menu_visible: bool,
     menu_cursor: usize,
     help_visible: bool,
     confirm_exit: bool,
+    /// Asking whether to delete the selected countdown.
+    confirm_delete: bool,
+    /// The screen the focus display was opened on; it shows only there.
+    focus_display: Option<AppMode>,
     should_quit: bool,
 }
 
@@ -104,15 +200,48 @@ impl TimersApp {
         let storage = TimerStorage::new();
 
         let alert_config = storage.load_alert_config();
-        let pomodoro = match storage.load_pomodoro_settings() {
-            Some((work, short, long, cycles)) => {
-                PomodoroState::from_settings(work, short, long, cycles)
+        let mode_order = storage.load_mode_order();
+        let resume_prompt = storage.load_resume_prompt();
+        let background_pause = storage.load_background_pause();
+        let tz_offset_s = storage.load_tz_offset_s();
+        let stopwatch_stats = storage.load_stopwatch_stats();
+        let mut pomodoro = match storage.load_pomodoro_settings() {
+            Some(settings) => {
+                let mut pomodoro = PomodoroState::from_settings(
+                    settings.work_ms, settings.short_ms, settings.long_ms, settings.cycles,
+                );
+                pomodoro.auto_extend_break = settings.auto_extend_break;
+                pomodoro.on_set_complete = settings.on_set_complete;
+                pomodoro.flexible_break = settings.flexible_break;
+                pomodoro.auto_start = settings.auto_start;
+                pomodoro
             }
             None => PomodoroState::new(),
         };
+        pomodoro.set_sequence(storage.load_pomodoro_sequence());
+        pomodoro.stats = storage.load_pomodoro_stats();
+        pomodoro.task = storage.load_pomodoro_task();
+
+        // A saved run comes back paused, laps and all
+        let mut stopwatch = storage.load_stopwatch().unwrap_or_else(StopwatchState::new);
+        if let Some(settings) = storage.load_stopwatch_settings() {
+            let now = tt.elapsed_ms();
+            stopwatch.set_reminder_interval(settings.reminder_interval_ms, stopwatch.timer.elapsed_ms(now));
+            stopwatch.set_lap_alert(settings.lap_alert_ms, now);
+            stopwatch.lap_on_stop = settings.lap_on_stop;
+            stopwatch.show_cumulative = settings.show_cumulative;
+        }
 
         let mut countdown = CountdownState::new();
         countdown.entries = storage.load_countdowns();
+        countdown.chains = storage.load_countdown_chains();
+        countdown.normalize_entries();
+        if let Some(settings) = storage.load_countdown_settings() {
+            countdown.set_default_countdown(settings.default_ms);
+            countdown.show_elapsed = settings.show_elapsed;
+        }
+
+        let interval = storage.load_interval_settings().map_or_else(IntervalState::new, IntervalState::from_settings);
 
         let pump_conn = xous::connect(pump_sid).expect("can't connect to pump");
 
@@ -125,20 +254,34 @@ impl TimersApp {
             llio,
             modals,
             storage,
+            tz_offset_s,
             mode: AppMode::ModeSelect,
             mode_cursor: 0,
+            mode_order,
             settings_cursor: 0,
             alert_config,
+            resume_prompt,
+            background_pause,
+            paused_by_system: false,
+            held_timers: PausedTimers::default(),
             pomodoro,
-            stopwatch: StopwatchState::new(),
+            stopwatch,
+            stopwatch_stats,
             countdown,
+            interval,
+            events: EventLog::new(),
+            unacked_expiries: UnackedExpiries::default(),
+            escalation: None,
             pump_conn,
             pump_running: false,
+            pump_interval_ms: 0,
             allow_redraw: true,
             menu_visible: false,
             menu_cursor: 0,
             help_visible: false,
             confirm_exit: false,
+            confirm_delete: false,
+            focus_display: None,
             should_quit: false,
         }
     }
@@ -160,37 +303,76 @@ impl TimersApp {
             ui::draw_confirm_exit(&self.gam, self.content, self.screensize);
             return;
         }
+        if self.confirm_delete {
+            let name = self.countdown.entries.get(self.countdown.cursor).map(|e| e.name.as_str()).unwrap_or("");
+            ui::draw_confirm_delete(&self.gam, self.content, self.screensize, name);
+            return;
+        }
         if self.menu_visible {
             ui::draw_menu(&self.gam, self.content, self.screensize, self.menu_items(), self.menu_cursor);
             return;
         }
 
         let now = self.now_ms();
+        if self.focus_shown() {
+            if let Some((label, ms)) = self.focus_reading(now) {
+                ui::draw_focus_display(&self.gam, self.content, self.screensize, &label, &format_auto(ms));
+                return;
+            }
+        }
         match self.mode {
             AppMode::ModeSelect => {
-                ui::draw_mode_select(&self.gam, self.content, self.screensize, self.mode_cursor);
+                // As a time of day, so the line doesn't go stale on this unpumped screen
+                let next_at = self.next_expiry_ms(now).map(|ms| self.seconds_of_day() + ms.div_ceil(1000) as u32);
+                ui::draw_mode_select(&self.gam, self.content, self.screensize, self.mode_order.entries(), self.mode_cursor, next_at);
             }
             AppMode::Pomodoro => {
-                ui::draw_pomodoro(&self.gam, self.content, self.screensize, &self.pomodoro, now);
+                ui::draw_pomodoro(&self.gam, self.content, self.screensize, &self.pomodoro, &self.alert_config, now);
             }
             AppMode::Stopwatch => {
-                ui::draw_stopwatch(&self.gam, self.content, self.screensize, &self.stopwatch, now);
+                ui::draw_stopwatch(&self.gam, self.content, self.screensize, &self.stopwatch, &self.alert_config, now);
             }
             AppMode::CountdownList => {
-                ui::draw_countdown_list(&self.gam, self.content, self.screensize, &self.countdown);
+                ui::draw_countdown_list(&self.gam, self.content, self.screensize, &self.countdown, now);
             }
             AppMode::CountdownRun => {
-                ui::draw_countdown_running(&self.gam, self.content, self.screensize, &self.countdown, now);
+                ui::draw_countdown_running(&self.gam, self.content, self.screensize, &self.countdown, &self.alert_config, now);
             }
             AppMode::Settings => {
-                ui::draw_settings(&self.gam, self.content, self.screensize, &self.alert_config, self.settings_cursor);
+                ui::draw_settings(&self.gam, self.content, self.screensize, &self.settings_items(), self.settings_cursor);
+            }
+            AppMode::Stats => {
+                let today = self.pomodoro.stats.sessions_on(self.today());
+                ui::draw_stats(&self.gam, self.content, self.screensize, &self.pomodoro.stats, today, &self.stopwatch_stats);
+            }
+            AppMode::EventLog => {
+                ui::draw_event_log(&self.gam, self.content, self.screensize, &self.events);
+            }
+            AppMode::Clock => {
+                ui::draw_clock(&self.gam, self.content, self.screensize, self.seconds_of_day());
             }
+            AppMode::Interval => {
+                ui::draw_interval(&self.gam, self.content, self.screensize, &self.interval, &self.alert_config, now);
+            }
+        }
+
+        let pending = self.unacked_expiries.pending(EventSource::for_mode(self.mode));
+        if pending > 0 {
+            ui::draw_expiry_badge(&self.gam, self.content, self.screensize, pending);
+        }
+    }
+
+    /// Clear the expiry badge for the timer currently on screen.
+    fn acknowledge_expiries(&mut self) {
+        if self.allow_redraw {
+            self.unacked_expiries.acknowledge(EventSource::for_mode(self.mode));
         }
     }
 
     fn start_pump(&mut self, interval_ms: u64) {
         if !self.pump_running {
             self.pump_running = true;
+            self.pump_interval_ms = interval_ms;
             xous::send_message(
                 self.pump_conn,
                 xous::Message::new_scalar(0, interval_ms as usize, 0, 0, 0),
@@ -208,46 +390,251 @@ impl TimersApp {
         }
     }
 
-    fn handle_pump(&mut self) {
+    /// Start the pump at the rate the screen on show needs, if anything
+    /// there is running or an alert is escalating. A pump already running
+    /// moves to that rate.
+    fn start_pump_for_mode(&mut self) {
+        if self.pump_running {
+            self.retune_pump();
+        } else if let Some(interval_ms) = self.wanted_pump_interval() {
+            self.start_pump(interval_ms);
+        }
+    }
+
+    /// UTC seconds since the epoch. On Xous the std clock comes from the
+    /// time server, which keeps it in step with the RTC; reading the RTC
+    /// through llio directly would give a raw count still needing the
+    /// RTC's own offset applied.
+    fn unix_seconds(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+
+    /// Local wall-clock seconds since midnight, for the clock screen.
+    fn seconds_of_day(&self) -> u32 {
+        clock::seconds_of_day(self.unix_seconds(), self.tz_offset_s)
+    }
+
+    /// Local day number, for counting today's pomodoros.
+    fn today(&self) -> u32 {
+        clock::day_number(self.unix_seconds(), self.tz_offset_s)
+    }
+
+    /// Local wall-clock minute of the day, for quiet hours.
+    fn minute_of_day(&self) -> u16 {
+        clock::minute_of_day(self.unix_seconds(), self.tz_offset_s)
+    }
+
+    fn wanted_pump_interval(&self) -> Option<u64> {
         let now = self.now_ms();
+        let wanted = if self.allow_redraw {
+            let wanted = pump::desired_pump_interval(
+                self.mode, &self.pomodoro, &self.stopwatch, &self.countdown, &self.interval, now,
+            );
+            pump::focus_pump_interval(wanted, self.focus_shown())
+        } else {
+            None
+        };
+        // Nothing on screen ticking (or nothing drawn at all): still wake
+        // for whichever timer runs out next, wherever it lives
+        wanted
+            .or_else(|| pump::expiry_wakeup_interval(self.next_expiry_ms(now)))
+            .or(self.escalation.map(|_| 1000))
+    }
+
+    /// Time until the first running timer in any mode runs out.
+    fn next_expiry_ms(&self, now_ms: u64) -> Option<u64> {
+        pump::next_expiry_ms(&self.pomodoro, &self.stopwatch, &self.countdown, &self.interval, now_ms)
+    }
+
+    /// Move a running pump to the rate the screen now wants.
+    fn retune_pump(&mut self) {
+        if !self.pump_running {
+            return;
+        }
+        if let Some(interval_ms) = self.wanted_pump_interval().filter(|&ms| ms != self.pump_interval_ms) {
+            self.pump_interval_ms = interval_ms;
+            xous::send_message(
+                self.pump_conn,
+                xous::Message::new_scalar(0, interval_ms as usize, 0, 0, 0),
+            ).ok();
+        }
+    }
+
+    fn focus_shown(&self) -> bool {
+        self.focus_display == Some(self.mode)
+    }
 
+    /// Label and time for the focus display on this screen: what's left,
+    /// or the time so far for timers that count up. None where the focus
+    /// display isn't offered.
+    fn focus_reading(&self, now: u64) -> Option<(String, u64)> {
+        let shown = |timer: &TimerCore| timer.remaining_ms(now).unwrap_or_else(|| timer.elapsed_ms(now));
         match self.mode {
-            AppMode::Pomodoro => {
-                if self.pomodoro.timer.is_expired(now) {
-                    self.pomodoro.timer.pause(now);
-                    let msg = self.pomodoro.advance_phase();
-                    fire_alert(&self.alert_config, &self.llio, &self.modals, msg);
-                    // Auto-start next phase
-                    let now2 = self.now_ms();
-                    self.pomodoro.timer.start(now2);
+            AppMode::Pomodoro => Some((self.pomodoro.phase.label().to_string(), shown(&self.pomodoro.timer))),
+            AppMode::Stopwatch => Some(("Stopwatch".to_string(), shown(&self.stopwatch.timer))),
+            AppMode::CountdownRun => {
+                let timer = self.countdown.active_timer()?;
+                Some((self.countdown.active_name().unwrap_or("Countdown").to_string(), shown(timer)))
+            }
+            AppMode::Interval => Some((self.interval.phase.label().to_string(), shown(&self.interval.timer))),
+            _ => None,
+        }
+    }
+
+    /// Open or close the big-digits focus display, slowing the pump to
+    /// match while it's up.
+    fn toggle_focus_display(&mut self) {
+        self.focus_display = if self.focus_shown() { None } else { Some(self.mode) };
+        self.retune_pump();
+        self.redraw();
+    }
+
+    /// Stop the pump unless something still needs it, otherwise move it to
+    /// the rate that is still wanted.
+    fn stop_pump_if_idle(&mut self) {
+        if self.wanted_pump_interval().is_none() {
+            self.stop_pump();
+        } else {
+            self.retune_pump();
+        }
+    }
+
+    /// Repeat an unacknowledged expiry alert when it's due.
+    fn service_escalation(&mut self) {
+        let now = self.now_ms();
+        match self.escalation.as_mut().map(|e| e.tick(now)) {
+            Some(Some(true)) => repeat_alert(&self.alert_config, &self.llio, &self.tt, self.minute_of_day()),
+            Some(None) => {
+                self.escalation = None;
+                self.stop_pump_if_idle();
+            }
+            _ => {}
+        }
+    }
+
+    /// Refuse to start another timer once `MAX_RUNNING_TIMERS` are going,
+    /// telling the user why. Returns true if the start should go ahead.
+    fn check_timer_cap(&self) -> bool {
+        let running = pump::running_timers(&self.pomodoro, &self.stopwatch, &self.countdown, &self.interval);
+        if pump::can_start_timer(running) {
+            return true;
+        }
+        let msg = format!("{} timers are already running. Pause one first.", running);
+        self.modals.show_notification(&msg, None).ok();
+        false
+    }
+
+    fn handle_pump(&mut self) {
+        let now = self.now_ms();
+        let effects = pump::pump_step(
+            self.mode, &mut self.pomodoro, &mut self.stopwatch, &mut self.countdown, &mut self.interval, now,
+        );
+        for effect in effects {
+            self.apply_effect(effect);
+        }
+        self.service_escalation();
+        // A countdown entering its last seconds wants a faster pump
+        self.retune_pump();
+    }
+
+    fn apply_effect(&mut self, effect: Effect) {
+        match effect {
+            Effect::Alert(source, msg) => {
+                self.log_event(EventKind::Alert, source);
+                fire_alert(&self.alert_config, &self.llio, &self.tt, &self.modals, &msg, self.minute_of_day());
+                if self.alert_config.notification && self.escalation.take().is_some() {
+                    // Dismissing the notification was the acknowledgement
+                    self.stop_pump_if_idle();
                 }
-                self.redraw();
             }
-            AppMode::Stopwatch => {
-                self.redraw();
+            Effect::SnoozableAlert(msg, idx) => {
+                self.log_event(EventKind::Alert, EventSource::Countdown);
+                if !self.alert_config.notification || self.alert_config.snooze_ms == 0 {
+                    fire_alert(&self.alert_config, &self.llio, &self.tt, &self.modals, &msg, self.minute_of_day());
+                } else {
+                    // The snooze choice stands in for the notification
+                    let silent = AlertConfig { notification: false, ..self.alert_config.clone() };
+                    fire_alert(&silent, &self.llio, &self.tt, &self.modals, &msg, self.minute_of_day());
+                    self.offer_snooze(&msg, idx);
+                }
+                if self.alert_config.notification && self.escalation.take().is_some() {
+                    self.stop_pump_if_idle();
+                }
             }
-            AppMode::CountdownRun => {
-                let expired = self.countdown.active_timer.as_ref()
-                    .map(|t| t.is_expired(now))
-                    .unwrap_or(false);
-                if expired {
-                    let name = self.countdown.active_name()
-                        .unwrap_or("Timer").to_string();
-                    let msg = format!("{} expired!", name);
-                    self.countdown.stop_active();
-                    self.stop_pump();
-                    fire_alert(&self.alert_config, &self.llio, &self.modals, &msg);
-                    self.mode = AppMode::CountdownList;
+            Effect::Expired(source) => {
+                self.log_event(EventKind::Expire, source);
+                if self.alert_config.escalate {
+                    self.escalation = Some(Escalation::start(self.now_ms()));
                 }
-                self.redraw();
+                let viewing = if self.allow_redraw { EventSource::for_mode(self.mode) } else { None };
+                self.unacked_expiries.record(source, viewing);
+            }
+            Effect::SavePomodoroStats => {
+                let today = self.today();
+                self.pomodoro.stats.count_today(today);
+                let stats = self.pomodoro.stats;
+                log::info!(
+                    "pomodoro session {} done ({} ms focus total), task: {}",
+                    stats.total_sessions,
+                    stats.total_focus_ms,
+                    self.pomodoro.task.as_deref().unwrap_or("none")
+                );
+                self.storage.save_pomodoro_stats(&stats);
+            }
+            Effect::StartPomodoro => {
+                // Alerts block, so read the clock again
+                let now = self.now_ms();
+                self.pomodoro.timer.start(now);
+                self.log_event(EventKind::Start, EventSource::Pomodoro);
+            }
+            Effect::StartCountdown(idx) => {
+                let now = self.now_ms();
+                if let Some(timer) = self.countdown.timer_mut(idx) {
+                    timer.start(now);
+                }
+                self.log_event(EventKind::Start, EventSource::Countdown);
             }
-            _ => {
-                self.stop_pump();
+            Effect::StartInterval => {
+                let now = self.now_ms();
+                self.interval.timer.start(now);
+                self.log_event(EventKind::Start, EventSource::Interval);
             }
+            Effect::StopPump => self.stop_pump_if_idle(),
+            Effect::SetMode(mode) => self.mode = mode,
+            Effect::Quit => self.should_quit = true,
+            Effect::Redraw => self.redraw(),
+        }
+    }
+
+    /// Ask whether to snooze countdown entry `idx`, which just ran out, and
+    /// bring it back on the run screen if so.
+    fn offer_snooze(&mut self, msg: &str, idx: usize) {
+        let snooze_ms = self.alert_config.snooze_ms;
+        self.modals.add_list_item("Dismiss").ok();
+        self.modals.add_list_item(&format!("Snooze {}", format_auto(snooze_ms))).ok();
+        match self.modals.get_radiobutton(msg) {
+            Ok(label) if label.starts_with("Snooze") => {}
+            _ => return,
+        }
+        // The choice blocks, so read the clock again
+        let now = self.now_ms();
+        if self.countdown.snooze(idx, now, snooze_ms) {
+            self.log_event(EventKind::Start, EventSource::Countdown);
+            self.mode = AppMode::CountdownRun;
+            self.start_pump_for_mode();
         }
     }
 
     fn handle_key(&mut self, key: char) {
+        // A key press acknowledges an escalating alert and does nothing else
+        if self.escalation.take().is_some() {
+            self.stop_pump_if_idle();
+            return;
+        }
+
         // F-keys always processed first
         match key {
             KEY_F1 => { self.toggle_menu(); return; }
@@ -268,10 +655,15 @@ impl TimersApp {
         if self.confirm_exit {
             match key {
                 'y' => {
-                    // Stop timers and exit
-                    self.stop_all_timers();
                     self.confirm_exit = false;
-                    self.mode = AppMode::ModeSelect;
+                    if self.mode == AppMode::CountdownRun {
+                        // Stop just the shown timer and go back to the list
+                        self.stop_countdown();
+                    } else {
+                        // Stop timers and exit
+                        self.stop_all_timers();
+                        self.mode = AppMode::ModeSelect;
+                    }
                     self.redraw();
                 }
                 'n' => {
@@ -283,6 +675,24 @@ impl TimersApp {
             return;
         }
 
+        // If confirm delete dialog is showing
+        if self.confirm_delete {
+            match key {
+                'y' => {
+                    self.confirm_delete = false;
+                    self.countdown.delete_selected();
+                    self.save_countdowns();
+                    self.redraw();
+                }
+                'n' => {
+                    self.confirm_delete = false;
+                    self.redraw();
+                }
+                _ => {}
+            }
+            return;
+        }
+
         // If menu is open, handle menu navigation only
         if self.menu_visible {
             match key {
@@ -307,6 +717,12 @@ impl TimersApp {
             return;
         }
 
+        // f opens and closes the focus display; q also closes it
+        if (key == 'f' || (key == 'q' && self.focus_shown())) && self.focus_reading(self.now_ms()).is_some() {
+            self.toggle_focus_display();
+            return;
+        }
+
         // Normal mode-specific key handling
         match self.mode.clone() {
             AppMode::ModeSelect => self.handle_key_mode_select(key),
@@ -315,41 +731,98 @@ impl TimersApp {
             AppMode::CountdownList => self.handle_key_countdown_list(key),
             AppMode::CountdownRun => self.handle_key_countdown_run(key),
             AppMode::Settings => self.handle_key_settings(key),
+            AppMode::Stats => self.handle_key_stats(key),
+            AppMode::EventLog => self.handle_key_event_log(key),
+            AppMode::Clock => self.handle_key_clock(key),
+            AppMode::Interval => self.handle_key_interval(key),
         }
     }
 
     fn any_timer_running(&self) -> bool {
-        self.pomodoro.timer.state == TimerState::Running
-            || self.stopwatch.timer.state == TimerState::Running
-            || self.countdown.active_timer.as_ref()
-                .map(|t| t.state == TimerState::Running)
-                .unwrap_or(false)
+        self.pomodoro.timer.state.is_active()
+            || self.stopwatch.timer.state.is_active()
+            || self.countdown.running_count() > 0
+            || self.interval.is_running()
     }
 
     fn stop_all_timers(&mut self) {
         let now = self.now_ms();
-        if self.pomodoro.timer.state == TimerState::Running {
+        if self.pomodoro.timer.state.is_active() {
             self.pomodoro.timer.pause(now);
+            self.log_event(EventKind::Pause, EventSource::Pomodoro);
         }
-        if self.stopwatch.timer.state == TimerState::Running {
+        if self.stopwatch.timer.state.is_active() {
             self.stopwatch.timer.pause(now);
+            self.log_event(EventKind::Pause, EventSource::Stopwatch);
         }
-        if let Some(timer) = &mut self.countdown.active_timer {
-            if timer.state == TimerState::Running {
-                timer.pause(now);
-            }
+        if self.countdown.running_count() > 0 {
+            self.countdown.pause_all(now);
+            self.log_event(EventKind::Pause, EventSource::Countdown);
+        }
+        if self.interval.is_running() {
+            self.interval.timer.pause(now);
+            self.log_event(EventKind::Pause, EventSource::Interval);
         }
         self.stop_pump();
     }
 
+    /// Pause everything running, in every mode, remembering it for
+    /// `resume_all_timers`.
+    fn pause_all_timers(&mut self) {
+        let now = self.now_ms();
+        let running = [
+            (self.pomodoro.timer.state.is_active(), EventSource::Pomodoro),
+            (self.stopwatch.timer.state.is_active(), EventSource::Stopwatch),
+            (self.countdown.running_count() > 0, EventSource::Countdown),
+            (self.interval.is_running(), EventSource::Interval),
+        ];
+        if !self.held_timers.pause_all(&mut self.pomodoro, &mut self.stopwatch, &mut self.countdown, &mut self.interval, now) {
+            self.modals.show_notification("Nothing is running.", None).ok();
+            return;
+        }
+        for (was_running, source) in running {
+            if was_running {
+                self.log_event(EventKind::Pause, source);
+            }
+        }
+        self.stop_pump_if_idle();
+    }
+
+    /// Restart the timers `pause_all_timers` stopped. Ones paused by hand
+    /// stay paused.
+    fn resume_all_timers(&mut self) {
+        let now = self.now_ms();
+        let held = [
+            (self.held_timers.pomodoro, EventSource::Pomodoro),
+            (self.held_timers.stopwatch, EventSource::Stopwatch),
+            (!self.held_timers.countdowns.is_empty(), EventSource::Countdown),
+            (self.held_timers.interval, EventSource::Interval),
+        ];
+        let resumed = self.held_timers.resume_all(
+            &mut self.pomodoro, &mut self.stopwatch, &mut self.countdown, &mut self.interval, now,
+        );
+        if resumed == 0 {
+            self.modals.show_notification("Nothing to resume.", None).ok();
+            return;
+        }
+        for (was_held, source) in held {
+            if was_held {
+                self.log_event(EventKind::Start, source);
+            }
+        }
+        self.start_pump_for_mode();
+    }
+
     fn menu_items(&self) -> &'static [&'static str] {
         match self.mode {
-            AppMode::ModeSelect => &["Help", "Settings"],
+            AppMode::ModeSelect => &["Help", "Settings", "Stats", "Pause all", "Resume all"],
             AppMode::Pomodoro => &["Help", "Start/Pause", "Reset", "Settings"],
-            AppMode::Stopwatch => &["Help", "Start/Pause", "Lap", "Reset"],
-            AppMode::CountdownList => &["Help", "New Timer", "Delete", "Settings"],
-            AppMode::CountdownRun => &["Help", "Pause/Resume", "Reset", "Back"],
+            AppMode::Stopwatch => &["Help", "Start/Pause", "Lap", "Reset", "Target time"],
+            AppMode::CountdownList => &["Help", "New Timer", "Delete", "Duplicate", "Settings"],
+            AppMode::CountdownRun => &["Help", "Pause/Resume", "Reset", "Back", "Bar/Digits", "Stop"],
             AppMode::Settings => &["Help", "Back"],
+            AppMode::Stats | AppMode::EventLog | AppMode::Clock => &["Help", "Back"],
+            AppMode::Interval => &["Help", "Start/Pause", "Reset", "Configure"],
         }
     }
 
@@ -359,7 +832,7 @@ impl TimersApp {
             self.redraw();
             return;
         }
-        if self.confirm_exit {
+        if self.confirm_exit || self.confirm_delete {
             return;
         }
         self.menu_visible = !self.menu_visible;
@@ -376,8 +849,10 @@ impl TimersApp {
                     0 => { self.help_visible = true; }
                     1 => {
                         self.mode = AppMode::Settings;
-                        self.settings_cursor = 0;
                     }
+                    2 => { self.mode = AppMode::Stats; }
+                    3 => { self.pause_all_timers(); }
+                    4 => { self.resume_all_timers(); }
                     _ => {}
                 }
             }
@@ -386,26 +861,11 @@ impl TimersApp {
                     0 => { self.help_visible = true; }
                     1 => {
                         // Start/Pause - same as Enter
-                        let now = self.now_ms();
-                        match self.pomodoro.timer.state {
-                            TimerState::Stopped | TimerState::Paused => {
-                                self.pomodoro.timer.start(now);
-                                self.start_pump(1000);
-                            }
-                            TimerState::Running => {
-                                self.pomodoro.timer.pause(now);
-                                self.stop_pump();
-                            }
-                            _ => {}
-                        }
-                    }
-                    2 => {
-                        self.pomodoro.reset();
-                        self.stop_pump();
+                        self.toggle_pomodoro();
                     }
+                    2 => { self.reset_pomodoro(); }
                     3 => {
                         self.mode = AppMode::Settings;
-                        self.settings_cursor = 0;
                     }
                     _ => {}
                 }
@@ -413,31 +873,23 @@ impl TimersApp {
             AppMode::Stopwatch => {
                 match self.menu_cursor {
                     0 => { self.help_visible = true; }
-                    1 => {
-                        let now = self.now_ms();
-                        match self.stopwatch.timer.state {
-                            TimerState::Stopped | TimerState::Paused => {
-                                self.stopwatch.timer.start(now);
-                                self.start_pump(100);
-                            }
-                            TimerState::Running => {
-                                self.stopwatch.timer.pause(now);
-                                self.stop_pump();
-                            }
-                            _ => {}
-                        }
-                    }
+                    1 => { self.toggle_stopwatch(); }
                     2 => {
-                        let now = self.now_ms();
-                        if self.stopwatch.timer.state == TimerState::Running {
-                            self.stopwatch.record_lap(now);
+                        if self.stopwatch.timer.state.is_active() {
+                            self.lap_stopwatch();
                         }
                     }
                     3 => {
-                        if self.stopwatch.timer.state != TimerState::Running {
-                            self.stopwatch.reset();
+                        if !self.stopwatch.timer.state.is_active() {
+                            self.reset_stopwatch();
                         }
                     }
+                    4 => {
+                        self.menu_visible = false;
+                        self.redraw();
+                        self.configure_stopwatch_target();
+                        return;
+                    }
                     _ => {}
                 }
             }
@@ -451,14 +903,17 @@ impl TimersApp {
                         return;
                     }
                     2 => {
-                        if !self.countdown.entries.is_empty() {
-                            self.countdown.delete_selected();
-                            self.storage.save_countdowns(&self.countdown.entries);
-                        }
+                        self.confirm_delete = !self.countdown.entries.is_empty();
                     }
                     3 => {
+                        if self.countdown.duplicate_selected() {
+                            self.save_countdowns();
+                        } else if self.countdown.has_selection() {
+                            self.modals.show_notification("Timer list is full.", None).ok();
+                        }
+                    }
+                    4 => {
                         self.mode = AppMode::Settings;
-                        self.settings_cursor = 0;
                     }
                     _ => {}
                 }
@@ -466,90 +921,51 @@ impl TimersApp {
             AppMode::CountdownRun => {
                 match self.menu_cursor {
                     0 => { self.help_visible = true; }
-                    1 => {
-                        let now = self.now_ms();
-                        let action = if let Some(timer) = &mut self.countdown.active_timer {
-                            match timer.state {
-                                TimerState::Running => { timer.pause(now); Some(false) }
-                                TimerState::Paused => { timer.start(now); Some(true) }
-                                _ => None,
-                            }
-                        } else { None };
-                        match action {
-                            Some(true) => self.start_pump(1000),
-                            Some(false) => self.stop_pump(),
-                            None => {}
-                        }
-                    }
-                    2 => {
-                        self.countdown.start_selected();
-                        self.stop_pump();
-                    }
-                    3 => {
-                        self.countdown.stop_active();
-                        self.stop_pump();
-                        self.mode = AppMode::CountdownList;
-                    }
+                    1 => { self.toggle_countdown(); }
+                    2 => { self.reset_countdown(); }
+                    3 => { self.mode = AppMode::CountdownList; }
+                    4 => { self.countdown.bar_only = !self.countdown.bar_only; }
+                    5 => { self.request_stop_countdown(); }
                     _ => {}
                 }
             }
-            AppMode::Settings => {
+            AppMode::Settings | AppMode::Stats | AppMode::EventLog | AppMode::Clock => {
                 match self.menu_cursor {
                     0 => { self.help_visible = true; }
                     1 => { self.mode = AppMode::ModeSelect; }
                     _ => {}
                 }
             }
+            AppMode::Interval => {
+                match self.menu_cursor {
+                    0 => { self.help_visible = true; }
+                    1 => { self.toggle_interval(); }
+                    2 => { self.reset_interval(); }
+                    3 => { self.configure_interval(); }
+                    _ => {}
+                }
+            }
         }
         self.redraw();
     }
 
     fn handle_f2(&mut self) {
         if self.help_visible { self.help_visible = false; self.redraw(); return; }
-        if self.confirm_exit { return; }
+        if self.confirm_exit || self.confirm_delete { return; }
         if self.menu_visible { self.menu_visible = false; }
         // F2 = Start/Stop (same as Enter in timer modes)
-        let now = self.now_ms();
         match self.mode {
             AppMode::Pomodoro => {
-                match self.pomodoro.timer.state {
-                    TimerState::Stopped | TimerState::Paused => {
-                        self.pomodoro.timer.start(now);
-                        self.start_pump(1000);
-                    }
-                    TimerState::Running => {
-                        self.pomodoro.timer.pause(now);
-                        self.stop_pump();
-                    }
-                    _ => {}
-                }
+                self.toggle_pomodoro();
             }
             AppMode::Stopwatch => {
-                match self.stopwatch.timer.state {
-                    TimerState::Stopped | TimerState::Paused => {
-                        self.stopwatch.timer.start(now);
-                        self.start_pump(100);
-                    }
-                    TimerState::Running => {
-                        self.stopwatch.timer.pause(now);
-                        self.stop_pump();
-                    }
-                    _ => {}
-                }
+                self.toggle_stopwatch();
             }
             AppMode::CountdownRun => {
-                let action = if let Some(timer) = &mut self.countdown.active_timer {
-                    match timer.state {
-                        TimerState::Running => { timer.pause(now); Some(false) }
-                        TimerState::Paused => { timer.start(now); Some(true) }
-                        _ => None,
-                    }
-                } else { None };
-                match action {
-                    Some(true) => self.start_pump(1000),
-                    Some(false) => self.stop_pump(),
-                    None => {}
-                }
+                self.toggle_countdown();
+            }
+            AppMode::Interval => {
+                self.toggle_interval();
             }
             _ => {}
         }
@@ -558,22 +974,23 @@ impl TimersApp {
 
     fn handle_f3(&mut self) {
         if self.help_visible { self.help_visible = false; self.redraw(); return; }
-        if self.confirm_exit { return; }
+        if self.confirm_exit || self.confirm_delete { return; }
         if self.menu_visible { self.menu_visible = false; }
         // F3 = Reset (same as 'r')
         match self.mode {
             AppMode::Pomodoro => {
-                self.pomodoro.reset();
-                self.stop_pump();
+                self.reset_pomodoro();
             }
             AppMode::Stopwatch => {
-                if self.stopwatch.timer.state != TimerState::Running {
-                    self.stopwatch.reset();
+                if !self.stopwatch.timer.state.is_active() {
+                    self.reset_stopwatch();
                 }
             }
             AppMode::CountdownRun => {
-                self.countdown.start_selected();
-                self.stop_pump();
+                self.reset_countdown();
+            }
+            AppMode::Interval => {
+                self.reset_interval();
             }
             _ => {}
         }
@@ -592,14 +1009,19 @@ impl TimersApp {
             self.redraw();
             return;
         }
-        if self.confirm_exit {
+        if self.confirm_exit || self.confirm_delete {
             self.confirm_exit = false;
+            self.confirm_delete = false;
             self.redraw();
             return;
         }
+        if self.focus_shown() {
+            self.toggle_focus_display();
+            return;
+        }
         // F4 = Back/Exit
         match self.mode {
-            AppMode::Pomodoro | AppMode::Stopwatch | AppMode::CountdownList => {
+            AppMode::Pomodoro | AppMode::Stopwatch | AppMode::CountdownList | AppMode::Interval => {
                 if self.any_timer_running() {
                     self.confirm_exit = true;
                     self.redraw();
@@ -609,12 +1031,15 @@ impl TimersApp {
                 }
             }
             AppMode::CountdownRun => {
-                self.countdown.stop_active();
-                self.stop_pump();
-                self.mode = AppMode::CountdownList;
+                if self.countdown.active_timer().is_some_and(|t| t.state.is_active()) {
+                    // Confirming stops it and goes back to the list
+                    self.request_stop_countdown();
+                } else {
+                    self.mode = AppMode::CountdownList;
+                }
                 self.redraw();
             }
-            AppMode::Settings => {
+            AppMode::Settings | AppMode::Stats | AppMode::EventLog | AppMode::Clock => {
                 self.mode = AppMode::ModeSelect;
                 self.redraw();
             }
@@ -633,7 +1058,11 @@ impl TimersApp {
                  F4     Quit\n\n\
                  Up/Dn  Move cursor\n\
                  Enter  Open mode\n\
+                 1-5    Open mode on that row\n\
+                 [ / ]  Move mode up/down\n\
                  s      Settings\n\
+                 t      Stats\n\
+                 p      Pause all / resume all\n\
                  q      Quit"
             }
             AppMode::Pomodoro => {
@@ -645,6 +1074,7 @@ impl TimersApp {
                  Enter  Start/Pause\n\
                  r      Reset\n\
                  s      Settings\n\
+                 f      Focus display\n\
                  q      Back"
             }
             AppMode::Stopwatch => {
@@ -655,8 +1085,12 @@ impl TimersApp {
                  F4     Back\n\n\
                  Enter  Start/Pause\n\
                  l      Record lap\n\
-                 Up/Dn  Scroll laps\n\
+                 g      Set lap goal\n\
+                 t      Set target time\n\
+                 c      Split / total lap times\n\
+                 Up/Dn  Scroll laps (also , .)\n\
                  r      Reset (stopped)\n\
+                 f      Focus display\n\
                  q      Back"
             }
             AppMode::CountdownList => {
@@ -665,9 +1099,12 @@ impl TimersApp {
                  F2     Start/Pause\n\
                  F3     Reset\n\
                  F4     Back\n\n\
-                 Enter  Start timer\n\
+                 Enter  Start / show timer\n\
+                 1-9    Start / show that row\n\
                  n      New timer\n\
                  d      Delete timer\n\
+                 a      Start another after\n\
+                 c      Run a chain\n\
                  q      Back"
             }
             AppMode::CountdownRun => {
@@ -678,6 +1115,10 @@ impl TimersApp {
                  F4     Back to list\n\n\
                  Enter  Pause/Resume\n\
                  r      Reset\n\
+                 b      Bar only / digits\n\
+                 + / -  Add/remove a minute\n\
+                 x      Stop timer\n\
+                 f      Focus display\n\
                  q      Back to list"
             }
             AppMode::Settings => {
@@ -685,7 +1126,48 @@ impl TimersApp {
                  F1     Menu\n\
                  F4     Back\n\n\
                  Up/Dn  Move cursor\n\
-                 Enter  Toggle setting\n\
+                 Enter  Toggle/edit setting\n\
+                 q      Back"
+            }
+            AppMode::Stats => {
+                "STATS HELP\n\n\
+                 F1     Menu\n\
+                 F4     Back\n\n\
+                 Lifetime pomodoro and\n\
+                 stopwatch usage. Today's\n\
+                 pomodoros start over at\n\
+                 midnight. A stopwatch\n\
+                 session counts when a\n\
+                 used stopwatch is reset.\n\
+                 q      Back"
+            }
+            AppMode::EventLog => {
+                "EVENT LOG HELP\n\n\
+                 F1     Menu\n\
+                 F4     Back\n\n\
+                 Last 32 timer events,\n\
+                 newest first. Times are\n\
+                 device uptime.\n\
+                 q      Back"
+            }
+            AppMode::Interval => {
+                "INTERVAL HELP\n\n\
+                 F1     Menu\n\
+                 F2     Start/Pause\n\
+                 F3     Reset\n\
+                 F4     Back\n\n\
+                 Enter  Start/Pause\n\
+                 r      Reset to round 1\n\
+                 c      Set work/rest/rounds\n\
+                 f      Focus display\n\
+                 q      Back"
+            }
+            AppMode::Clock => {
+                "CLOCK HELP\n\n\
+                 F1     Menu\n\
+                 F4     Back\n\n\
+                 Local time of day,\n\
+                 from the system clock.\n\
                  q      Back"
             }
         }
@@ -700,117 +1182,345 @@ impl TimersApp {
                 }
             }
             '↓' | 'j' => {
-                if self.mode_cursor < 2 {
+                if self.mode_cursor + 1 < self.mode_order.len() {
                     self.mode_cursor += 1;
                     self.redraw();
                 }
             }
             '\r' | '\n' => {
-                match self.mode_cursor {
-                    0 => self.mode = AppMode::Pomodoro,
-                    1 => self.mode = AppMode::Stopwatch,
-                    2 => self.mode = AppMode::CountdownList,
-                    _ => {}
+                self.open_selected_mode();
+            }
+            '[' => {
+                let idx = self.mode_order.move_up(self.mode_cursor);
+                if idx != self.mode_cursor {
+                    self.mode_cursor = idx;
+                    self.storage.save_mode_order(&self.mode_order);
+                    self.redraw();
+                }
+            }
+            ']' => {
+                let idx = self.mode_order.move_down(self.mode_cursor);
+                if idx != self.mode_cursor {
+                    self.mode_cursor = idx;
+                    self.storage.save_mode_order(&self.mode_order);
+                    self.redraw();
                 }
-                self.redraw();
             }
             's' => {
                 self.mode = AppMode::Settings;
-                self.settings_cursor = 0;
                 self.redraw();
             }
-            _ => {}
-        }
-    }
-
-    fn handle_key_pomodoro(&mut self, key: char) {
-        let now = self.now_ms();
-        match key {
-            '\r' | '\n' => {
-                match self.pomodoro.timer.state {
-                    TimerState::Stopped | TimerState::Paused => {
-                        self.pomodoro.timer.start(now);
-                        self.start_pump(1000);
-                    }
-                    TimerState::Running => {
-                        self.pomodoro.timer.pause(now);
-                        self.stop_pump();
-                    }
-                    _ => {}
-                }
+            't' => {
+                self.mode = AppMode::Stats;
                 self.redraw();
             }
-            'r' => {
-                self.pomodoro.reset();
-                self.stop_pump();
+            'D' => {
+                // Hidden debug screen
+                self.mode = AppMode::EventLog;
                 self.redraw();
             }
-            's' => {
-                self.mode = AppMode::Settings;
-                self.settings_cursor = 0;
+            'p' => {
+                // Pause while anything runs; once all is still, resume
+                if pump::running_timers(&self.pomodoro, &self.stopwatch, &self.countdown, &self.interval) > 0 {
+                    self.pause_all_timers();
+                } else {
+                    self.resume_all_timers();
+                }
                 self.redraw();
             }
-            'q' => {
-                if self.pomodoro.timer.state == TimerState::Running {
-                    self.pomodoro.timer.pause(now);
+            c => {
+                // Number keys open the mode on that row
+                if let Some(idx) = digit_to_index(c).filter(|&idx| idx < self.mode_order.len()) {
+                    self.mode_cursor = idx;
+                    self.open_selected_mode();
                 }
-                self.stop_pump();
-                self.mode = AppMode::ModeSelect;
-                self.redraw();
             }
-            _ => {}
         }
     }
 
-    fn handle_key_stopwatch(&mut self, key: char) {
+    fn open_selected_mode(&mut self) {
+        match self.mode_order.get(self.mode_cursor) {
+            Some(ModeEntry::Pomodoro) => {
+                self.mode = AppMode::Pomodoro;
+                self.offer_resume();
+            }
+            Some(ModeEntry::Stopwatch) => {
+                // Lap scroll position is kept from the last visit
+                self.stopwatch.clamp_scroll();
+                self.mode = AppMode::Stopwatch;
+                self.offer_resume();
+            }
+            Some(ModeEntry::Countdown) => self.mode = AppMode::CountdownList,
+            Some(ModeEntry::Interval) => self.mode = AppMode::Interval,
+            Some(ModeEntry::Clock) => {
+                self.mode = AppMode::Clock;
+                self.start_pump_for_mode();
+            }
+            None => {}
+        }
+        self.redraw();
+    }
+
+    /// Start/pause the pomodoro. During an auto-extended break this
+    /// acknowledges the break and moves straight on to work.
+    fn toggle_pomodoro(&mut self) {
         let now = self.now_ms();
-        match key {
-            '\r' | '\n' => {
-                match self.stopwatch.timer.state {
-                    TimerState::Stopped | TimerState::Paused => {
-                        self.stopwatch.timer.start(now);
-                        self.start_pump(100);
-                    }
-                    TimerState::Running => {
-                        self.stopwatch.timer.pause(now);
-                        self.stop_pump();
-                    }
-                    _ => {}
-                }
-                self.redraw();
-            }
-            'l' => {
-                if self.stopwatch.timer.state == TimerState::Running {
-                    self.stopwatch.record_lap(now);
-                    self.redraw();
-                }
-            }
-            'r' => {
-                if self.stopwatch.timer.state != TimerState::Running {
-                    self.stopwatch.reset();
-                    self.redraw();
-                }
-            }
-            '↑' | 'k' => {
-                // Scroll up through lap history (show older laps)
-                if self.stopwatch.lap_scroll_offset + 1 < self.stopwatch.laps.len() {
-                    self.stopwatch.lap_scroll_offset += 1;
-                    self.redraw();
-                }
-            }
-            '↓' | 'j' => {
+        if self.pomodoro.break_extensions > 0 {
+            self.pomodoro.advance_phase();
+            self.pomodoro.timer.start(now);
+            self.log_event(EventKind::Start, EventSource::Pomodoro);
+            self.start_pump_for_mode();
+            return;
+        }
+        // A flexible break that hasn't been started yet starts like any other
+        let ended = if self.pomodoro.timer.state == TimerState::Stopped {
+            None
+        } else {
+            self.pomodoro.end_flexible_break(now)
+        };
+        if let Some(length) = ended {
+            log::info!("flexible break lasted {} ms", length);
+            self.pomodoro.timer.start(now);
+            self.log_event(EventKind::Start, EventSource::Pomodoro);
+            self.start_pump_for_mode();
+            return;
+        }
+        match self.pomodoro.timer.state {
+            TimerState::Stopped | TimerState::Paused if !self.check_timer_cap() => return,
+            TimerState::Stopped => self.pomodoro.timer.start(now),
+            TimerState::Paused => self.pomodoro.timer.resume(now),
+            TimerState::Running => {
+                self.pomodoro.timer.pause(now);
+                self.log_event(EventKind::Pause, EventSource::Pomodoro);
+                self.stop_pump_if_idle();
+                return;
+            }
+            TimerState::Expired => return,
+        }
+        self.log_event(EventKind::Start, EventSource::Pomodoro);
+        self.start_pump_for_mode();
+    }
+
+    /// Cut the current break short and start work right away.
+    fn skip_pomodoro_break(&mut self) {
+        if !self.pomodoro.is_break() {
+            let msg = self.pomodoro.skip_phase();
+            self.modals.show_notification(msg, None).ok();
+            return;
+        }
+        let now = self.now_ms();
+        self.pomodoro.skip_phase();
+        self.pomodoro.timer.start(now);
+        self.log_event(EventKind::Start, EventSource::Pomodoro);
+        self.start_pump_for_mode();
+        self.redraw();
+    }
+
+    /// Step back to the phase before the last transition, stopped.
+    fn previous_pomodoro_phase(&mut self) {
+        if !self.pomodoro.previous_phase() {
+            self.modals.show_notification("No earlier phase to go back to.", None).ok();
+            return;
+        }
+        self.stop_pump_if_idle();
+        self.storage.save_pomodoro_stats(&self.pomodoro.stats);
+        self.redraw();
+    }
+
+    /// On entering the pomodoro or stopwatch with its timer paused, ask
+    /// whether to resume where it left off or start over.
+    fn offer_resume(&mut self) {
+        let now = self.now_ms();
+        let (state, shown) = match self.mode {
+            AppMode::Pomodoro => (
+                self.pomodoro.timer.state,
+                format_ms(self.pomodoro.timer.remaining_ms(now).unwrap_or(0)),
+            ),
+            AppMode::Stopwatch => (
+                self.stopwatch.timer.state,
+                format_hms(self.stopwatch.timer.elapsed_ms(now)),
+            ),
+            _ => return,
+        };
+        if !resume_prompt_needed(self.resume_prompt, state) {
+            return;
+        }
+
+        self.modals.add_list_item(&format!("Resume ({})", shown)).ok();
+        self.modals.add_list_item("Restart").ok();
+        let choice = match self.modals.get_radiobutton("Timer is paused:") {
+            Ok(label) => ResumeChoice::from_selection(&label),
+            Err(_) => return,
+        };
+
+        match (self.mode, choice) {
+            (AppMode::Pomodoro, ResumeChoice::Restart) => {
+                self.reset_pomodoro();
+                self.toggle_pomodoro();
+            }
+            (AppMode::Pomodoro, ResumeChoice::Resume) => self.toggle_pomodoro(),
+            (AppMode::Stopwatch, ResumeChoice::Restart) => {
+                self.reset_stopwatch();
+                self.toggle_stopwatch();
+            }
+            (AppMode::Stopwatch, ResumeChoice::Resume) => self.toggle_stopwatch(),
+            _ => {}
+        }
+    }
+
+    /// Timer on screen in the current mode, with its event source.
+    fn shown_timer_mut(&mut self) -> Option<(&mut TimerCore, EventSource)> {
+        match self.mode {
+            AppMode::Pomodoro => Some((&mut self.pomodoro.timer, EventSource::Pomodoro)),
+            AppMode::Stopwatch => Some((&mut self.stopwatch.timer, EventSource::Stopwatch)),
+            AppMode::CountdownRun => self.countdown.active_timer_mut().map(|t| (t, EventSource::Countdown)),
+            _ => None,
+        }
+    }
+
+    /// Going to the background: pause the shown timer if that setting is on,
+    /// remembering that the app did it.
+    fn pause_for_background(&mut self) {
+        let now = self.now_ms();
+        let enabled = self.background_pause;
+        let paused = match self.shown_timer_mut() {
+            Some((timer, source)) if background_pause_needed(enabled, timer.state) => {
+                timer.pause(now);
+                Some(source)
+            }
+            _ => None,
+        };
+        if let Some(source) = paused {
+            self.paused_by_system = true;
+            self.log_event(EventKind::Pause, source);
+        }
+    }
+
+    /// Back in the foreground: resume a timer the app paused on the way out.
+    /// Pump restarts are left to the caller's running-timer check.
+    fn resume_from_background(&mut self) {
+        let now = self.now_ms();
+        let by_system = std::mem::take(&mut self.paused_by_system);
+        let resumed = match self.shown_timer_mut() {
+            Some((timer, source)) if auto_resume_needed(by_system, timer.state) => {
+                timer.resume(now);
+                Some(source)
+            }
+            _ => None,
+        };
+        if let Some(source) = resumed {
+            self.log_event(EventKind::Start, source);
+        }
+    }
+
+    fn reset_pomodoro(&mut self) {
+        self.pomodoro.reset();
+        self.log_event(EventKind::Reset, EventSource::Pomodoro);
+        self.stop_pump_if_idle();
+    }
+
+    fn toggle_stopwatch(&mut self) {
+        let now = self.now_ms();
+        match self.stopwatch.timer.state {
+            TimerState::Stopped | TimerState::Paused if !self.check_timer_cap() => return,
+            TimerState::Stopped => self.stopwatch.timer.start(now),
+            TimerState::Paused => self.stopwatch.timer.resume(now),
+            TimerState::Running => {
+                let goal_reached = self.stopwatch.stop(now);
+                self.storage.save_stopwatch(&self.stopwatch, now);
+                self.log_event(EventKind::Pause, EventSource::Stopwatch);
+                if goal_reached {
+                    self.alert_lap_goal();
+                }
+                self.stop_pump_if_idle();
+                return;
+            }
+            TimerState::Expired => return,
+        }
+        self.log_event(EventKind::Start, EventSource::Stopwatch);
+        self.start_pump_for_mode();
+    }
+
+    fn handle_key_pomodoro(&mut self, key: char) {
+        let now = self.now_ms();
+        match key {
+            '\r' | '\n' => {
+                self.toggle_pomodoro();
+                self.redraw();
+            }
+            'r' => {
+                self.reset_pomodoro();
+                self.redraw();
+            }
+            'n' => self.skip_pomodoro_break(),
+            'b' => self.previous_pomodoro_phase(),
+            't' => self.configure_pomodoro_task(),
+            's' => {
+                self.mode = AppMode::Settings;
+                self.redraw();
+            }
+            'q' => {
+                if self.pomodoro.timer.state.is_active() {
+                    self.pomodoro.timer.pause(now);
+                    self.log_event(EventKind::Pause, EventSource::Pomodoro);
+                }
+                self.mode = AppMode::ModeSelect;
+                self.stop_pump_if_idle();
+                self.redraw();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_key_stopwatch(&mut self, key: char) {
+        let now = self.now_ms();
+        match key {
+            '\r' | '\n' => {
+                self.toggle_stopwatch();
+                self.redraw();
+            }
+            'l' => {
+                if self.stopwatch.timer.state.is_active() {
+                    self.lap_stopwatch();
+                    self.redraw();
+                }
+            }
+            'g' => {
+                self.configure_lap_goal();
+            }
+            't' => {
+                self.configure_stopwatch_target();
+            }
+            'c' => {
+                self.stopwatch.show_cumulative = !self.stopwatch.show_cumulative;
+                self.storage.save_stopwatch_settings(&self.stopwatch.settings());
+                self.redraw();
+            }
+            'r' => {
+                if !self.stopwatch.timer.state.is_active() {
+                    self.reset_stopwatch();
+                    self.redraw();
+                }
+            }
+            '↑' | 'k' | ',' => {
+                // Scroll up through lap history (show older laps)
+                if self.stopwatch.scroll_up() {
+                    self.redraw();
+                }
+            }
+            '↓' | 'j' | '.' => {
                 // Scroll down through lap history (show newer laps)
-                if self.stopwatch.lap_scroll_offset > 0 {
-                    self.stopwatch.lap_scroll_offset -= 1;
+                if self.stopwatch.scroll_down() {
                     self.redraw();
                 }
             }
             'q' => {
-                if self.stopwatch.timer.state == TimerState::Running {
+                if self.stopwatch.timer.state.is_active() {
                     self.stopwatch.timer.pause(now);
+                    self.log_event(EventKind::Pause, EventSource::Stopwatch);
                 }
-                self.stop_pump();
                 self.mode = AppMode::ModeSelect;
+                self.stop_pump_if_idle();
                 self.redraw();
             }
             _ => {}
@@ -834,24 +1544,42 @@ impl TimersApp {
                 }
             }
             '\r' | '\n' => {
-                if !self.countdown.entries.is_empty() {
-                    self.countdown.start_selected();
-                    let now = self.now_ms();
-                    if let Some(timer) = &mut self.countdown.active_timer {
-                        timer.start(now);
-                    }
-                    self.mode = AppMode::CountdownRun;
-                    self.start_pump(1000);
-                    self.redraw();
-                }
+                self.open_selected_countdown();
             }
             'n' => {
                 self.create_new_countdown();
             }
             'd' => {
                 if !self.countdown.entries.is_empty() {
-                    self.countdown.delete_selected();
-                    self.storage.save_countdowns(&self.countdown.entries);
+                    self.confirm_delete = true;
+                    self.redraw();
+                }
+            }
+            'a' => {
+                if !self.countdown.entries.is_empty() {
+                    self.link_countdown();
+                }
+            }
+            'c' => {
+                if !self.countdown.entries.is_empty() {
+                    self.run_countdown_chain();
+                }
+            }
+            '[' => {
+                if self.countdown.move_up() {
+                    self.save_countdowns();
+                    self.redraw();
+                }
+            }
+            ']' => {
+                if self.countdown.move_down() {
+                    self.save_countdowns();
+                    self.redraw();
+                }
+            }
+            'l' => {
+                if self.countdown.toggle_repeat_selected() {
+                    self.save_countdowns();
                     self.redraw();
                 }
             }
@@ -861,49 +1589,81 @@ impl TimersApp {
             }
             's' => {
                 self.mode = AppMode::Settings;
-                self.settings_cursor = 0;
                 self.redraw();
             }
-            _ => {}
+            c => {
+                // Number keys pick the entry on that row, as Enter would
+                if let Some(idx) = digit_to_index(c).filter(|&idx| idx < self.countdown.entries.len()) {
+                    self.countdown.cursor = idx;
+                    self.open_selected_countdown();
+                }
+            }
+        }
+    }
+
+    /// Start the selected entry and show it, or just show it if it
+    /// already has a timer.
+    fn open_selected_countdown(&mut self) {
+        // An entry that already has a timer just brings it up
+        if self.countdown.timer(self.countdown.cursor).is_some() {
+            self.countdown.active_index = Some(self.countdown.cursor);
+            self.mode = AppMode::CountdownRun;
+            self.redraw();
+            return;
+        }
+        if !self.countdown.selection_invalid() && !self.check_timer_cap() {
+            return;
+        }
+        if self.countdown.start_selected() {
+            let now = self.now_ms();
+            if let Some(timer) = self.countdown.active_timer_mut() {
+                timer.start(now);
+            }
+            self.log_event(EventKind::Start, EventSource::Countdown);
+            self.mode = AppMode::CountdownRun;
+            self.start_pump_for_mode();
+            self.redraw();
+        } else if self.countdown.selection_invalid() {
+            self.modals.show_notification("This timer has no duration. Press d to delete it.", None).ok();
+        } else if !self.countdown.entries.is_empty() {
+            self.no_timer_hint();
         }
     }
 
     fn handle_key_countdown_run(&mut self, key: char) {
-        let now = self.now_ms();
         match key {
             '\r' | '\n' => {
-                // Determine action without holding borrow across pump calls
-                let action = if let Some(timer) = &mut self.countdown.active_timer {
-                    match timer.state {
-                        TimerState::Running => {
-                            timer.pause(now);
-                            Some(false) // need to stop pump
-                        }
-                        TimerState::Paused => {
-                            timer.start(now);
-                            Some(true) // need to start pump
-                        }
-                        _ => None,
-                    }
-                } else {
-                    None
-                };
-                match action {
-                    Some(true) => self.start_pump(1000),
-                    Some(false) => self.stop_pump(),
-                    None => {}
-                }
+                self.toggle_countdown();
                 self.redraw();
             }
             'r' => {
                 // Reset to original duration
-                self.countdown.start_selected();
-                self.stop_pump();
+                self.reset_countdown();
+                self.redraw();
+            }
+            'b' => {
+                self.countdown.bar_only = !self.countdown.bar_only;
+                self.redraw();
+            }
+            '+' | '=' => {
+                if let Some(timer) = self.countdown.active_timer_mut() {
+                    timer.extend_ms(ADJUST_STEP_MS);
+                    self.redraw();
+                }
+            }
+            '-' => {
+                let now = self.now_ms();
+                if let Some(timer) = self.countdown.active_timer_mut() {
+                    timer.shrink_ms(ADJUST_STEP_MS, now);
+                    self.redraw();
+                }
+            }
+            'x' => {
+                self.request_stop_countdown();
                 self.redraw();
             }
             'q' => {
-                self.countdown.stop_active();
-                self.stop_pump();
+                // Leave the timer running; the list shows it counting
                 self.mode = AppMode::CountdownList;
                 self.redraw();
             }
@@ -920,22 +1680,97 @@ impl TimersApp {
                 }
             }
             '↓' | 'j' => {
-                if self.settings_cursor < 3 {
+                if self.settings_cursor + 1 < SETTINGS_ROWS.len() {
                     self.settings_cursor += 1;
                     self.redraw();
                 }
             }
             '\r' | '\n' => {
-                match self.settings_cursor {
-                    0 => self.alert_config.vibration = !self.alert_config.vibration,
-                    1 => self.alert_config.notification = !self.alert_config.notification,
-                    2 => self.alert_config.audio = !self.alert_config.audio,
-                    3 => {
+                match SETTINGS_ROWS[self.settings_cursor] {
+                    SettingsRow::Vibration => self.alert_config.vibration = !self.alert_config.vibration,
+                    SettingsRow::Notification => self.alert_config.notification = !self.alert_config.notification,
+                    SettingsRow::Audio => self.alert_config.audio = !self.alert_config.audio,
+                    SettingsRow::Led => self.alert_config.led = !self.alert_config.led,
+                    SettingsRow::VibePattern => self.alert_config.vibe = self.alert_config.vibe.next(),
+                    SettingsRow::SoftVibe => self.alert_config.soft_start_vibe = !self.alert_config.soft_start_vibe,
+                    SettingsRow::Escalate => self.alert_config.escalate = !self.alert_config.escalate,
+                    SettingsRow::ConfigurePomodoro => {
                         // Configure Pomodoro durations
                         self.configure_pomodoro();
                         return;
                     }
-                    _ => {}
+                    SettingsRow::PomodoroSequence => {
+                        self.configure_pomodoro_sequence();
+                        return;
+                    }
+                    SettingsRow::ExtendBreaks => {
+                        self.pomodoro.auto_extend_break = !self.pomodoro.auto_extend_break;
+                        self.storage.save_pomodoro_settings(&self.pomodoro.settings());
+                        self.redraw();
+                        return;
+                    }
+                    SettingsRow::FlexibleBreak => {
+                        self.pomodoro.flexible_break = !self.pomodoro.flexible_break;
+                        self.storage.save_pomodoro_settings(&self.pomodoro.settings());
+                        self.redraw();
+                        return;
+                    }
+                    SettingsRow::AutoStart => {
+                        self.pomodoro.auto_start = !self.pomodoro.auto_start;
+                        self.storage.save_pomodoro_settings(&self.pomodoro.settings());
+                        self.redraw();
+                        return;
+                    }
+                    SettingsRow::SetComplete => {
+                        self.pomodoro.on_set_complete = self.pomodoro.on_set_complete.next();
+                        self.storage.save_pomodoro_settings(&self.pomodoro.settings());
+                        self.redraw();
+                        return;
+                    }
+                    SettingsRow::StopwatchReminder => {
+                        self.configure_stopwatch_reminder();
+                        return;
+                    }
+                    SettingsRow::LapAlert => {
+                        self.configure_lap_alert();
+                        return;
+                    }
+                    SettingsRow::LapOnStop => {
+                        self.stopwatch.lap_on_stop = !self.stopwatch.lap_on_stop;
+                        self.storage.save_stopwatch_settings(&self.stopwatch.settings());
+                        self.redraw();
+                        return;
+                    }
+                    SettingsRow::Snooze => {
+                        self.configure_snooze();
+                        return;
+                    }
+                    SettingsRow::QuietHours => {
+                        self.configure_quiet_hours();
+                        return;
+                    }
+                    SettingsRow::DefaultCountdown => {
+                        self.configure_default_countdown();
+                        return;
+                    }
+                    SettingsRow::ShowElapsed => {
+                        self.countdown.show_elapsed = !self.countdown.show_elapsed;
+                        self.storage.save_countdown_settings(&self.countdown.settings());
+                        self.redraw();
+                        return;
+                    }
+                    SettingsRow::ResumePrompt => {
+                        self.resume_prompt = !self.resume_prompt;
+                        self.storage.save_resume_prompt(self.resume_prompt);
+                        self.redraw();
+                        return;
+                    }
+                    SettingsRow::BackgroundPause => {
+                        self.background_pause = !self.background_pause;
+                        self.storage.save_background_pause(self.background_pause);
+                        self.redraw();
+                        return;
+                    }
                 }
                 self.storage.save_alert_config(&self.alert_config);
                 self.redraw();
@@ -949,59 +1784,255 @@ impl TimersApp {
         }
     }
 
-    fn configure_pomodoro(&mut self) {
-        // Work duration
-        let work_mins = match self.modals.alert_builder("Work duration (mins):")
-            .field(Some(format!("{}", self.pomodoro.work_duration_ms / 60000)), None)
+    fn settings_items(&self) -> Vec<(&'static str, String)> {
+        let on_off = |enabled: bool| if enabled { "[ON]" } else { "[OFF]" }.to_string();
+        SETTINGS_ROWS.iter().map(|row| match row {
+            SettingsRow::Vibration => ("Vibration", on_off(self.alert_config.vibration)),
+            SettingsRow::Notification => ("Notification", on_off(self.alert_config.notification)),
+            SettingsRow::Audio => ("Audio", on_off(self.alert_config.audio)),
+            SettingsRow::Led => ("LED flash", on_off(self.alert_config.led)),
+            SettingsRow::VibePattern => ("Vibration pattern", format!("[{}]", self.alert_config.vibe.label())),
+            SettingsRow::SoftVibe => ("Soft vibration", on_off(self.alert_config.soft_start_vibe)),
+            SettingsRow::Escalate => ("Repeat until seen", on_off(self.alert_config.escalate)),
+            SettingsRow::Snooze => {
+                let value = match self.alert_config.snooze_ms {
+                    0 => "[OFF]".to_string(),
+                    ms => format!("[{}]", format_ms(ms)),
+                };
+                ("Snooze", value)
+            }
+            SettingsRow::QuietHours => {
+                let config = &self.alert_config;
+                let value = if config.quiet_start_min == config.quiet_end_min {
+                    "[OFF]".to_string()
+                } else {
+                    format!("[{}]", format_quiet_hours(config.quiet_start_min, config.quiet_end_min))
+                };
+                ("Quiet hours", value)
+            }
+            SettingsRow::ConfigurePomodoro => ("Configure Pomodoro...", String::new()),
+            SettingsRow::PomodoroSequence => {
+                let value = match self.pomodoro.sequence.as_slice() {
+                    [] => "[OFF]".to_string(),
+                    sequence => format!("[{}]", format_sequence(sequence)),
+                };
+                ("Sequence", value)
+            }
+            SettingsRow::ExtendBreaks => ("Extend breaks", on_off(self.pomodoro.auto_extend_break)),
+            SettingsRow::FlexibleBreak => ("Flexible breaks", on_off(self.pomodoro.flexible_break)),
+            SettingsRow::AutoStart => ("Auto-start phases", on_off(self.pomodoro.auto_start)),
+            SettingsRow::SetComplete => ("End of set", format!("[{}]", self.pomodoro.on_set_complete.label())),
+            SettingsRow::StopwatchReminder => {
+                let interval = self.stopwatch.reminder_interval_ms;
+                let value = if interval == 0 {
+                    "[OFF]".to_string()
+                } else {
+                    format!("[{}m]", interval / 60_000)
+                };
+                ("SW reminder", value)
+            }
+            SettingsRow::LapAlert => {
+                let value = match self.stopwatch.lap_alert_ms {
+                    0 => "[OFF]".to_string(),
+                    ms => format!("[{}]", format_ms(ms)),
+                };
+                ("Lap alert", value)
+            }
+            SettingsRow::LapOnStop => ("Lap on stop", on_off(self.stopwatch.lap_on_stop)),
+            SettingsRow::DefaultCountdown => {
+                ("New countdown", format!("[{}]", format_hms(self.countdown.default_countdown_ms)))
+            }
+            SettingsRow::ShowElapsed => ("Show elapsed", on_off(self.countdown.show_elapsed)),
+            SettingsRow::ResumePrompt => ("Resume prompt", on_off(self.resume_prompt)),
+            SettingsRow::BackgroundPause => ("Pause in background", on_off(self.background_pause)),
+        }).collect()
+    }
+
+    fn handle_key_stats(&mut self, key: char) {
+        if key == 'q' {
+            self.mode = AppMode::ModeSelect;
+            self.redraw();
+        }
+    }
+
+    fn handle_key_interval(&mut self, key: char) {
+        match key {
+            '\r' | '\n' => {
+                self.toggle_interval();
+                self.redraw();
+            }
+            'r' => {
+                self.reset_interval();
+                self.redraw();
+            }
+            'c' => self.configure_interval(),
+            'q' => {
+                if self.interval.is_running() {
+                    self.interval.timer.pause(self.now_ms());
+                    self.log_event(EventKind::Pause, EventSource::Interval);
+                }
+                self.mode = AppMode::ModeSelect;
+                self.stop_pump_if_idle();
+                self.redraw();
+            }
+            _ => {}
+        }
+    }
+
+    /// Start or pause the current stretch. A finished workout starts over.
+    fn toggle_interval(&mut self) {
+        if self.interval.finished {
+            self.interval.reset();
+        }
+        let now = self.now_ms();
+        match self.interval.timer.state {
+            TimerState::Stopped | TimerState::Paused if !self.check_timer_cap() => return,
+            TimerState::Stopped => self.interval.timer.start(now),
+            TimerState::Paused => self.interval.timer.resume(now),
+            TimerState::Running => {
+                self.interval.timer.pause(now);
+                self.log_event(EventKind::Pause, EventSource::Interval);
+                self.stop_pump_if_idle();
+                return;
+            }
+            TimerState::Expired => return,
+        }
+        self.log_event(EventKind::Start, EventSource::Interval);
+        self.start_pump_for_mode();
+    }
+
+    fn reset_interval(&mut self) {
+        self.interval.reset();
+        self.log_event(EventKind::Reset, EventSource::Interval);
+        self.stop_pump_if_idle();
+    }
+
+    fn configure_interval(&mut self) {
+        let work_ms = match self.modals.alert_builder("Work time (MM:SS):")
+            .field(Some(format_ms(self.interval.work_ms)), None)
             .build()
         {
-            Ok(response) => {
-                let payload = response.first();
-                payload.content.trim().parse::<u64>().unwrap_or(25)
-            }
+            Ok(response) => parse_hms(&response.first().content).unwrap_or(0),
             Err(_) => return,
         };
-
-        // Short break duration
-        let short_mins = match self.modals.alert_builder("Short break (mins):")
-            .field(Some(format!("{}", self.pomodoro.short_break_ms / 60000)), None)
+        let rest_ms = match self.modals.alert_builder("Rest time (MM:SS, 0 = none):")
+            .field(Some(format_ms(self.interval.rest_ms)), None)
             .build()
         {
-            Ok(response) => {
-                let payload = response.first();
-                payload.content.trim().parse::<u64>().unwrap_or(5)
-            }
+            Ok(response) => parse_hms(&response.first().content),
             Err(_) => return,
         };
-
-        // Long break duration
-        let long_mins = match self.modals.alert_builder("Long break (mins):")
-            .field(Some(format!("{}", self.pomodoro.long_break_ms / 60000)), None)
+        let rounds = match self.modals.alert_builder("Rounds:")
+            .field(Some(format!("{}", self.interval.rounds)), None)
             .build()
         {
-            Ok(response) => {
-                let payload = response.first();
-                payload.content.trim().parse::<u64>().unwrap_or(15)
-            }
+            Ok(response) => response.first().content.trim().parse::<u8>().unwrap_or(0),
             Err(_) => return,
         };
 
+        let settings = rest_ms.map(|rest_ms| IntervalSettings { work_ms, rest_ms, rounds });
+        match settings {
+            Some(settings) if self.interval.set_settings(settings) => {
+                self.storage.save_interval_settings(&self.interval.settings());
+                self.stop_pump_if_idle();
+            }
+            _ => {
+                self.modals.show_notification("Invalid interval settings.", None).ok();
+            }
+        }
+        self.redraw();
+    }
+
+    fn handle_key_clock(&mut self, key: char) {
+        if key == 'q' {
+            self.mode = AppMode::ModeSelect;
+            self.stop_pump_if_idle();
+            self.redraw();
+        }
+    }
+
+    fn handle_key_event_log(&mut self, key: char) {
+        if key == 'q' {
+            self.mode = AppMode::ModeSelect;
+            self.redraw();
+        }
+    }
+
+    fn log_event(&mut self, kind: EventKind, source: EventSource) {
+        let now = self.now_ms();
+        self.events.push(kind, source, now);
+        let status = match source {
+            EventSource::Pomodoro => self.pomodoro.status_line(now),
+            EventSource::Stopwatch => self.stopwatch.status_line(now),
+            EventSource::Countdown => self.countdown.status_line(now),
+            EventSource::Interval => self.interval.status_line(now),
+        };
+        log::debug!("{} {}: {}", source.label(), kind.label(), status);
+    }
+
+    /// Store the stopwatch run as it stands, e.g. on quit or losing focus,
+    /// so it comes back (paused) with its laps.
+    fn save_stopwatch_run(&self) {
+        self.storage.save_stopwatch(&self.stopwatch, self.now_ms());
+    }
+
+    /// Count the finished session in the lifetime stats, then clear the stopwatch.
+    fn reset_stopwatch(&mut self) {
+        let elapsed = self.stopwatch.timer.elapsed_ms(self.now_ms());
+        if self.stopwatch_stats.record_session(elapsed, self.stopwatch.laps.len()) {
+            self.storage.save_stopwatch_stats(&self.stopwatch_stats);
+        }
+        self.stopwatch.reset();
+        self.storage.save_stopwatch(&self.stopwatch, self.now_ms());
+        self.log_event(EventKind::Reset, EventSource::Stopwatch);
+    }
+
+    /// Ask for work, short break and long break minutes and the cycle
+    /// count. Out-of-range input is reported and nothing is changed.
+    fn configure_pomodoro(&mut self) {
+        let prompts = [
+            ("Work duration (mins):", self.pomodoro.work_duration_ms),
+            ("Short break (mins):", self.pomodoro.short_break_ms),
+            ("Long break (mins):", self.pomodoro.long_break_ms),
+        ];
+        let mut durations_ms = [0u64; 3];
+        for (slot, (title, current_ms)) in durations_ms.iter_mut().zip(prompts) {
+            let text = match self.modals.alert_builder(title)
+                .field(Some(format!("{}", current_ms / 60_000)), None)
+                .build()
+            {
+                Ok(response) => response.first().content.clone(),
+                Err(_) => return,
+            };
+            match parse_minutes(&text) {
+                Some(mins) => *slot = mins * 60 * 1000,
+                None => {
+                    let msg = format!("Durations must be 1-{} minutes.", MAX_PHASE_MINS);
+                    self.modals.show_notification(&msg, None).ok();
+                    self.redraw();
+                    return;
+                }
+            }
+        }
+        let [work_ms, short_ms, long_ms] = durations_ms;
+
         // Cycles before long break
         let cycles = match self.modals.alert_builder("Cycles before long break:")
             .field(Some(format!("{}", self.pomodoro.cycles_before_long)), None)
             .build()
         {
-            Ok(response) => {
-                let payload = response.first();
-                payload.content.trim().parse::<u8>().unwrap_or(4)
-            }
+            Ok(response) => response.first().content.trim().parse::<u8>().ok(),
             Err(_) => return,
         };
-
-        // Apply and save settings
-        let work_ms = work_mins * 60 * 1000;
-        let short_ms = short_mins * 60 * 1000;
-        let long_ms = long_mins * 60 * 1000;
+        let cycles = match cycles.filter(|c| (1..=MAX_CYCLES).contains(c)) {
+            Some(cycles) => cycles,
+            None => {
+                let msg = format!("Cycles must be 1-{}.", MAX_CYCLES);
+                self.modals.show_notification(&msg, None).ok();
+                self.redraw();
+                return;
+            }
+        };
 
         self.pomodoro.work_duration_ms = work_ms;
         self.pomodoro.short_break_ms = short_ms;
@@ -1009,7 +2040,218 @@ impl TimersApp {
         self.pomodoro.cycles_before_long = cycles;
         self.pomodoro.reset();
 
-        self.storage.save_pomodoro_settings(work_ms, short_ms, long_ms, cycles);
+        self.storage.save_pomodoro_settings(&self.pomodoro.settings());
+        self.redraw();
+    }
+
+    fn configure_stopwatch_reminder(&mut self) {
+        let mins = match self.modals.alert_builder("Stopwatch reminder every (mins, 0=off):")
+            .field(Some(format!("{}", self.stopwatch.reminder_interval_ms / 60_000)), None)
+            .build()
+        {
+            Ok(response) => {
+                let payload = response.first();
+                match payload.content.trim().parse::<u64>() {
+                    Ok(mins) => mins,
+                    Err(_) => return,
+                }
+            }
+            Err(_) => return,
+        };
+
+        let interval_ms = mins * 60 * 1000;
+        let elapsed = self.stopwatch.timer.elapsed_ms(self.now_ms());
+        self.stopwatch.set_reminder_interval(interval_ms, elapsed);
+        self.storage.save_stopwatch_settings(&self.stopwatch.settings());
+        self.redraw();
+    }
+
+    fn configure_lap_alert(&mut self) {
+        let lap_alert_ms = match self.modals.alert_builder("Alert if no lap for (MM:SS, 0=off):")
+            .field(Some(format_ms(self.stopwatch.lap_alert_ms)), None)
+            .build()
+        {
+            Ok(response) => match parse_hms(&response.first().content) {
+                Some(ms) => ms,
+                None => {
+                    self.modals.show_notification("Invalid duration.", None).ok();
+                    self.redraw();
+                    return;
+                }
+            },
+            Err(_) => return,
+        };
+
+        self.stopwatch.set_lap_alert(lap_alert_ms, self.now_ms());
+        self.storage.save_stopwatch_settings(&self.stopwatch.settings());
+        self.redraw();
+    }
+
+    /// Record a lap and alert when it completes the lap goal.
+    fn lap_stopwatch(&mut self) {
+        let now = self.now_ms();
+        let goal_reached = self.stopwatch.record_lap(now);
+        self.storage.save_stopwatch(&self.stopwatch, now);
+        if goal_reached {
+            self.alert_lap_goal();
+        }
+    }
+
+    fn alert_lap_goal(&mut self) {
+        self.log_event(EventKind::Alert, EventSource::Stopwatch);
+        let msg = format!("Lap goal reached: {} laps!", self.stopwatch.laps.len());
+        fire_alert(&self.alert_config, &self.llio, &self.tt, &self.modals, &msg, self.minute_of_day());
+    }
+
+    fn configure_lap_goal(&mut self) {
+        let current = self.stopwatch.lap_goal
+            .map(|g| format!("{}", g))
+            .unwrap_or_default();
+        let input = match self.modals.alert_builder("Lap goal (blank=none):")
+            .field(Some(current), None)
+            .build()
+        {
+            Ok(response) => response.first().content.trim().to_string(),
+            Err(_) => return,
+        };
+
+        if input.is_empty() {
+            self.stopwatch.set_lap_goal(None);
+        } else {
+            match input.parse::<usize>() {
+                Ok(n) => self.stopwatch.set_lap_goal(Some(n)),
+                Err(_) => {
+                    self.modals.show_notification("Invalid lap goal.", None).ok();
+                }
+            }
+        }
+        self.redraw();
+    }
+
+    fn configure_stopwatch_target(&mut self) {
+        let current = self.stopwatch.target_ms.map(format_hms).unwrap_or_default();
+        let input = match self.modals.alert_builder("Target time HH:MM:SS (blank=none):")
+            .field(Some(current), None)
+            .build()
+        {
+            Ok(response) => response.first().content.trim().to_string(),
+            Err(_) => return,
+        };
+
+        if input.is_empty() {
+            self.stopwatch.set_target(None, self.now_ms());
+        } else {
+            match parse_hms(&input) {
+                Some(ms) if ms > 0 => self.stopwatch.set_target(Some(ms), self.now_ms()),
+                _ => {
+                    self.modals.show_notification("Invalid target time.", None).ok();
+                }
+            }
+        }
+        self.redraw();
+    }
+
+    fn configure_pomodoro_task(&mut self) {
+        let current = self.pomodoro.task.clone().unwrap_or_default();
+        let input = match self.modals.alert_builder("Task (blank=none):")
+            .field(Some(current), None)
+            .build()
+        {
+            Ok(response) => response.first().content.to_string(),
+            Err(_) => return,
+        };
+
+        self.pomodoro.set_task(&input);
+        self.storage.save_pomodoro_task(self.pomodoro.task.as_deref());
+        self.redraw();
+    }
+
+    fn configure_default_countdown(&mut self) {
+        let duration_ms = match self.modals.alert_builder("New countdown default (HH:MM:SS):")
+            .field(Some(format_hms(self.countdown.default_countdown_ms)), None)
+            .build()
+        {
+            Ok(response) => parse_hms(&response.first().content).unwrap_or(0),
+            Err(_) => return,
+        };
+
+        if self.countdown.set_default_countdown(duration_ms) {
+            self.storage.save_countdown_settings(&self.countdown.settings());
+        } else {
+            self.modals.show_notification("Invalid duration.", None).ok();
+        }
+        self.redraw();
+    }
+
+    fn configure_snooze(&mut self) {
+        let snooze_ms = match self.modals.alert_builder("Snooze length (HH:MM:SS, 0 = off):")
+            .field(Some(format_hms(self.alert_config.snooze_ms)), None)
+            .build()
+        {
+            Ok(response) => parse_hms(&response.first().content).unwrap_or(0),
+            Err(_) => return,
+        };
+
+        if self.alert_config.set_snooze_ms(snooze_ms) {
+            self.storage.save_alert_config(&self.alert_config);
+        } else {
+            self.modals.show_notification("Snooze can be at most an hour.", None).ok();
+        }
+        self.redraw();
+    }
+
+    /// Ask for a custom phase sequence as work/break pairs. Blank goes back
+    /// to the classic pattern; bad input is reported and nothing changes.
+    fn configure_pomodoro_sequence(&mut self) {
+        let text = match self.modals.alert_builder("Sequence, work/break mins (e.g. 50/10 50/30; blank = classic):")
+            .field(Some(format_sequence(&self.pomodoro.sequence)), None)
+            .build()
+        {
+            Ok(response) => response.first().content.to_string(),
+            Err(_) => return,
+        };
+
+        match parse_sequence(&text) {
+            Some(sequence) => {
+                self.pomodoro.set_sequence(sequence);
+                self.storage.save_pomodoro_sequence(&self.pomodoro.sequence);
+            }
+            None => {
+                let msg = format!(
+                    "Use up to {} work/break pairs like 50/10, each 1-{} minutes.",
+                    MAX_SEQUENCE_PAIRS, MAX_PHASE_MINS
+                );
+                self.modals.show_notification(&msg, None).ok();
+            }
+        }
+        self.redraw();
+    }
+
+    fn configure_quiet_hours(&mut self) {
+        let config = &self.alert_config;
+        let current = if config.quiet_start_min == config.quiet_end_min {
+            String::new()
+        } else {
+            format_quiet_hours(config.quiet_start_min, config.quiet_end_min)
+        };
+        let text = match self.modals.alert_builder("Quiet hours (HH:MM-HH:MM, blank = off):")
+            .field(Some(current), None)
+            .build()
+        {
+            Ok(response) => response.first().content.to_string(),
+            Err(_) => return,
+        };
+
+        match parse_quiet_hours(&text) {
+            Some((start, end)) => {
+                self.alert_config.quiet_start_min = start;
+                self.alert_config.quiet_end_min = end;
+                self.storage.save_alert_config(&self.alert_config);
+            }
+            None => {
+                self.modals.show_notification("Use HH:MM-HH:MM, e.g. 22:00-06:00.", None).ok();
+            }
+        }
         self.redraw();
     }
 
@@ -1024,51 +2266,166 @@ impl TimersApp {
                 if payload.content.is_empty() {
                     return;
                 }
-                let mut name = payload.content.clone();
-                name.truncate(20);
-                name
+                payload.content.clone()
             }
             Err(_) => return,
         };
 
-        // Use modals for duration input (in seconds)
-        let duration_ms = match self.modals.alert_builder("Duration (MM:SS):")
-            .field(Some("05:00".to_string()), None)
+        let duration_ms = match self.modals.alert_builder("Duration (HH:MM:SS):")
+            .field(Some(format_hms(self.countdown.default_countdown_ms)), None)
             .build()
         {
-            Ok(response) => {
-                let payload = response.first();
-                parse_mmss(&payload.content)
-            }
+            Ok(response) => parse_hms(&response.first().content),
             Err(_) => return,
         };
 
-        if duration_ms > 0 {
-            self.countdown.add_entry(name, duration_ms);
-            self.storage.save_countdowns(&self.countdown.entries);
+        // "00:00" would fire the moment it started, so it isn't added
+        match duration_ms {
+            Some(ms) if ms > 0 => {
+                self.countdown.add_entry(name, ms);
+                self.save_countdowns();
+            }
+            _ => {
+                self.modals.show_notification("Invalid duration.", None).ok();
+            }
         }
         self.redraw();
     }
-}
 
-/// Parse "MM:SS" format into milliseconds
-fn parse_mmss(s: &str) -> u64 {
-    let parts: Vec<&str> = s.split(':').collect();
-    match parts.len() {
-        1 => {
-            // Just seconds
-            if let Ok(secs) = parts[0].trim().parse::<u64>() {
-                secs * 1000
-            } else {
-                0
+    fn no_timer_hint(&self) {
+        self.modals.show_notification("No timer selected.", None).ok();
+    }
+
+    /// Pause/resume the active countdown, keeping the pump in step.
+    fn toggle_countdown(&mut self) {
+        let paused = self.countdown.active_timer().map(|t| t.state) == Some(TimerState::Paused);
+        if paused && !self.check_timer_cap() {
+            return;
+        }
+        match self.countdown.toggle_active(self.now_ms()) {
+            Some(true) => {
+                self.log_event(EventKind::Start, EventSource::Countdown);
+                self.start_pump_for_mode();
+            }
+            Some(false) => {
+                self.log_event(EventKind::Pause, EventSource::Countdown);
+                self.stop_pump_if_idle();
+            }
+            None => {
+                if self.countdown.active_timer().is_none() {
+                    self.no_timer_hint();
+                }
+            }
+        }
+    }
+
+    /// Reset the active countdown to its full duration.
+    fn reset_countdown(&mut self) {
+        if self.countdown.reset_active() {
+            self.log_event(EventKind::Reset, EventSource::Countdown);
+            self.stop_pump_if_idle();
+        } else {
+            self.no_timer_hint();
+        }
+    }
+
+    /// Stop the shown countdown, asking first if it's running.
+    fn request_stop_countdown(&mut self) {
+        if self.countdown.active_timer().is_some_and(|t| t.state.is_active()) {
+            self.confirm_exit = true;
+        } else {
+            self.stop_countdown();
+        }
+    }
+
+    /// Drop the shown countdown and go back to the list.
+    fn stop_countdown(&mut self) {
+        self.countdown.stop_active();
+        self.stop_pump_if_idle();
+        self.mode = AppMode::CountdownList;
+    }
+
+    /// Timers and the chains that refer to them are saved together, since
+    /// list edits renumber both.
+    fn save_countdowns(&self) {
+        self.storage.save_countdowns(&self.countdown.entries);
+        self.storage.save_countdown_chains(&self.countdown.chains);
+    }
+
+    /// Ask for a chain of list numbers (prefilled with the last one used),
+    /// save it and start its first step.
+    fn run_countdown_chain(&mut self) {
+        let current = self.countdown.chains.last()
+            .map(|c| format_chain(&c.steps))
+            .unwrap_or_default();
+        let input = match self.modals.alert_builder("Chain timers # in order (e.g. 1 2 1 2):")
+            .field(Some(current), None)
+            .build()
+        {
+            Ok(response) => response.first().content.trim().to_string(),
+            Err(_) => return,
+        };
+
+        let steps = match parse_chain(&input, self.countdown.entries.len()) {
+            Some(steps) => steps,
+            None => {
+                self.modals.show_notification("Use timer numbers from the list.", None).ok();
+                self.redraw();
+                return;
             }
+        };
+        if !self.check_timer_cap() {
+            return;
+        }
+        let started = self.countdown.save_chain(steps)
+            .is_some_and(|idx| self.countdown.start_chain(idx));
+        self.save_countdowns();
+        if started {
+            let now = self.now_ms();
+            if let Some(timer) = self.countdown.active_timer_mut() {
+                timer.start(now);
+            }
+            self.log_event(EventKind::Start, EventSource::Countdown);
+            self.mode = AppMode::CountdownRun;
+            self.start_pump_for_mode();
+        } else {
+            self.modals.show_notification("None of those timers can run.", None).ok();
         }
-        2 => {
-            let mins = parts[0].trim().parse::<u64>().unwrap_or(0);
-            let secs = parts[1].trim().parse::<u64>().unwrap_or(0);
-            (mins * 60 + secs) * 1000
+        self.redraw();
+    }
+
+    fn link_countdown(&mut self) {
+        let from = self.countdown.cursor;
+        let current = self.countdown.entries[from].on_expiry_start
+            .map(|i| format!("{}", i + 1))
+            .unwrap_or_default();
+        let input = match self.modals.alert_builder("On expiry start timer # (blank=none):")
+            .field(Some(current), None)
+            .build()
+        {
+            Ok(response) => response.first().content.trim().to_string(),
+            Err(_) => return,
+        };
+
+        let to = if input.is_empty() {
+            None
+        } else {
+            match input.parse::<usize>() {
+                Ok(n) if n >= 1 && n <= self.countdown.entries.len() => Some(n - 1),
+                _ => {
+                    self.modals.show_notification("No timer with that number.", None).ok();
+                    self.redraw();
+                    return;
+                }
+            }
+        };
+
+        if self.countdown.set_on_expiry_start(from, to) {
+            self.save_countdowns();
+        } else {
+            self.modals.show_notification("Can't link: timers would loop.", None).ok();
         }
-        _ => 0,
+        self.redraw();
     }
 }
 
@@ -1158,6 +2515,7 @@ fn main() -> ! {
                         app.handle_key(key);
                     }
                 }
+                app.acknowledge_expiries();
                 // Check if quit was requested
                 if app.should_quit {
                     break;
@@ -1168,42 +2526,38 @@ fn main() -> ! {
                 match new_state {
                     gam::FocusState::Background => {
                         app.allow_redraw = false;
-                        app.stop_pump();
+                        app.pause_for_background();
+                        app.save_stopwatch_run();
+                        // Keep the pump only to alert when a timer runs out
+                        app.stop_pump_if_idle();
+                        app.retune_pump();
                     }
                     gam::FocusState::Foreground => {
                         app.allow_redraw = true;
+                        // The timezone may have been changed in the meantime
+                        app.tz_offset_s = app.storage.load_tz_offset_s();
+                        app.resume_from_background();
                         // Restart pump if a timer is running
-                        match app.mode {
-                            AppMode::Stopwatch if app.stopwatch.timer.state == TimerState::Running => {
-                                app.start_pump(100);
-                            }
-                            AppMode::Pomodoro if app.pomodoro.timer.state == TimerState::Running => {
-                                app.start_pump(1000);
-                            }
-                            AppMode::CountdownRun => {
-                                let should_pump = app.countdown.active_timer.as_ref()
-                                    .map(|t| t.state == TimerState::Running)
-                                    .unwrap_or(false);
-                                if should_pump {
-                                    app.start_pump(1000);
-                                }
-                            }
-                            _ => {}
-                        }
+                        app.start_pump_for_mode();
                         app.redraw();
+                        app.acknowledge_expiries();
                     }
                 }
             }),
             Some(AppOp::Pump) => {
                 app.handle_pump();
+                if app.should_quit {
+                    break;
+                }
             }
             Some(AppOp::Quit) => break,
             _ => log::error!("unknown opcode: {:?}", msg),
         }
     }
 
-    // Clean up
+    // Clean up; the stopwatch run is kept for the next launch
     app.stop_pump();
+    app.save_stopwatch_run();
     xous::send_message(app.pump_conn, xous::Message::new_scalar(2, 0, 0, 0, 0)).ok();
     xns.unregister_server(sid).unwrap();
     xous::destroy_server(sid).unwrap();
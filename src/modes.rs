@@ -0,0 +1,260 @@
+use timer_core::TimerState;
+
+/// Timer modes listed on the mode select screen.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ModeEntry {
+    Pomodoro,
+    Stopwatch,
+    Countdown,
+    Interval,
+    Clock,
+}
+
+const DEFAULT_ORDER: [ModeEntry; 5] = [
+    ModeEntry::Pomodoro,
+    ModeEntry::Stopwatch,
+    ModeEntry::Countdown,
+    ModeEntry::Interval,
+    ModeEntry::Clock,
+];
+
+impl ModeEntry {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ModeEntry::Pomodoro => "Pomodoro",
+            ModeEntry::Stopwatch => "Stopwatch",
+            ModeEntry::Countdown => "Countdown",
+            ModeEntry::Interval => "Interval",
+            ModeEntry::Clock => "Clock",
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            ModeEntry::Pomodoro => 0,
+            ModeEntry::Stopwatch => 1,
+            ModeEntry::Countdown => 2,
+            ModeEntry::Clock => 3,
+            ModeEntry::Interval => 4,
+        }
+    }
+
+    fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            0 => Some(ModeEntry::Pomodoro),
+            1 => Some(ModeEntry::Stopwatch),
+            2 => Some(ModeEntry::Countdown),
+            3 => Some(ModeEntry::Clock),
+            4 => Some(ModeEntry::Interval),
+            _ => None,
+        }
+    }
+}
+
+/// Answer to the "resume or restart?" prompt on entering a mode.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ResumeChoice {
+    Resume,
+    Restart,
+}
+
+impl ResumeChoice {
+    /// Map the selected radio button label back to a choice. Anything
+    /// unrecognised resumes, which is what Enter would have done anyway.
+    pub fn from_selection(label: &str) -> Self {
+        if label.starts_with("Restart") {
+            ResumeChoice::Restart
+        } else {
+            ResumeChoice::Resume
+        }
+    }
+}
+
+/// Whether entering a mode should ask about its timer: only when the
+/// prompt is enabled and the timer was left paused.
+pub fn resume_prompt_needed(enabled: bool, state: TimerState) -> bool {
+    enabled && state == TimerState::Paused
+}
+
+/// Whether going to the background should pause the shown timer.
+pub fn background_pause_needed(enabled: bool, state: TimerState) -> bool {
+    enabled && state.is_active()
+}
+
+/// Whether coming back to the foreground should resume the shown timer:
+/// only if the app paused it on the way out and it is still paused. A
+/// timer the user paused themselves stays paused.
+pub fn auto_resume_needed(paused_by_system: bool, state: TimerState) -> bool {
+    paused_by_system && state == TimerState::Paused
+}
+
+/// List row picked by a number key: '1' is the first row, up to '9'.
+pub fn digit_to_index(c: char) -> Option<usize> {
+    match c {
+        '1'..='9' => Some(c as usize - '1' as usize),
+        _ => None,
+    }
+}
+
+/// User-defined ordering of the mode select list.
+pub struct ModeOrder {
+    order: Vec<ModeEntry>,
+}
+
+impl ModeOrder {
+    pub fn new() -> Self {
+        Self { order: DEFAULT_ORDER.to_vec() }
+    }
+
+    /// Rebuild an order from stored bytes. Unknown or duplicate ids are
+    /// dropped and any mode missing from the stored list is appended, so
+    /// the result always contains every mode exactly once.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut order = Vec::with_capacity(DEFAULT_ORDER.len());
+        for &b in bytes {
+            if let Some(entry) = ModeEntry::from_byte(b) {
+                if !order.contains(&entry) {
+                    order.push(entry);
+                }
+            }
+        }
+        for entry in DEFAULT_ORDER {
+            if !order.contains(&entry) {
+                order.push(entry);
+            }
+        }
+        Self { order }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.order.iter().map(|e| e.to_byte()).collect()
+    }
+
+    pub fn entries(&self) -> &[ModeEntry] {
+        &self.order
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Mode shown at the given cursor position.
+    pub fn get(&self, index: usize) -> Option<ModeEntry> {
+        self.order.get(index).copied()
+    }
+
+    /// Move the mode at `index` one slot up. Returns the new index.
+    pub fn move_up(&mut self, index: usize) -> usize {
+        if index == 0 || index >= self.order.len() {
+            return index;
+        }
+        self.order.swap(index, index - 1);
+        index - 1
+    }
+
+    /// Move the mode at `index` one slot down. Returns the new index.
+    pub fn move_down(&mut self, index: usize) -> usize {
+        if index + 1 >= self.order.len() {
+            return index;
+        }
+        self.order.swap(index, index + 1);
+        index + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digit_to_index() {
+        assert_eq!(digit_to_index('1'), Some(0));
+        assert_eq!(digit_to_index('3'), Some(2));
+        assert_eq!(digit_to_index('9'), Some(8));
+        assert_eq!(digit_to_index('0'), None);
+        assert_eq!(digit_to_index('a'), None);
+        assert_eq!(digit_to_index('↑'), None);
+    }
+
+    #[test]
+    fn test_default_order() {
+        let order = ModeOrder::new();
+        assert_eq!(order.get(0), Some(ModeEntry::Pomodoro));
+        assert_eq!(order.get(1), Some(ModeEntry::Stopwatch));
+        assert_eq!(order.get(2), Some(ModeEntry::Countdown));
+        assert_eq!(order.get(3), Some(ModeEntry::Interval));
+        assert_eq!(order.get(4), Some(ModeEntry::Clock));
+        assert_eq!(order.get(5), None);
+    }
+
+    #[test]
+    fn test_move_keeps_cursor_on_mode() {
+        let mut order = ModeOrder::new();
+        let idx = order.move_up(2);
+        assert_eq!(idx, 1);
+        assert_eq!(order.get(idx), Some(ModeEntry::Countdown));
+        let idx = order.move_up(idx);
+        assert_eq!(idx, 0);
+        assert_eq!(order.get(0), Some(ModeEntry::Countdown));
+        assert_eq!(order.get(1), Some(ModeEntry::Pomodoro));
+
+        // No-ops at the ends
+        assert_eq!(order.move_up(0), 0);
+        assert_eq!(order.move_down(4), 4);
+        assert_eq!(order.get(0), Some(ModeEntry::Countdown));
+    }
+
+    #[test]
+    fn test_resume_prompt_needed() {
+        assert!(resume_prompt_needed(true, TimerState::Paused));
+        assert!(!resume_prompt_needed(false, TimerState::Paused));
+        for state in [TimerState::Stopped, TimerState::Running, TimerState::Expired] {
+            assert!(!resume_prompt_needed(true, state));
+        }
+    }
+
+    #[test]
+    fn test_background_pause_and_auto_resume() {
+        assert!(background_pause_needed(true, TimerState::Running));
+        assert!(!background_pause_needed(false, TimerState::Running));
+        for state in [TimerState::Stopped, TimerState::Paused, TimerState::Expired] {
+            assert!(!background_pause_needed(true, state));
+        }
+
+        // Only a pause the app made is undone
+        assert!(auto_resume_needed(true, TimerState::Paused));
+        assert!(!auto_resume_needed(false, TimerState::Paused));
+        for state in [TimerState::Stopped, TimerState::Running, TimerState::Expired] {
+            assert!(!auto_resume_needed(true, state));
+        }
+    }
+
+    #[test]
+    fn test_resume_choice_from_selection() {
+        assert_eq!(ResumeChoice::from_selection("Restart"), ResumeChoice::Restart);
+        assert_eq!(ResumeChoice::from_selection("Resume (12:30)"), ResumeChoice::Resume);
+        assert_eq!(ResumeChoice::from_selection(""), ResumeChoice::Resume);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let mut order = ModeOrder::new();
+        order.move_down(0);
+        let restored = ModeOrder::from_bytes(&order.to_bytes());
+        assert_eq!(restored.entries(), order.entries());
+    }
+
+    #[test]
+    fn test_from_bytes_repairs_bad_data() {
+        // Duplicate, unknown id, and a missing mode
+        let order = ModeOrder::from_bytes(&[2, 2, 9]);
+        assert_eq!(
+            order.entries(),
+            &[ModeEntry::Countdown, ModeEntry::Pomodoro, ModeEntry::Stopwatch, ModeEntry::Interval, ModeEntry::Clock]
+        );
+        // Orders saved before newer modes existed get them at the end
+        assert_eq!(ModeOrder::from_bytes(&[1, 0, 2]).get(3), Some(ModeEntry::Interval));
+        assert_eq!(ModeOrder::from_bytes(&[3, 1, 0, 2]).get(4), Some(ModeEntry::Interval));
+        assert_eq!(ModeOrder::from_bytes(&[]).entries(), ModeOrder::new().entries());
+    }
+}
@@ -1,4 +1,6 @@
-use timer_core::TimerCore;
+use std::collections::VecDeque;
+
+use timer_core::{format_auto, TimerCore};
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum PomPhase {
@@ -7,6 +9,202 @@ pub enum PomPhase {
     LongBreak,
 }
 
+impl PomPhase {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PomPhase::Work => "Work",
+            PomPhase::ShortBreak => "Short Break",
+            PomPhase::LongBreak => "Long Break",
+        }
+    }
+}
+
+/// What happens when the long break ending a full set expires.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SetCompleteAction {
+    /// Start the next set's work phase right away.
+    Loop,
+    /// Stop on the next work phase and show "set complete".
+    Stop,
+    /// Quit the app.
+    Exit,
+}
+
+impl SetCompleteAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SetCompleteAction::Loop => "Loop",
+            SetCompleteAction::Stop => "Stop",
+            SetCompleteAction::Exit => "Exit",
+        }
+    }
+
+    /// Next option, for cycling through them in settings.
+    pub fn next(self) -> Self {
+        match self {
+            SetCompleteAction::Loop => SetCompleteAction::Stop,
+            SetCompleteAction::Stop => SetCompleteAction::Exit,
+            SetCompleteAction::Exit => SetCompleteAction::Loop,
+        }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        match self {
+            SetCompleteAction::Loop => 0,
+            SetCompleteAction::Stop => 1,
+            SetCompleteAction::Exit => 2,
+        }
+    }
+
+    /// Unknown values fall back to `Loop`.
+    pub fn from_byte(b: u8) -> Self {
+        match b {
+            1 => SetCompleteAction::Stop,
+            2 => SetCompleteAction::Exit,
+            _ => SetCompleteAction::Loop,
+        }
+    }
+}
+
+/// Longest task label kept, in characters.
+pub const MAX_TASK_CHARS: usize = 30;
+
+/// Phase transitions remembered for `previous_phase`.
+const HISTORY_LEN: usize = 8;
+
+/// Where the cycle stood before a transition, so it can be undone.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct PhaseSnapshot {
+    phase: PomPhase,
+    current_cycle: u8,
+    total_completed: u32,
+    stats: PomodoroStats,
+    step: usize,
+}
+
+/// Longest work or break phase the settings editor accepts, in minutes.
+pub const MAX_PHASE_MINS: u64 = 120;
+/// Most work sessions the editor accepts before a long break.
+pub const MAX_CYCLES: u8 = 12;
+
+/// A whole number of minutes from 1 to `MAX_PHASE_MINS`, e.g. " 25 ".
+pub fn parse_minutes(text: &str) -> Option<u64> {
+    text.trim().parse::<u64>().ok().filter(|mins| (1..=MAX_PHASE_MINS).contains(mins))
+}
+
+/// Most work/break pairs in a custom sequence.
+pub const MAX_SEQUENCE_PAIRS: usize = 8;
+
+/// A custom sequence from work/break pairs in minutes, e.g.
+/// "50/10 50/10 50/30". The last pair's break is the long break that ends
+/// the set; the others are short. Blank gives an empty sequence (the
+/// classic pattern). None if a pair is malformed, a length is outside
+/// 1-`MAX_PHASE_MINS`, or there are more than `MAX_SEQUENCE_PAIRS`.
+pub fn parse_sequence(text: &str) -> Option<Vec<(PomPhase, u64)>> {
+    let pairs: Vec<&str> = text.split_whitespace().collect();
+    if pairs.len() > MAX_SEQUENCE_PAIRS {
+        return None;
+    }
+    let mut sequence = Vec::with_capacity(pairs.len() * 2);
+    for (i, pair) in pairs.iter().enumerate() {
+        let (work, rest) = pair.split_once('/')?;
+        let rest_phase = if i + 1 == pairs.len() { PomPhase::LongBreak } else { PomPhase::ShortBreak };
+        sequence.push((PomPhase::Work, parse_minutes(work)? * 60 * 1000));
+        sequence.push((rest_phase, parse_minutes(rest)? * 60 * 1000));
+    }
+    Some(sequence)
+}
+
+/// A sequence as `parse_sequence` reads it: each work step starts a new
+/// pair, its breaks follow after a slash.
+pub fn format_sequence(sequence: &[(PomPhase, u64)]) -> String {
+    let mut text = String::new();
+    for (i, &(phase, duration_ms)) in sequence.iter().enumerate() {
+        let sep = match phase {
+            _ if i == 0 => "",
+            PomPhase::Work => " ",
+            PomPhase::ShortBreak | PomPhase::LongBreak => "/",
+        };
+        text.push_str(sep);
+        text.push_str(&(duration_ms / 60_000).to_string());
+    }
+    text
+}
+
+/// Break extensions allowed before the phase advances on its own.
+pub const MAX_BREAK_EXTENSIONS: u8 = 10;
+const BREAK_EXTENSION_MS: u64 = 60 * 1000;
+
+/// Lifetime pomodoro totals, persisted across launches.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct PomodoroStats {
+    pub total_sessions: u32,
+    pub total_focus_ms: u64,
+    /// Sessions completed on `day`.
+    pub today_sessions: u32,
+    /// Local day number (see `clock::day_number`) `today_sessions` counts.
+    pub day: u32,
+}
+
+impl PomodoroStats {
+    /// Account for a completed work phase of `work_ms`.
+    pub fn record_work(&mut self, work_ms: u64) {
+        self.total_sessions = self.total_sessions.saturating_add(1);
+        self.total_focus_ms = self.total_focus_ms.saturating_add(work_ms);
+    }
+
+    /// Count a session finished on `day` toward that day's total,
+    /// starting from zero when the date has moved on.
+    pub fn count_today(&mut self, day: u32) {
+        if day != self.day {
+            self.day = day;
+            self.today_sessions = 0;
+        }
+        self.today_sessions = self.today_sessions.saturating_add(1);
+    }
+
+    /// Sessions completed on `day`: none if the last one was on another day.
+    pub fn sessions_on(&self, day: u32) -> u32 {
+        if day == self.day {
+            self.today_sessions
+        } else {
+            0
+        }
+    }
+}
+
+/// Alert text for each phase transition, so it can be reworded or
+/// translated.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PomodoroMessages {
+    pub work_to_short: String,
+    pub work_to_long: String,
+    pub break_to_work: String,
+}
+
+impl Default for PomodoroMessages {
+    fn default() -> Self {
+        Self {
+            work_to_short: "Work done! Short break.".to_string(),
+            work_to_long: "Work done! Long break.".to_string(),
+            break_to_work: "Break over! Time to work.".to_string(),
+        }
+    }
+}
+
+/// Persisted pomodoro configuration.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PomodoroSettings {
+    pub work_ms: u64,
+    pub short_ms: u64,
+    pub long_ms: u64,
+    pub cycles: u8,
+    pub auto_extend_break: bool,
+    pub on_set_complete: SetCompleteAction,
+    pub flexible_break: bool,
+    pub auto_start: bool,
+}
+
 pub struct PomodoroState {
     pub timer: TimerCore,
     pub phase: PomPhase,
@@ -16,6 +214,32 @@ pub struct PomodoroState {
     pub cycles_before_long: u8,
     pub current_cycle: u8,
     pub total_completed: u32,
+    /// Lifetime totals; `total_completed` only counts this launch.
+    pub stats: PomodoroStats,
+    /// Keep an unacknowledged break going a minute at a time.
+    pub auto_extend_break: bool,
+    pub break_extensions: u8,
+    pub on_set_complete: SetCompleteAction,
+    /// A set just finished with `SetCompleteAction::Stop`.
+    pub set_complete: bool,
+    /// Breaks count up with no end until the user starts the next work
+    /// phase, instead of running for the configured length.
+    pub flexible_break: bool,
+    /// Actual length of the most recent flexible break.
+    pub last_break_ms: Option<u64>,
+    /// Start each phase as soon as the previous one ends. When off, the
+    /// new phase waits stopped for the user to press start.
+    pub auto_start: bool,
+    /// What the user is working on, e.g. "Write report".
+    pub task: Option<String>,
+    pub messages: PomodoroMessages,
+    /// Custom phase order, e.g. 50/10, 50/10, 50/30, walked in a loop.
+    /// Empty means the classic long-break-every-N-cycles pattern.
+    pub sequence: Vec<(PomPhase, u64)>,
+    /// Position in `sequence`.
+    step: usize,
+    /// Most recent transitions last, for `previous_phase`.
+    history: VecDeque<PhaseSnapshot>,
 }
 
 impl PomodoroState {
@@ -29,6 +253,19 @@ impl PomodoroState {
             cycles_before_long: 4,
             current_cycle: 0,
             total_completed: 0,
+            stats: PomodoroStats::default(),
+            auto_extend_break: false,
+            break_extensions: 0,
+            on_set_complete: SetCompleteAction::Loop,
+            set_complete: false,
+            flexible_break: false,
+            last_break_ms: None,
+            auto_start: true,
+            task: None,
+            messages: PomodoroMessages::default(),
+            sequence: Vec::new(),
+            step: 0,
+            history: VecDeque::new(),
         }
     }
 
@@ -42,24 +279,155 @@ impl PomodoroState {
             cycles_before_long: cycles,
             current_cycle: 0,
             total_completed: 0,
+            stats: PomodoroStats::default(),
+            auto_extend_break: false,
+            break_extensions: 0,
+            on_set_complete: SetCompleteAction::Loop,
+            set_complete: false,
+            flexible_break: false,
+            last_break_ms: None,
+            auto_start: true,
+            task: None,
+            messages: PomodoroMessages::default(),
+            sequence: Vec::new(),
+            step: 0,
+            history: VecDeque::new(),
         }
     }
 
+    /// Default settings running `sequence`; see `set_sequence`.
+    #[cfg(test)]
+    pub fn from_sequence(sequence: Vec<(PomPhase, u64)>) -> Self {
+        let mut state = Self::new();
+        state.set_sequence(sequence);
+        state
+    }
+
+    /// Run `sequence` in a loop instead of the classic pattern, starting
+    /// the set over. An empty sequence goes back to the classic pattern.
+    pub fn set_sequence(&mut self, sequence: Vec<(PomPhase, u64)>) {
+        self.sequence = sequence;
+        self.reset();
+    }
+
+    /// Work phases in a set: the work steps of the sequence, or the cycles
+    /// before a long break.
+    pub fn cycles_per_set(&self) -> u8 {
+        match self.sequence.iter().filter(|(phase, _)| *phase == PomPhase::Work).count() {
+            0 => self.cycles_before_long,
+            work_steps => work_steps.min(u8::MAX as usize) as u8,
+        }
+    }
+
+    /// Current step of the sequence and its length, counting from 1; None
+    /// without a sequence.
+    pub fn sequence_step(&self) -> Option<(usize, usize)> {
+        (!self.sequence.is_empty()).then(|| (self.step + 1, self.sequence.len()))
+    }
+
+    pub fn settings(&self) -> PomodoroSettings {
+        PomodoroSettings {
+            work_ms: self.work_duration_ms,
+            short_ms: self.short_break_ms,
+            long_ms: self.long_break_ms,
+            cycles: self.cycles_before_long,
+            auto_extend_break: self.auto_extend_break,
+            on_set_complete: self.on_set_complete,
+            flexible_break: self.flexible_break,
+            auto_start: self.auto_start,
+        }
+    }
+
+    /// Set the task label, trimmed and cut to `MAX_TASK_CHARS`. Blank
+    /// clears it.
+    pub fn set_task(&mut self, task: &str) {
+        let trimmed = task.trim();
+        self.task = if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed.chars().take(MAX_TASK_CHARS).collect())
+        };
+    }
+
+    pub fn is_break(&self) -> bool {
+        self.phase != PomPhase::Work
+    }
+
+    /// In a break that is counting up until the user ends it?
+    pub fn in_flexible_break(&self) -> bool {
+        self.is_break() && self.timer.target_ms().is_none()
+    }
+
+    /// End a flexible break: record how long it actually ran and move on
+    /// to the (stopped) work phase. Returns the break length, or None if
+    /// not in a flexible break.
+    pub fn end_flexible_break(&mut self, now_ms: u64) -> Option<u64> {
+        if !self.in_flexible_break() {
+            return None;
+        }
+        let length = self.timer.elapsed_ms(now_ms);
+        self.last_break_ms = Some(length);
+        self.advance_phase();
+        Some(length)
+    }
+
+    /// Fresh timer for `phase`: a countdown, except that breaks count up
+    /// when flexible breaks are on. With a sequence, the length comes from
+    /// the current step.
+    fn phase_timer(&self, phase: PomPhase) -> TimerCore {
+        if self.flexible_break && phase != PomPhase::Work {
+            TimerCore::new_stopwatch()
+        } else if let Some(&(_, duration_ms)) = self.sequence.get(self.step) {
+            TimerCore::new_countdown(duration_ms)
+        } else {
+            TimerCore::new_countdown(self.phase_duration_ms(phase))
+        }
+    }
+
+    /// Called when a break expires without being acknowledged. If auto-extend
+    /// is on and the cap isn't reached, re-arms the break for another minute
+    /// and returns true; otherwise the caller should advance the phase.
+    pub fn try_extend_break(&mut self) -> bool {
+        if !self.auto_extend_break || !self.is_break() || self.break_extensions >= MAX_BREAK_EXTENSIONS {
+            return false;
+        }
+        self.break_extensions += 1;
+        self.timer = TimerCore::new_countdown(BREAK_EXTENSION_MS);
+        true
+    }
+
     /// Transition to the next phase after timer expires.
     /// Returns the alert message to display.
-    pub fn advance_phase(&mut self) -> &'static str {
+    pub fn advance_phase(&mut self) -> &str {
+        self.remember_phase();
+        self.break_extensions = 0;
+        self.set_complete = false;
+        if !self.sequence.is_empty() {
+            if let Some(&(PomPhase::Work, work_ms)) = self.sequence.get(self.step) {
+                self.current_cycle += 1;
+                self.total_completed += 1;
+                self.stats.record_work(work_ms);
+            }
+            self.next_step();
+            return match self.phase {
+                PomPhase::Work => &self.messages.break_to_work,
+                PomPhase::ShortBreak => &self.messages.work_to_short,
+                PomPhase::LongBreak => &self.messages.work_to_long,
+            };
+        }
         match self.phase {
             PomPhase::Work => {
                 self.current_cycle += 1;
                 self.total_completed += 1;
+                self.stats.record_work(self.work_duration_ms);
                 if self.current_cycle >= self.cycles_before_long {
                     self.phase = PomPhase::LongBreak;
-                    self.timer = TimerCore::new_countdown(self.long_break_ms);
-                    "Work done! Long break."
+                    self.timer = self.phase_timer(PomPhase::LongBreak);
+                    &self.messages.work_to_long
                 } else {
                     self.phase = PomPhase::ShortBreak;
-                    self.timer = TimerCore::new_countdown(self.short_break_ms);
-                    "Work done! Short break."
+                    self.timer = self.phase_timer(PomPhase::ShortBreak);
+                    &self.messages.work_to_short
                 }
             }
             PomPhase::ShortBreak | PomPhase::LongBreak => {
@@ -67,40 +435,568 @@ impl PomodoroState {
                     self.current_cycle = 0;
                 }
                 self.phase = PomPhase::Work;
-                self.timer = TimerCore::new_countdown(self.work_duration_ms);
-                "Break over! Time to work."
+                self.timer = self.phase_timer(PomPhase::Work);
+                &self.messages.break_to_work
             }
         }
     }
 
+    /// Cut a break short and move to the (stopped) work phase. Unlike
+    /// `advance_phase` nothing is counted; skipping a long break still
+    /// finishes the set. Work can't be skipped.
+    pub fn skip_phase(&mut self) -> &'static str {
+        match self.phase {
+            PomPhase::Work => "Only breaks can be skipped.",
+            PomPhase::ShortBreak | PomPhase::LongBreak if !self.sequence.is_empty() => {
+                self.remember_phase();
+                self.break_extensions = 0;
+                self.set_complete = false;
+                self.next_step();
+                "Break skipped."
+            }
+            PomPhase::ShortBreak | PomPhase::LongBreak => {
+                self.remember_phase();
+                if self.phase == PomPhase::LongBreak {
+                    self.current_cycle = 0;
+                }
+                self.break_extensions = 0;
+                self.set_complete = false;
+                self.phase = PomPhase::Work;
+                self.timer = self.phase_timer(PomPhase::Work);
+                "Break skipped. Time to work."
+            }
+        }
+    }
+
+    /// Start the set over: back to a stopped first work phase. Session
+    /// counts and stats are kept.
     pub fn reset(&mut self) {
-        let duration = match self.phase {
-            PomPhase::Work => self.work_duration_ms,
-            PomPhase::ShortBreak => self.short_break_ms,
-            PomPhase::LongBreak => self.long_break_ms,
+        self.step = 0;
+        self.history.clear();
+        self.phase = self.sequence.first().map_or(PomPhase::Work, |&(phase, _)| phase);
+        self.current_cycle = 0;
+        self.timer = self.phase_timer(self.phase);
+        self.break_extensions = 0;
+        self.set_complete = false;
+    }
+
+    fn remember_phase(&mut self) {
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(PhaseSnapshot {
+            phase: self.phase,
+            current_cycle: self.current_cycle,
+            total_completed: self.total_completed,
+            stats: self.stats,
+            step: self.step,
+        });
+    }
+
+    /// Undo the last phase transition: back to the phase before it, with
+    /// a fresh stopped timer and the session counts it had then. Returns
+    /// false if there is nothing to undo (a reset clears the history).
+    pub fn previous_phase(&mut self) -> bool {
+        let Some(snapshot) = self.history.pop_back() else {
+            return false;
         };
-        self.timer = TimerCore::new_countdown(duration);
+        self.phase = snapshot.phase;
+        self.current_cycle = snapshot.current_cycle;
+        self.total_completed = snapshot.total_completed;
+        self.stats = snapshot.stats;
+        self.step = snapshot.step;
+        self.break_extensions = 0;
+        self.set_complete = false;
+        self.timer = self.phase_timer(self.phase);
+        true
     }
 
-    pub fn phase_label(&self) -> &'static str {
-        match self.phase {
-            PomPhase::Work => "Work",
-            PomPhase::ShortBreak => "Short Break",
-            PomPhase::LongBreak => "Long Break",
+    /// Move to the next sequence step, wrapping to the start (and a new
+    /// set) at the end.
+    fn next_step(&mut self) {
+        self.step = (self.step + 1) % self.sequence.len();
+        if self.step == 0 {
+            self.current_cycle = 0;
         }
+        self.phase = self.sequence[self.step].0;
+        self.timer = self.phase_timer(self.phase);
     }
 
-    pub fn progress_fraction(&self, now_ms: u64) -> f32 {
-        let target = match self.phase {
+    pub fn phase_label(&self) -> &'static str {
+        self.phase.label()
+    }
+
+    fn phase_duration_ms(&self, phase: PomPhase) -> u64 {
+        match phase {
             PomPhase::Work => self.work_duration_ms,
             PomPhase::ShortBreak => self.short_break_ms,
             PomPhase::LongBreak => self.long_break_ms,
+        }
+    }
+
+    /// The next `count` phases after the current one, with their durations.
+    /// Follows the same cycle rules as `advance_phase` without changing state.
+    pub fn upcoming_phases(&self, count: usize) -> Vec<(PomPhase, u64)> {
+        if !self.sequence.is_empty() {
+            let len = self.sequence.len();
+            return (1..=count).map(|i| self.sequence[(self.step + i) % len]).collect();
+        }
+        let mut phase = self.phase;
+        let mut cycle = self.current_cycle;
+        let mut upcoming = Vec::with_capacity(count);
+        for _ in 0..count {
+            phase = match phase {
+                PomPhase::Work => {
+                    cycle += 1;
+                    if cycle >= self.cycles_before_long {
+                        PomPhase::LongBreak
+                    } else {
+                        PomPhase::ShortBreak
+                    }
+                }
+                PomPhase::ShortBreak => PomPhase::Work,
+                PomPhase::LongBreak => {
+                    cycle = 0;
+                    PomPhase::Work
+                }
+            };
+            upcoming.push((phase, self.phase_duration_ms(phase)));
+        }
+        upcoming
+    }
+
+    /// One-line plain-text status, e.g.
+    /// "Work, 18:32 remaining, cycle 2/4, running."
+    pub fn status_line(&self, now_ms: u64) -> String {
+        let time = if self.in_flexible_break() {
+            format!("{} elapsed", format_auto(self.timer.elapsed_ms(now_ms)))
+        } else {
+            format!("{} remaining", format_auto(self.timer.remaining_ms(now_ms).unwrap_or(0)))
         };
-        if target == 0 {
-            return 1.0;
+        let phase = match &self.task {
+            Some(task) => format!("{} on {}", self.phase_label(), task),
+            None => self.phase_label().to_string(),
+        };
+        format!(
+            "{}, {}, cycle {}/{}, {}.",
+            phase,
+            time,
+            self.current_cycle + 1,
+            self.cycles_per_set(),
+            self.timer.state.label().to_lowercase()
+        )
+    }
+
+    /// Phase progress in thousandths, for the progress bar.
+    pub fn progress_permille(&self, now_ms: u64) -> u16 {
+        self.timer.elapsed_permille(now_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_today_count_resets_at_midnight() {
+        let mut stats = PomodoroStats::default();
+        let day = 19_723;
+        for _ in 0..3 {
+            stats.record_work(25 * MIN);
+            stats.count_today(day);
+        }
+        assert_eq!(stats.sessions_on(day), 3);
+
+        // Nothing yet on the next day, and the lifetime totals stand
+        assert_eq!(stats.sessions_on(day + 1), 0);
+        stats.record_work(25 * MIN);
+        stats.count_today(day + 1);
+        assert_eq!(stats.sessions_on(day + 1), 1);
+        assert_eq!(stats.sessions_on(day), 0);
+        assert_eq!(stats.total_sessions, 4);
+        assert_eq!(stats.total_focus_ms, 100 * MIN);
+    }
+
+    #[test]
+    fn test_parse_minutes() {
+        assert_eq!(parse_minutes("25"), Some(25));
+        assert_eq!(parse_minutes(" 5 "), Some(5));
+        assert_eq!(parse_minutes("1"), Some(1));
+        assert_eq!(parse_minutes("120"), Some(MAX_PHASE_MINS));
+        assert_eq!(parse_minutes("0"), None);
+        assert_eq!(parse_minutes("121"), None);
+        assert_eq!(parse_minutes(""), None);
+        assert_eq!(parse_minutes("-5"), None);
+        assert_eq!(parse_minutes("2.5"), None);
+        assert_eq!(parse_minutes("25m"), None);
+        assert_eq!(parse_minutes("99999999999999999999"), None);
+    }
+
+    const MIN: u64 = 60 * 1000;
+
+    #[test]
+    fn test_parse_sequence() {
+        let sequence = parse_sequence(" 50/10  50/30 ").unwrap();
+        assert_eq!(
+            sequence,
+            vec![
+                (PomPhase::Work, 50 * MIN),
+                (PomPhase::ShortBreak, 10 * MIN),
+                (PomPhase::Work, 50 * MIN),
+                (PomPhase::LongBreak, 30 * MIN),
+            ]
+        );
+        assert_eq!(format_sequence(&sequence), "50/10 50/30");
+        assert_eq!(parse_sequence(&format_sequence(&sequence)), Some(sequence));
+
+        assert_eq!(parse_sequence(""), Some(Vec::new()));
+        assert_eq!(format_sequence(&[]), "");
+        assert_eq!(parse_sequence("50"), None);
+        assert_eq!(parse_sequence("50/0"), None);
+        assert_eq!(parse_sequence("50/10/5"), None);
+        assert_eq!(parse_sequence("121/10"), None);
+        assert_eq!(parse_sequence(&["25/5"; MAX_SEQUENCE_PAIRS].join(" ")).map(|s| s.len()), Some(16));
+        assert_eq!(parse_sequence(&["25/5"; MAX_SEQUENCE_PAIRS + 1].join(" ")), None);
+    }
+
+    #[test]
+    fn test_clearing_sequence_restores_classic() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 3);
+        state.set_sequence(parse_sequence("50/10 50/30").unwrap());
+        assert_eq!(state.cycles_per_set(), 2);
+        assert_eq!(state.timer.target_ms(), Some(50 * MIN));
+
+        state.set_sequence(Vec::new());
+        assert_eq!(state.cycles_per_set(), 3);
+        assert_eq!(state.timer.target_ms(), Some(25 * MIN));
+        assert_eq!(state.advance_phase(), "Work done! Short break.");
+        assert_eq!(state.timer.target_ms(), Some(5 * MIN));
+    }
+
+    #[test]
+    fn test_work_completion_updates_stats() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        state.stats = PomodoroStats { total_sessions: 3, total_focus_ms: 75 * MIN, ..PomodoroStats::default() };
+        state.advance_phase();
+        assert_eq!(state.stats, PomodoroStats { total_sessions: 4, total_focus_ms: 100 * MIN, ..PomodoroStats::default() });
+
+        // Finishing a break adds nothing
+        state.advance_phase();
+        assert_eq!(state.stats.total_sessions, 4);
+    }
+
+    #[test]
+    fn test_skip_short_break() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        state.advance_phase();
+        assert_eq!(state.phase, PomPhase::ShortBreak);
+        state.timer.start(0);
+
+        state.skip_phase();
+        assert_eq!(state.phase, PomPhase::Work);
+        assert_eq!(state.timer.state, timer_core::TimerState::Stopped);
+        assert_eq!(state.timer.remaining_ms(0), Some(25 * MIN));
+        assert_eq!(state.total_completed, 1);
+        assert_eq!(state.current_cycle, 1);
+        assert_eq!(state.stats.total_sessions, 1);
+    }
+
+    #[test]
+    fn test_skip_work_refused() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        state.skip_phase();
+        assert_eq!(state.phase, PomPhase::Work);
+        assert_eq!(state.total_completed, 0);
+        assert_eq!(state.current_cycle, 0);
+    }
+
+    #[test]
+    fn test_skip_long_break_ends_set() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 1);
+        state.advance_phase();
+        assert_eq!(state.phase, PomPhase::LongBreak);
+        state.skip_phase();
+        assert_eq!(state.phase, PomPhase::Work);
+        assert_eq!(state.current_cycle, 0);
+    }
+
+    #[test]
+    fn test_custom_messages() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 2);
+        state.messages.work_to_short = "Stretch!".to_string();
+        assert_eq!(state.advance_phase(), "Stretch!");
+        assert_eq!(state.advance_phase(), "Break over! Time to work.");
+        assert_eq!(state.advance_phase(), "Work done! Long break.");
+    }
+
+    #[test]
+    fn test_sequence_advances_and_wraps() {
+        let mut state = PomodoroState::from_sequence(vec![
+            (PomPhase::Work, 50 * MIN),
+            (PomPhase::ShortBreak, 10 * MIN),
+            (PomPhase::Work, 50 * MIN),
+            (PomPhase::LongBreak, 30 * MIN),
+        ]);
+        assert_eq!(state.phase, PomPhase::Work);
+        assert_eq!(state.timer.target_ms(), Some(50 * MIN));
+        assert_eq!(state.cycles_per_set(), 2);
+        assert_eq!(state.sequence_step(), Some((1, 4)));
+        assert_eq!(state.upcoming_phases(2), vec![(PomPhase::ShortBreak, 10 * MIN), (PomPhase::Work, 50 * MIN)]);
+
+        assert_eq!(state.advance_phase(), "Work done! Short break.");
+        assert_eq!(state.timer.target_ms(), Some(10 * MIN));
+        assert_eq!(state.advance_phase(), "Break over! Time to work.");
+        assert_eq!(state.current_cycle, 1);
+        assert_eq!(state.advance_phase(), "Work done! Long break.");
+        assert_eq!((state.phase, state.timer.target_ms()), (PomPhase::LongBreak, Some(30 * MIN)));
+        assert_eq!(state.current_cycle, 2);
+        assert_eq!(state.sequence_step(), Some((4, 4)));
+        assert_eq!(state.upcoming_phases(1), vec![(PomPhase::Work, 50 * MIN)]);
+
+        // Wraps back to the first step and starts a new set
+        state.advance_phase();
+        assert_eq!((state.phase, state.timer.target_ms()), (PomPhase::Work, Some(50 * MIN)));
+        assert_eq!(state.current_cycle, 0);
+        assert_eq!(state.total_completed, 2);
+        assert_eq!(state.stats.total_focus_ms, 100 * MIN);
+    }
+
+    #[test]
+    fn test_sequence_skip_and_reset() {
+        let mut state = PomodoroState::from_sequence(vec![
+            (PomPhase::Work, 50 * MIN),
+            (PomPhase::ShortBreak, 10 * MIN),
+            (PomPhase::ShortBreak, 5 * MIN),
+        ]);
+        state.advance_phase();
+        state.skip_phase();
+        assert_eq!((state.phase, state.timer.target_ms()), (PomPhase::ShortBreak, Some(5 * MIN)));
+        assert_eq!(state.total_completed, 1);
+
+        state.reset();
+        assert_eq!((state.phase, state.timer.target_ms()), (PomPhase::Work, Some(50 * MIN)));
+        assert_eq!(state.current_cycle, 0);
+    }
+
+    #[test]
+    fn test_empty_sequence_is_classic() {
+        let mut state = PomodoroState::from_sequence(Vec::new());
+        assert_eq!(state.cycles_per_set(), 4);
+        assert_eq!(state.sequence_step(), None);
+        assert_eq!(state.advance_phase(), "Work done! Short break.");
+        assert_eq!(state.timer.target_ms(), Some(5 * MIN));
+    }
+
+    #[test]
+    fn test_previous_phase_undoes_advance() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        state.timer.start(0);
+        state.advance_phase();
+        assert_eq!((state.phase, state.current_cycle, state.total_completed), (PomPhase::ShortBreak, 1, 1));
+
+        assert!(state.previous_phase());
+        assert_eq!((state.phase, state.current_cycle, state.total_completed), (PomPhase::Work, 0, 0));
+        assert_eq!(state.stats, PomodoroStats::default());
+        assert_eq!(state.timer.target_ms(), Some(25 * MIN));
+        assert_eq!(state.timer.state, timer_core::TimerState::Stopped);
+        assert!(!state.previous_phase());
+    }
+
+    #[test]
+    fn test_previous_phase_from_work_to_break() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 1);
+        state.advance_phase();
+        state.advance_phase();
+        assert_eq!((state.phase, state.current_cycle), (PomPhase::Work, 0));
+
+        // Back into the long break, with the cycle it ended
+        assert!(state.previous_phase());
+        assert_eq!((state.phase, state.current_cycle), (PomPhase::LongBreak, 1));
+        assert_eq!(state.timer.target_ms(), Some(15 * MIN));
+        assert_eq!(state.total_completed, 1);
+    }
+
+    #[test]
+    fn test_previous_phase_history_bounded() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        for _ in 0..HISTORY_LEN + 3 {
+            state.advance_phase();
+        }
+        let mut undone = 0;
+        while state.previous_phase() {
+            undone += 1;
+        }
+        assert_eq!(undone, HISTORY_LEN);
+
+        state.advance_phase();
+        state.reset();
+        assert!(!state.previous_phase());
+    }
+
+    #[test]
+    fn test_extend_break_capped() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        state.auto_extend_break = true;
+        // Work phases are never extended
+        assert!(!state.try_extend_break());
+
+        state.advance_phase();
+        for i in 1..=MAX_BREAK_EXTENSIONS {
+            assert!(state.try_extend_break());
+            assert_eq!(state.break_extensions, i);
+            assert_eq!(state.timer.target_ms(), Some(MIN));
+            assert_eq!(state.phase, PomPhase::ShortBreak);
+        }
+        assert!(!state.try_extend_break());
+
+        state.advance_phase();
+        assert_eq!(state.break_extensions, 0);
+    }
+
+    #[test]
+    fn test_extend_break_disabled() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        state.advance_phase();
+        assert!(!state.try_extend_break());
+        assert_eq!(state.timer.target_ms(), Some(5 * MIN));
+    }
+
+    #[test]
+    fn test_flexible_break_counts_up() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        state.flexible_break = true;
+        assert!(!state.in_flexible_break());
+        assert_eq!(state.end_flexible_break(0), None);
+
+        state.advance_phase();
+        assert_eq!(state.phase, PomPhase::ShortBreak);
+        assert!(state.in_flexible_break());
+        state.timer.start(0);
+        // Well past the configured 5 minutes, still waiting
+        assert_eq!(state.timer.tick(10 * MIN), timer_core::TimerState::Running);
+        assert!(!state.try_extend_break());
+
+        assert_eq!(state.end_flexible_break(12 * MIN), Some(12 * MIN));
+        assert_eq!(state.last_break_ms, Some(12 * MIN));
+        assert_eq!(state.phase, PomPhase::Work);
+        assert_eq!(state.timer.target_ms(), Some(25 * MIN));
+        assert!(!state.in_flexible_break());
+    }
+
+    #[test]
+    fn test_flexible_long_break_and_reset() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 1);
+        state.flexible_break = true;
+        state.advance_phase();
+        assert_eq!(state.phase, PomPhase::LongBreak);
+        state.timer.start(0);
+        assert_eq!(state.end_flexible_break(3 * MIN), Some(3 * MIN));
+        assert_eq!(state.current_cycle, 0);
+
+        state.advance_phase();
+        state.timer.start(0);
+        state.reset();
+        assert!(!state.in_flexible_break());
+        assert_eq!(state.phase, PomPhase::Work);
+    }
+
+    #[test]
+    fn test_reset_starts_set_over() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        for _ in 0..4 {
+            state.advance_phase();
+        }
+        assert_eq!(state.current_cycle, 2);
+        state.advance_phase();
+        assert_eq!(state.phase, PomPhase::ShortBreak);
+        state.timer.start(0);
+
+        state.reset();
+        assert_eq!(state.phase, PomPhase::Work);
+        assert_eq!(state.current_cycle, 0);
+        assert_eq!(state.timer.target_ms(), Some(state.work_duration_ms));
+        assert_eq!(state.timer.state, timer_core::TimerState::Stopped);
+        assert_eq!(state.total_completed, 3);
+    }
+
+    #[test]
+    fn test_set_task() {
+        let mut state = PomodoroState::new();
+        state.set_task("  Write report ");
+        assert_eq!(state.task.as_deref(), Some("Write report"));
+        assert_eq!(state.status_line(0), "Work on Write report, 25:00 remaining, cycle 1/4, stopped.");
+
+        state.set_task(&"x".repeat(MAX_TASK_CHARS + 5));
+        assert_eq!(state.task.as_ref().map(|t| t.chars().count()), Some(MAX_TASK_CHARS));
+
+        state.set_task("   ");
+        assert_eq!(state.task, None);
+    }
+
+    #[test]
+    fn test_status_line() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        assert_eq!(state.status_line(0), "Work, 25:00 remaining, cycle 1/4, stopped.");
+
+        state.advance_phase();
+        state.advance_phase();
+        state.timer.start(0);
+        assert_eq!(
+            state.status_line(6 * MIN + 28_000),
+            "Work, 18:32 remaining, cycle 2/4, running."
+        );
+
+        state.flexible_break = true;
+        state.advance_phase();
+        state.timer.start(0);
+        state.timer.pause(90_000);
+        assert_eq!(state.status_line(MIN * 10), "Short Break, 1:30 elapsed, cycle 3/4, paused.");
+    }
+
+    #[test]
+    fn test_upcoming_from_start() {
+        let state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        assert_eq!(
+            state.upcoming_phases(3),
+            vec![
+                (PomPhase::ShortBreak, 5 * MIN),
+                (PomPhase::Work, 25 * MIN),
+                (PomPhase::ShortBreak, 5 * MIN),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_upcoming_across_long_break() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 2);
+        state.advance_phase(); // -> short break, cycle 1
+        assert_eq!(
+            state.upcoming_phases(3),
+            vec![
+                (PomPhase::Work, 25 * MIN),
+                (PomPhase::LongBreak, 15 * MIN),
+                (PomPhase::Work, 25 * MIN),
+            ]
+        );
+
+        state.advance_phase(); // -> work
+        state.advance_phase(); // -> long break, cycle 2
+        assert_eq!(state.phase, PomPhase::LongBreak);
+        assert_eq!(
+            state.upcoming_phases(2),
+            vec![(PomPhase::Work, 25 * MIN), (PomPhase::ShortBreak, 5 * MIN)]
+        );
+    }
+
+    #[test]
+    fn test_upcoming_matches_advance() {
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 3);
+        let predicted = state.upcoming_phases(8);
+        for (phase, duration) in predicted {
+            state.advance_phase();
+            assert_eq!(state.phase, phase);
+            assert_eq!(state.timer.target_ms(), Some(duration));
         }
-        let elapsed = self.timer.elapsed_ms(now_ms);
-        let frac = elapsed as f32 / target as f32;
-        if frac > 1.0 { 1.0 } else { frac }
+        assert!(state.upcoming_phases(0).is_empty());
     }
 }
@@ -0,0 +1,1040 @@
+use crate::countdown::{CountdownState, ExpiryAction};
+use crate::eventlog::EventSource;
+use crate::interval::{IntervalState, IntervalStep};
+use crate::pomodoro::{PomPhase, PomodoroState, SetCompleteAction};
+use crate::stopwatch::StopwatchState;
+use crate::AppMode;
+use timer_core::{format_auto, TimerCore, TimerState};
+
+/// Side effects requested by a pump tick. The app applies them in order;
+/// tests can inspect them directly.
+#[derive(Debug, PartialEq)]
+pub enum Effect {
+    /// Fire the configured alert channels with this message, on behalf of
+    /// this timer.
+    Alert(EventSource, String),
+    /// Like `Alert`, for countdown entry `usize` having run out for good,
+    /// so the user can snooze it.
+    SnoozableAlert(String, usize),
+    /// Record in the event log that a timer ran out.
+    Expired(EventSource),
+    /// A work phase completed; persist the pomodoro stats.
+    SavePomodoroStats,
+    /// Start the pomodoro timer once any preceding alert is dismissed.
+    StartPomodoro,
+    /// Start this entry's countdown once any preceding alert is dismissed.
+    StartCountdown(usize),
+    /// Start the interval timer's next stretch once any preceding alert
+    /// is dismissed.
+    StartInterval,
+    StopPump,
+    SetMode(AppMode),
+    /// Leave the app.
+    Quit,
+    Redraw,
+}
+
+/// Most timers allowed to run at once, across all modes. Each one adds to
+/// the work done on every pump tick and redraw.
+pub const MAX_RUNNING_TIMERS: usize = 8;
+
+/// Timers running right now, in every mode.
+pub fn running_timers(
+    pomodoro: &PomodoroState,
+    stopwatch: &StopwatchState,
+    countdown: &CountdownState,
+    interval: &IntervalState,
+) -> usize {
+    pomodoro.timer.state.is_active() as usize
+        + stopwatch.timer.state.is_active() as usize
+        + countdown.running_count()
+        + interval.is_running() as usize
+}
+
+/// Timers stopped by "pause all", so "resume all" restarts just those and
+/// leaves alone anything the user had paused themselves.
+#[derive(Debug, Default, PartialEq)]
+pub struct PausedTimers {
+    pub pomodoro: bool,
+    pub stopwatch: bool,
+    /// Countdown entry indices.
+    pub countdowns: Vec<usize>,
+    pub interval: bool,
+}
+
+impl PausedTimers {
+    /// Pause every running timer, adding it to the ones already held.
+    /// Returns true if anything was running.
+    pub fn pause_all(
+        &mut self,
+        pomodoro: &mut PomodoroState,
+        stopwatch: &mut StopwatchState,
+        countdown: &mut CountdownState,
+        interval: &mut IntervalState,
+        now_ms: u64,
+    ) -> bool {
+        let mut paused = false;
+        let mut pause = |timer: &mut TimerCore| {
+            let running = timer.state.is_active();
+            if running {
+                timer.pause(now_ms);
+                paused = true;
+            }
+            running
+        };
+        self.pomodoro |= pause(&mut pomodoro.timer);
+        self.stopwatch |= pause(&mut stopwatch.timer);
+        for idx in 0..countdown.armed.len() {
+            if countdown.timer_mut(idx).is_some_and(&mut pause) && !self.countdowns.contains(&idx) {
+                self.countdowns.push(idx);
+            }
+        }
+        self.interval |= pause(&mut interval.timer);
+        paused
+    }
+
+    /// Resume the timers `pause_all` stopped that are still paused, and
+    /// forget them. Returns how many restarted.
+    pub fn resume_all(
+        &mut self,
+        pomodoro: &mut PomodoroState,
+        stopwatch: &mut StopwatchState,
+        countdown: &mut CountdownState,
+        interval: &mut IntervalState,
+        now_ms: u64,
+    ) -> usize {
+        let taken = std::mem::take(self);
+        let mut resumed = 0;
+        let mut resume = |timer: &mut TimerCore| {
+            // Reset or restarted since: not ours to touch any more
+            if timer.state == TimerState::Paused {
+                timer.resume(now_ms);
+                resumed += 1;
+            }
+        };
+        if taken.pomodoro {
+            resume(&mut pomodoro.timer);
+        }
+        if taken.stopwatch {
+            resume(&mut stopwatch.timer);
+        }
+        for idx in taken.countdowns {
+            if let Some(timer) = countdown.timer_mut(idx) {
+                resume(timer);
+            }
+        }
+        if taken.interval {
+            resume(&mut interval.timer);
+        }
+        resumed
+    }
+}
+
+/// Whether one more timer may start with `running` already going.
+pub fn can_start_timer(running: usize) -> bool {
+    running < MAX_RUNNING_TIMERS
+}
+
+/// A countdown with less than this left is about to finish.
+pub const FINISHING_MS: u64 = 10_000;
+
+/// Pump rate for a countdown about to finish, so the last seconds and the
+/// expiry land close to on time rather than up to a second late.
+pub const FINISHING_PUMP_MS: u64 = 250;
+
+/// Pump interval for the screen in `mode` at `now_ms`, or None if nothing
+/// shown there is running. One tick services every timer on the screen,
+/// so the interval is the finest any of them needs (centiseconds for the
+/// stopwatch, quarter seconds for a countdown in its last seconds,
+/// seconds otherwise) and doesn't shrink as timers are added.
+pub fn desired_pump_interval(
+    mode: AppMode,
+    pomodoro: &PomodoroState,
+    stopwatch: &StopwatchState,
+    countdown: &CountdownState,
+    interval: &IntervalState,
+    now_ms: u64,
+) -> Option<u64> {
+    match mode {
+        AppMode::Pomodoro if pomodoro.timer.state.is_active() => Some(1000),
+        AppMode::Stopwatch if stopwatch.timer.state.is_active() => Some(100),
+        AppMode::CountdownList | AppMode::CountdownRun => match countdown.soonest_remaining_ms(now_ms) {
+            Some(left) if left < FINISHING_MS => Some(FINISHING_PUMP_MS),
+            Some(_) => Some(1000),
+            None => None,
+        },
+        AppMode::Interval if interval.is_running() => Some(1000),
+        AppMode::Clock => Some(1000),
+        _ => None,
+    }
+}
+
+/// Time until the first running timer in any mode runs out: the
+/// pomodoro, countdowns, interval timer, or a stopwatch short of its
+/// target. None if nothing will. `pump_step` ticks all of these whatever
+/// the screen, so this is when the pump must next wake.
+pub fn next_expiry_ms(
+    pomodoro: &PomodoroState,
+    stopwatch: &StopwatchState,
+    countdown: &CountdownState,
+    interval: &IntervalState,
+    now_ms: u64,
+) -> Option<u64> {
+    let running = |timer: &TimerCore| timer.state.is_active();
+    let stopwatch_left = if running(&stopwatch.timer) && !stopwatch.past_target(now_ms) {
+        stopwatch.remaining_to_target(now_ms)
+    } else {
+        None
+    };
+    soonest_expiry(&[
+        pomodoro.timer.remaining_ms(now_ms).filter(|_| running(&pomodoro.timer)),
+        countdown.soonest_remaining_ms(now_ms),
+        interval.timer.remaining_ms(now_ms).filter(|_| running(&interval.timer)),
+        stopwatch_left,
+    ])
+}
+
+/// The soonest of several timers' remaining times; None if none of them
+/// will run out.
+pub fn soonest_expiry(remaining: &[Option<u64>]) -> Option<u64> {
+    remaining.iter().flatten().copied().min()
+}
+
+/// Shortest expiry wakeup, so a timer due now doesn't spin the pump.
+pub const WAKEUP_MIN_PUMP_MS: u64 = 100;
+
+/// Pump interval when nothing on screen needs ticking, e.g. in the
+/// background or on mode select: wake only when the next timer is due to
+/// run out.
+pub fn expiry_wakeup_interval(until_expiry_ms: Option<u64>) -> Option<u64> {
+    until_expiry_ms.map(|ms| ms.max(WAKEUP_MIN_PUMP_MS))
+}
+
+/// Slowest redraw the focus display needs: its digits show whole seconds,
+/// even for the stopwatch.
+pub const FOCUS_PUMP_MS: u64 = 1000;
+
+/// Pump interval with the focus display up or not: it never ticks faster
+/// than `FOCUS_PUMP_MS`, and doesn't keep an idle screen ticking.
+pub fn focus_pump_interval(wanted: Option<u64>, focus: bool) -> Option<u64> {
+    wanted.map(|ms| if focus { ms.max(FOCUS_PUMP_MS) } else { ms })
+}
+
+/// Decide what a pump tick at `now_ms` does with `mode` on screen. Every
+/// running timer is ticked whatever the screen, so one that runs out
+/// while another mode is shown still alerts. Timer state transitions
+/// happen here; anything touching hardware or the UI is returned as an
+/// `Effect`.
+pub fn pump_step(
+    mode: AppMode,
+    pomodoro: &mut PomodoroState,
+    stopwatch: &mut StopwatchState,
+    countdown: &mut CountdownState,
+    interval: &mut IntervalState,
+    now_ms: u64,
+) -> Vec<Effect> {
+    let mut effects = Vec::new();
+
+    tick_pomodoro(pomodoro, now_ms, &mut effects);
+    check_stopwatch(mode, stopwatch, now_ms, &mut effects);
+    tick_countdowns(mode, countdown, now_ms, &mut effects);
+    tick_interval(interval, now_ms, &mut effects);
+
+    let ticking_screen = matches!(
+        mode,
+        AppMode::Pomodoro
+            | AppMode::Stopwatch
+            | AppMode::CountdownList
+            | AppMode::CountdownRun
+            | AppMode::Interval
+            | AppMode::Clock
+    );
+    let expired = effects.iter().any(|e| matches!(e, Effect::Expired(_)));
+    if ticking_screen || expired {
+        effects.push(Effect::Redraw);
+    }
+    if !ticking_screen {
+        // Nothing here counts: keep the pump only for the next expiry
+        effects.push(Effect::StopPump);
+    }
+    effects
+}
+
+fn tick_pomodoro(pomodoro: &mut PomodoroState, now_ms: u64, effects: &mut Vec<Effect>) {
+    if !pomodoro.timer.tick(now_ms).is_finished() {
+        return;
+    }
+    let source = EventSource::Pomodoro;
+    effects.push(Effect::Expired(EventSource::Pomodoro));
+    let set_done = pomodoro.phase == PomPhase::LongBreak;
+    let work_done = pomodoro.phase == PomPhase::Work;
+    let msg = if pomodoro.try_extend_break() {
+        "Break over! Extended 1m - Enter to work.".to_string()
+    } else {
+        pomodoro.advance_phase().to_string()
+    };
+    if work_done {
+        effects.push(Effect::SavePomodoroStats);
+    }
+    // An extended long break hasn't finished the set yet
+    let action = if set_done && pomodoro.break_extensions == 0 {
+        pomodoro.on_set_complete
+    } else {
+        SetCompleteAction::Loop
+    };
+    match action {
+        // An extension re-arms the same break, so it keeps going
+        SetCompleteAction::Loop if pomodoro.auto_start || pomodoro.break_extensions > 0 => {
+            effects.push(Effect::Alert(source, msg));
+            // Auto-start next phase
+            effects.push(Effect::StartPomodoro);
+        }
+        SetCompleteAction::Loop => {
+            // Next phase waits for the user to press start
+            effects.push(Effect::StopPump);
+            effects.push(Effect::Alert(source, msg));
+        }
+        SetCompleteAction::Stop => {
+            pomodoro.set_complete = true;
+            effects.push(Effect::StopPump);
+            effects.push(Effect::Alert(source, "Set complete!".to_string()));
+        }
+        SetCompleteAction::Exit => {
+            effects.push(Effect::StopPump);
+            effects.push(Effect::Alert(source, "Set complete!".to_string()));
+            effects.push(Effect::Quit);
+        }
+    }
+}
+
+/// The target time alerts from any screen; reminders and overdue laps are
+/// about the run being watched, so only on the stopwatch screen.
+fn check_stopwatch(mode: AppMode, stopwatch: &mut StopwatchState, now_ms: u64, effects: &mut Vec<Effect>) {
+    let source = EventSource::Stopwatch;
+    if mode == AppMode::Stopwatch {
+        let elapsed = stopwatch.timer.elapsed_ms(now_ms);
+        if stopwatch.check_reminder(elapsed) {
+            effects.push(Effect::Alert(source, format!("You've been at it {}m", elapsed / 60_000)));
+        }
+        if stopwatch.check_lap_overdue(now_ms) {
+            effects.push(Effect::Alert(source, format!("No split for {}", format_auto(stopwatch.lap_alert_ms))));
+        }
+    }
+    if stopwatch.check_target(now_ms) {
+        let target = format_auto(stopwatch.target_ms.unwrap_or(0));
+        effects.push(Effect::Alert(source, format!("Target {} reached", target)));
+    }
+}
+
+fn tick_countdowns(mode: AppMode, countdown: &mut CountdownState, now_ms: u64, effects: &mut Vec<Effect>) {
+    let source = EventSource::Countdown;
+    let expired = countdown.tick_all(now_ms);
+    // Keep pumping while anything still runs or is about to restart
+    let restarting = expired.iter().any(|&idx| countdown.expiry_action(idx) != ExpiryAction::Stop);
+    let mut pump_stopped = false;
+    for idx in expired {
+        effects.push(Effect::Expired(EventSource::Countdown));
+        let name = countdown.entries.get(idx).map_or("Timer", |e| e.name.as_str());
+        let msg = format!("{} expired!", name);
+        match countdown.expiry_action(idx) {
+            ExpiryAction::Repeat => {
+                countdown.repeat(idx);
+                effects.push(Effect::Alert(source, msg));
+                effects.push(Effect::StartCountdown(idx));
+            }
+            ExpiryAction::StartNext(next) => {
+                // Linked timer takes over once the alert is dismissed
+                countdown.hand_over(idx, next);
+                effects.push(Effect::Alert(source, msg));
+                effects.push(Effect::StartCountdown(next));
+            }
+            ExpiryAction::NextStep(next) => {
+                let next_name = countdown.entries.get(next).map_or("Timer", |e| e.name.as_str());
+                let msg = format!("{} done, {} next", name, next_name);
+                countdown.advance_chain(idx);
+                effects.push(Effect::Alert(source, msg));
+                effects.push(Effect::StartCountdown(next));
+            }
+            ExpiryAction::Stop => {
+                let shown = countdown.active_index == Some(idx);
+                countdown.disarm(idx);
+                if !pump_stopped && !restarting && countdown.running_count() == 0 {
+                    effects.push(Effect::StopPump);
+                    pump_stopped = true;
+                }
+                // Leave the run screen first; a snooze brings it back
+                if shown && mode == AppMode::CountdownRun {
+                    effects.push(Effect::SetMode(AppMode::CountdownList));
+                }
+                effects.push(Effect::SnoozableAlert(msg, idx));
+            }
+        }
+    }
+}
+
+fn tick_interval(interval: &mut IntervalState, now_ms: u64, effects: &mut Vec<Effect>) {
+    if !interval.timer.tick(now_ms).is_finished() {
+        return;
+    }
+    let source = EventSource::Interval;
+    let step = interval.advance();
+    let msg = interval.message(step);
+    if step == IntervalStep::Done {
+        // Only the end of the workout counts as an expiry; the stretches
+        // in between carry on by themselves
+        effects.push(Effect::Expired(EventSource::Interval));
+        effects.push(Effect::StopPump);
+        effects.push(Effect::Alert(source, msg));
+    } else {
+        effects.push(Effect::Alert(source, msg));
+        effects.push(Effect::StartInterval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::UnackedExpiries;
+    use crate::interval::IntervalSettings;
+
+    const MIN: u64 = 60 * 1000;
+
+    struct Sim {
+        mode: AppMode,
+        pomodoro: PomodoroState,
+        stopwatch: StopwatchState,
+        countdown: CountdownState,
+        interval: IntervalState,
+        alerts: Vec<String>,
+        unacked: UnackedExpiries,
+    }
+
+    impl Sim {
+        fn new(mode: AppMode) -> Self {
+            Self {
+                mode,
+                pomodoro: PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 2),
+                stopwatch: StopwatchState::new(),
+                countdown: CountdownState::new(),
+                interval: IntervalState::from_settings(IntervalSettings { work_ms: 30_000, rest_ms: 10_000, rounds: 2 }),
+                alerts: Vec::new(),
+                unacked: UnackedExpiries::default(),
+            }
+        }
+
+        /// Run one tick and apply its effects the way the app would.
+        fn tick(&mut self, now_ms: u64) -> Vec<Effect> {
+            let effects = pump_step(
+                self.mode, &mut self.pomodoro, &mut self.stopwatch, &mut self.countdown, &mut self.interval, now_ms,
+            );
+            for effect in &effects {
+                match effect {
+                    Effect::Alert(_, msg) | Effect::SnoozableAlert(msg, _) => self.alerts.push(msg.clone()),
+                    Effect::StartPomodoro => self.pomodoro.timer.start(now_ms),
+                    Effect::StartCountdown(idx) => {
+                        if let Some(timer) = self.countdown.timer_mut(*idx) {
+                            timer.start(now_ms);
+                        }
+                    }
+                    Effect::StartInterval => self.interval.timer.start(now_ms),
+                    Effect::SetMode(mode) => self.mode = *mode,
+                    Effect::Expired(source) => self.unacked.record(*source, EventSource::for_mode(self.mode)),
+                    Effect::SavePomodoroStats
+                    | Effect::StopPump
+                    | Effect::Quit
+                    | Effect::Redraw => {}
+                }
+            }
+            effects
+        }
+    }
+
+    #[test]
+    fn test_running_timer_cap() {
+        let mut sim = Sim::new(AppMode::Pomodoro);
+        let running = |sim: &Sim| running_timers(&sim.pomodoro, &sim.stopwatch, &sim.countdown, &sim.interval);
+        assert_eq!(running(&sim), 0);
+        sim.pomodoro.timer.start(0);
+        sim.stopwatch.timer.start(0);
+        sim.interval.timer.start(0);
+        assert_eq!(running(&sim), 3);
+
+        // Paused timers don't count
+        sim.stopwatch.timer.pause(1000);
+        assert_eq!(running(&sim), 2);
+
+        assert!(can_start_timer(0));
+        assert!(can_start_timer(MAX_RUNNING_TIMERS - 1));
+        assert!(!can_start_timer(MAX_RUNNING_TIMERS));
+        assert!(!can_start_timer(MAX_RUNNING_TIMERS + 3));
+    }
+
+    #[test]
+    fn test_resume_all_restarts_only_what_pause_all_paused() {
+        let mut sim = Sim::new(AppMode::ModeSelect);
+        sim.pomodoro.timer.start(0);
+        // Paused by the user beforehand
+        sim.stopwatch.timer.start(0);
+        sim.stopwatch.timer.pause(1000);
+        sim.countdown.add_entry("Tea".to_string(), 3 * MIN);
+        sim.countdown.add_entry("Eggs".to_string(), 7 * MIN);
+        for idx in 0..2 {
+            sim.countdown.cursor = idx;
+            sim.countdown.start_selected();
+            sim.countdown.active_timer_mut().unwrap().start(0);
+        }
+        sim.countdown.timer_mut(1).unwrap().pause(2000);
+
+        let mut held = PausedTimers::default();
+        let pause_all = |held: &mut PausedTimers, sim: &mut Sim, now_ms| {
+            held.pause_all(&mut sim.pomodoro, &mut sim.stopwatch, &mut sim.countdown, &mut sim.interval, now_ms)
+        };
+        assert!(pause_all(&mut held, &mut sim, 5000));
+        assert_eq!(held, PausedTimers { pomodoro: true, countdowns: vec![0], ..Default::default() });
+        assert_eq!(running_timers(&sim.pomodoro, &sim.stopwatch, &sim.countdown, &sim.interval), 0);
+        // Nothing left running: a second pause changes nothing
+        assert!(!pause_all(&mut held, &mut sim, 6000));
+
+        let resumed = held.resume_all(&mut sim.pomodoro, &mut sim.stopwatch, &mut sim.countdown, &mut sim.interval, 9000);
+        assert_eq!(resumed, 2);
+        assert_eq!(held, PausedTimers::default());
+        assert_eq!(sim.pomodoro.timer.state, TimerState::Running);
+        assert_eq!(sim.countdown.timer(0).unwrap().state, TimerState::Running);
+        assert_eq!(sim.stopwatch.timer.state, TimerState::Paused);
+        assert_eq!(sim.countdown.timer(1).unwrap().state, TimerState::Paused);
+        // Time paused doesn't count
+        assert_eq!(sim.pomodoro.timer.elapsed_ms(10_000), 6000);
+    }
+
+    #[test]
+    fn test_resume_all_skips_timers_reset_since() {
+        let mut sim = Sim::new(AppMode::ModeSelect);
+        sim.pomodoro.timer.start(0);
+        sim.interval.timer.start(0);
+        let mut held = PausedTimers::default();
+        held.pause_all(&mut sim.pomodoro, &mut sim.stopwatch, &mut sim.countdown, &mut sim.interval, 1000);
+        sim.pomodoro.timer.reset();
+        let resumed = held.resume_all(&mut sim.pomodoro, &mut sim.stopwatch, &mut sim.countdown, &mut sim.interval, 2000);
+        assert_eq!(resumed, 1);
+        assert_eq!(sim.pomodoro.timer.state, TimerState::Stopped);
+        assert!(sim.interval.is_running());
+    }
+
+    #[test]
+    fn test_desired_pump_interval() {
+        let mut sim = Sim::new(AppMode::Stopwatch);
+        let interval = |sim: &Sim, mode| {
+            desired_pump_interval(mode, &sim.pomodoro, &sim.stopwatch, &sim.countdown, &sim.interval, 0)
+        };
+        assert_eq!(interval(&sim, AppMode::Stopwatch), None);
+
+        sim.stopwatch.timer.start(0);
+        sim.pomodoro.timer.start(0);
+        assert_eq!(interval(&sim, AppMode::Stopwatch), Some(100));
+        assert_eq!(interval(&sim, AppMode::Pomodoro), Some(1000));
+        // Nothing ticks on screens without a timer
+        assert_eq!(interval(&sim, AppMode::ModeSelect), None);
+        assert_eq!(interval(&sim, AppMode::CountdownRun), None);
+
+        // Countdowns keep ticking on the list so it can show them counting
+        sim.countdown.add_entry("Tea".to_string(), 3 * MIN);
+        sim.countdown.start_selected();
+        sim.countdown.active_timer_mut().unwrap().start(0);
+        assert_eq!(interval(&sim, AppMode::CountdownList), Some(1000));
+        assert_eq!(interval(&sim, AppMode::CountdownRun), Some(1000));
+
+        assert_eq!(interval(&sim, AppMode::Interval), None);
+        sim.interval.timer.start(0);
+        assert_eq!(interval(&sim, AppMode::Interval), Some(1000));
+        // The clock ticks with nothing running
+        assert_eq!(interval(&sim, AppMode::Clock), Some(1000));
+    }
+
+    #[test]
+    fn test_pump_speeds_up_near_expiry() {
+        let mut sim = Sim::new(AppMode::CountdownRun);
+        let interval = |sim: &Sim, now_ms| {
+            desired_pump_interval(AppMode::CountdownRun, &sim.pomodoro, &sim.stopwatch, &sim.countdown, &sim.interval, now_ms)
+        };
+        sim.countdown.add_entry("Eggs".to_string(), MIN);
+        sim.countdown.start_selected();
+        sim.countdown.active_timer_mut().unwrap().start(0);
+        assert_eq!(interval(&sim, MIN - FINISHING_MS), Some(1000));
+        assert_eq!(interval(&sim, MIN - FINISHING_MS + 1), Some(FINISHING_PUMP_MS));
+
+        // Paused in its last seconds, nothing needs the pump
+        sim.countdown.active_timer_mut().unwrap().pause(MIN - 5000);
+        assert_eq!(interval(&sim, MIN), None);
+    }
+
+    #[test]
+    fn test_next_expiry_ms() {
+        let mut sim = Sim::new(AppMode::ModeSelect);
+        let until = |sim: &Sim, now_ms| {
+            next_expiry_ms(&sim.pomodoro, &sim.stopwatch, &sim.countdown, &sim.interval, now_ms)
+        };
+        assert_eq!(until(&sim, 0), None);
+
+        // The soonest of several running countdowns
+        sim.countdown.add_entry("Tea".to_string(), 3 * MIN);
+        sim.countdown.add_entry("Eggs".to_string(), 7 * MIN);
+        for idx in 0..2 {
+            sim.countdown.cursor = idx;
+            sim.countdown.start_selected();
+            sim.countdown.active_timer_mut().unwrap().start(0);
+        }
+        assert_eq!(until(&sim, MIN), Some(2 * MIN));
+
+        // Other modes' timers count too, whatever the screen
+        sim.stopwatch.set_target(Some(90 * 1000), 0);
+        sim.stopwatch.timer.start(0);
+        assert_eq!(until(&sim, MIN), Some(30 * 1000));
+        sim.stopwatch.timer.pause(MIN);
+        assert_eq!(until(&sim, MIN), Some(2 * MIN));
+        sim.interval.timer.start(MIN);
+        assert_eq!(until(&sim, MIN), Some(30 * 1000));
+        sim.interval.timer.pause(MIN);
+        sim.pomodoro.timer.start(MIN);
+        sim.countdown.pause_all(MIN);
+        assert_eq!(until(&sim, MIN), Some(25 * MIN));
+        sim.pomodoro.timer.pause(MIN);
+        assert_eq!(until(&sim, MIN), None);
+
+        assert_eq!(expiry_wakeup_interval(Some(2 * MIN)), Some(2 * MIN));
+        assert_eq!(expiry_wakeup_interval(Some(0)), Some(WAKEUP_MIN_PUMP_MS));
+        assert_eq!(expiry_wakeup_interval(None), None);
+    }
+
+    #[test]
+    fn test_countdown_expires_on_mode_select() {
+        let mut sim = Sim::new(AppMode::ModeSelect);
+        sim.countdown.add_entry("Tea".to_string(), 3 * MIN);
+        sim.countdown.start_selected();
+        sim.countdown.timer_mut(0).unwrap().start(0);
+
+        // Nothing on this screen ticks, so the pump drops to an expiry wakeup
+        assert_eq!(sim.tick(MIN), vec![Effect::StopPump]);
+        let until = next_expiry_ms(&sim.pomodoro, &sim.stopwatch, &sim.countdown, &sim.interval, MIN);
+        assert_eq!(expiry_wakeup_interval(until), Some(2 * MIN));
+
+        let effects = sim.tick(3 * MIN);
+        assert!(effects.contains(&Effect::Expired(EventSource::Countdown)));
+        assert!(effects.contains(&Effect::SnoozableAlert("Tea expired!".to_string(), 0)));
+        assert!(!effects.iter().any(|e| matches!(e, Effect::SetMode(_))));
+        assert_eq!(sim.mode, AppMode::ModeSelect);
+        assert_eq!(sim.alerts, vec!["Tea expired!"]);
+        assert_eq!(sim.countdown.running_count(), 0);
+    }
+
+    #[test]
+    fn test_expiries_off_screen_are_unacked() {
+        let mut sim = Sim::new(AppMode::Pomodoro);
+        sim.countdown.add_entry("Tea".to_string(), 3 * MIN);
+        sim.countdown.start_selected();
+        sim.countdown.timer_mut(0).unwrap().start(0);
+        sim.pomodoro.timer.start(0);
+
+        // Tea runs out behind the pomodoro screen
+        sim.tick(3 * MIN);
+        assert_eq!(sim.alerts, vec!["Tea expired!"]);
+        assert_eq!(sim.unacked.pending(Some(EventSource::Pomodoro)), 1);
+
+        // The pomodoro runs out while mode select is up
+        sim.mode = AppMode::ModeSelect;
+        let effects = sim.tick(25 * MIN);
+        assert!(effects.contains(&Effect::Expired(EventSource::Pomodoro)));
+        assert_eq!(sim.unacked.pending(None), 2);
+
+        // Visiting the countdowns clears theirs only
+        sim.unacked.acknowledge(Some(EventSource::Countdown));
+        assert_eq!(sim.unacked.pending(Some(EventSource::Countdown)), 1);
+    }
+
+    #[test]
+    fn test_workout_done_off_screen_is_unacked() {
+        let mut sim = Sim::new(AppMode::Stopwatch);
+        sim.interval.rounds = 1;
+        sim.interval.timer.start(0);
+        let effects = sim.tick(30_000);
+        assert!(effects.contains(&Effect::Expired(EventSource::Interval)));
+        assert_eq!(sim.alerts, vec!["Workout done!"]);
+        assert_eq!(sim.unacked.pending(Some(EventSource::Stopwatch)), 1);
+        assert_eq!(sim.unacked.pending(Some(EventSource::Interval)), 0);
+    }
+
+    #[test]
+    fn test_expiry_on_screen_is_not_unacked() {
+        let mut sim = Sim::new(AppMode::CountdownList);
+        sim.countdown.add_entry("Tea".to_string(), 3 * MIN);
+        sim.countdown.start_selected();
+        sim.countdown.timer_mut(0).unwrap().start(0);
+        sim.tick(3 * MIN);
+        assert_eq!(sim.unacked.pending(None), 0);
+    }
+
+    #[test]
+    fn test_soonest_expiry() {
+        assert_eq!(soonest_expiry(&[]), None);
+        assert_eq!(soonest_expiry(&[None, None]), None);
+        assert_eq!(soonest_expiry(&[None, Some(5000), Some(2000), None]), Some(2000));
+        // Something due right now wins
+        assert_eq!(soonest_expiry(&[Some(0), Some(2000)]), Some(0));
+    }
+
+    #[test]
+    fn test_focus_pump_interval() {
+        // The stopwatch drops from centiseconds to seconds
+        assert_eq!(focus_pump_interval(Some(100), true), Some(FOCUS_PUMP_MS));
+        assert_eq!(focus_pump_interval(Some(100), false), Some(100));
+        assert_eq!(focus_pump_interval(Some(1000), true), Some(1000));
+        // Nothing running stays idle
+        assert_eq!(focus_pump_interval(None, true), None);
+    }
+
+    #[test]
+    fn test_manual_start_waits_at_phase_boundary() {
+        let mut sim = Sim::new(AppMode::Pomodoro);
+        sim.pomodoro.auto_start = false;
+        sim.pomodoro.timer.start(0);
+
+        let effects = sim.tick(25 * MIN);
+        assert!(effects.contains(&Effect::StopPump));
+        assert!(!effects.contains(&Effect::StartPomodoro));
+        assert_eq!(sim.alerts, vec!["Work done! Short break."]);
+        assert_eq!(sim.pomodoro.phase, PomPhase::ShortBreak);
+        assert_eq!(sim.pomodoro.timer.state, TimerState::Stopped);
+
+        // Nothing moves until the user starts the break
+        sim.tick(40 * MIN);
+        assert_eq!(sim.pomodoro.timer.remaining_ms(40 * MIN), Some(5 * MIN));
+        sim.pomodoro.timer.start(40 * MIN);
+        sim.tick(45 * MIN);
+        assert_eq!(sim.pomodoro.phase, PomPhase::Work);
+        assert_eq!(sim.pomodoro.timer.state, TimerState::Stopped);
+    }
+
+    #[test]
+    fn test_auto_start_runs_next_phase() {
+        let mut sim = Sim::new(AppMode::Pomodoro);
+        assert!(sim.pomodoro.auto_start);
+        sim.pomodoro.timer.start(0);
+        let effects = sim.tick(25 * MIN);
+        assert!(effects.contains(&Effect::StartPomodoro));
+        assert!(!effects.contains(&Effect::StopPump));
+        assert_eq!(sim.pomodoro.timer.state, TimerState::Running);
+    }
+
+    #[test]
+    fn test_flexible_break_waits_for_user() {
+        let mut sim = Sim::new(AppMode::Pomodoro);
+        sim.pomodoro.flexible_break = true;
+        sim.pomodoro.timer.start(0);
+
+        sim.tick(25 * MIN);
+        assert!(sim.pomodoro.in_flexible_break());
+        assert_eq!(sim.pomodoro.timer.state, TimerState::Running);
+
+        // Hours later the break is still open
+        assert_eq!(sim.tick(3 * 60 * MIN), vec![Effect::Redraw]);
+        assert_eq!(sim.pomodoro.phase, PomPhase::ShortBreak);
+
+        assert_eq!(sim.pomodoro.end_flexible_break(3 * 60 * MIN + 25 * MIN), Some(3 * 60 * MIN));
+        assert_eq!(sim.pomodoro.phase, PomPhase::Work);
+    }
+
+    #[test]
+    fn test_full_pomodoro_cycle() {
+        let mut sim = Sim::new(AppMode::Pomodoro);
+        sim.pomodoro.timer.start(0);
+
+        assert_eq!(sim.tick(24 * MIN), vec![Effect::Redraw]);
+
+        let mut t = 25 * MIN;
+        assert_eq!(
+            sim.tick(t),
+            vec![
+                Effect::Expired(EventSource::Pomodoro),
+                Effect::SavePomodoroStats,
+                Effect::Alert(EventSource::Pomodoro, "Work done! Short break.".to_string()),
+                Effect::StartPomodoro,
+                Effect::Redraw,
+            ]
+        );
+        assert_eq!(sim.pomodoro.phase, PomPhase::ShortBreak);
+        assert_eq!(sim.pomodoro.timer.state, TimerState::Running);
+
+        t += 5 * MIN;
+        sim.tick(t);
+        assert_eq!(sim.pomodoro.phase, PomPhase::Work);
+
+        t += 25 * MIN;
+        sim.tick(t);
+        assert_eq!(sim.pomodoro.phase, PomPhase::LongBreak);
+
+        t += 15 * MIN;
+        sim.tick(t);
+        assert_eq!(sim.pomodoro.phase, PomPhase::Work);
+        assert_eq!(sim.pomodoro.current_cycle, 0);
+        assert_eq!(sim.pomodoro.total_completed, 2);
+        assert_eq!(
+            sim.alerts,
+            vec![
+                "Work done! Short break.",
+                "Break over! Time to work.",
+                "Work done! Long break.",
+                "Break over! Time to work.",
+            ]
+        );
+    }
+
+    /// Run a two-cycle set through to the end of its long break.
+    fn finish_set(sim: &mut Sim) -> Vec<Effect> {
+        sim.pomodoro.timer.start(0);
+        let mut t = 25 * MIN;
+        sim.tick(t);
+        t += 5 * MIN;
+        sim.tick(t);
+        t += 25 * MIN;
+        sim.tick(t);
+        assert_eq!(sim.pomodoro.phase, PomPhase::LongBreak);
+        t += 15 * MIN;
+        sim.tick(t)
+    }
+
+    #[test]
+    fn test_set_complete_loop() {
+        let mut sim = Sim::new(AppMode::Pomodoro);
+        let effects = finish_set(&mut sim);
+        assert!(effects.contains(&Effect::StartPomodoro));
+        assert_eq!(sim.pomodoro.phase, PomPhase::Work);
+        assert_eq!(sim.pomodoro.timer.state, TimerState::Running);
+        assert!(!sim.pomodoro.set_complete);
+    }
+
+    #[test]
+    fn test_set_complete_stop() {
+        let mut sim = Sim::new(AppMode::Pomodoro);
+        sim.pomodoro.on_set_complete = SetCompleteAction::Stop;
+        let effects = finish_set(&mut sim);
+        assert_eq!(
+            effects,
+            vec![
+                Effect::Expired(EventSource::Pomodoro),
+                Effect::StopPump,
+                Effect::Alert(EventSource::Pomodoro, "Set complete!".to_string()),
+                Effect::Redraw,
+            ]
+        );
+        assert_eq!(sim.pomodoro.phase, PomPhase::Work);
+        assert_eq!(sim.pomodoro.timer.state, TimerState::Stopped);
+        assert!(sim.pomodoro.set_complete);
+        assert_eq!(sim.pomodoro.total_completed, 2);
+    }
+
+    #[test]
+    fn test_set_complete_exit() {
+        let mut sim = Sim::new(AppMode::Pomodoro);
+        sim.pomodoro.on_set_complete = SetCompleteAction::Exit;
+        let effects = finish_set(&mut sim);
+        assert!(effects.contains(&Effect::Quit));
+        assert!(!effects.contains(&Effect::StartPomodoro));
+        assert_eq!(sim.pomodoro.timer.state, TimerState::Stopped);
+    }
+
+    #[test]
+    fn test_short_break_ignores_set_action() {
+        let mut sim = Sim::new(AppMode::Pomodoro);
+        sim.pomodoro.on_set_complete = SetCompleteAction::Stop;
+        sim.pomodoro.timer.start(0);
+        sim.tick(25 * MIN);
+        let effects = sim.tick(30 * MIN);
+        assert!(effects.contains(&Effect::StartPomodoro));
+        assert!(!sim.pomodoro.set_complete);
+    }
+
+    #[test]
+    fn test_countdown_expiry_returns_to_list() {
+        let mut sim = Sim::new(AppMode::CountdownRun);
+        sim.countdown.add_entry("Tea".to_string(), 3 * MIN);
+        sim.countdown.start_selected();
+        sim.countdown.active_timer_mut().unwrap().start(0);
+
+        assert_eq!(sim.tick(MIN), vec![Effect::Redraw]);
+        let effects = sim.tick(3 * MIN);
+        assert_eq!(
+            effects,
+            vec![
+                Effect::Expired(EventSource::Countdown),
+                Effect::StopPump,
+                Effect::SetMode(AppMode::CountdownList),
+                Effect::SnoozableAlert("Tea expired!".to_string(), 0),
+                Effect::Redraw,
+            ]
+        );
+        assert_eq!(sim.mode, AppMode::CountdownList);
+        assert!(sim.countdown.active_timer().is_none());
+    }
+
+    #[test]
+    fn test_concurrent_countdowns_expire_separately() {
+        let mut sim = Sim::new(AppMode::CountdownList);
+        sim.countdown.add_entry("Tea".to_string(), 3 * MIN);
+        sim.countdown.add_entry("Eggs".to_string(), 7 * MIN);
+        for idx in 0..2 {
+            sim.countdown.cursor = idx;
+            sim.countdown.start_selected();
+            sim.countdown.timer_mut(idx).unwrap().start(0);
+        }
+
+        // The first one goes off while the second keeps the pump alive
+        let effects = sim.tick(3 * MIN);
+        assert_eq!(
+            effects,
+            vec![
+                Effect::Expired(EventSource::Countdown),
+                Effect::SnoozableAlert("Tea expired!".to_string(), 0),
+                Effect::Redraw,
+            ]
+        );
+        assert!(sim.countdown.timer(0).is_none());
+        assert_eq!(sim.countdown.timer(1).unwrap().state, TimerState::Running);
+        assert_eq!(sim.countdown.timer(1).unwrap().remaining_ms(4 * MIN), Some(3 * MIN));
+
+        let effects = sim.tick(7 * MIN);
+        assert!(effects.contains(&Effect::StopPump));
+        assert_eq!(sim.alerts, vec!["Tea expired!", "Eggs expired!"]);
+        assert_eq!(sim.countdown.running_count(), 0);
+    }
+
+    #[test]
+    fn test_background_expiry_keeps_run_screen() {
+        let mut sim = Sim::new(AppMode::CountdownRun);
+        sim.countdown.add_entry("Tea".to_string(), 3 * MIN);
+        sim.countdown.add_entry("Eggs".to_string(), 7 * MIN);
+        sim.countdown.start_selected();
+        sim.countdown.timer_mut(0).unwrap().start(0);
+        sim.countdown.cursor = 1;
+        sim.countdown.start_selected();
+        sim.countdown.timer_mut(1).unwrap().start(0);
+
+        // Tea expires behind the Eggs screen
+        let effects = sim.tick(3 * MIN);
+        assert!(!effects.contains(&Effect::SetMode(AppMode::CountdownList)));
+        assert_eq!(sim.mode, AppMode::CountdownRun);
+        assert_eq!(sim.countdown.active_name(), Some("Eggs"));
+    }
+
+    #[test]
+    fn test_countdown_chain_advances_and_ends() {
+        let mut sim = Sim::new(AppMode::CountdownRun);
+        sim.countdown.add_entry("Squats".to_string(), 30 * 1000);
+        sim.countdown.add_entry("Rest".to_string(), 10 * 1000);
+        sim.countdown.add_entry("Plank".to_string(), MIN);
+        let chain = sim.countdown.save_chain(vec![0, 1, 2]).unwrap();
+        assert!(sim.countdown.start_chain(chain));
+        sim.countdown.active_timer_mut().unwrap().start(0);
+
+        let effects = sim.tick(30 * 1000);
+        assert_eq!(
+            effects,
+            vec![
+                Effect::Expired(EventSource::Countdown),
+                Effect::Alert(EventSource::Countdown, "Squats done, Rest next".to_string()),
+                Effect::StartCountdown(1),
+                Effect::Redraw,
+            ]
+        );
+        assert_eq!(sim.countdown.active_name(), Some("Rest"));
+        assert!(sim.countdown.timer(0).is_none());
+
+        sim.tick(40 * 1000);
+        assert_eq!(sim.countdown.active_name(), Some("Plank"));
+        assert_eq!(sim.countdown.chain.as_ref().unwrap().position, 2);
+
+        // The last step stops like a plain timer and ends the chain
+        let effects = sim.tick(100 * 1000);
+        assert!(effects.contains(&Effect::StopPump));
+        assert_eq!(sim.mode, AppMode::CountdownList);
+        assert!(sim.countdown.chain.is_none());
+        assert_eq!(sim.countdown.running_count(), 0);
+        assert_eq!(sim.alerts, vec!["Squats done, Rest next", "Rest done, Plank next", "Plank expired!"]);
+    }
+
+    #[test]
+    fn test_countdown_follow_on() {
+        let mut sim = Sim::new(AppMode::CountdownRun);
+        sim.countdown.add_entry("Work".to_string(), 30 * MIN);
+        sim.countdown.add_entry("Rest".to_string(), 5 * MIN);
+        sim.countdown.set_on_expiry_start(0, Some(1));
+        sim.countdown.start_selected();
+        sim.countdown.active_timer_mut().unwrap().start(0);
+
+        sim.tick(30 * MIN);
+        assert_eq!(sim.mode, AppMode::CountdownRun);
+        assert_eq!(sim.countdown.active_name(), Some("Rest"));
+        assert_eq!(sim.countdown.active_timer().unwrap().remaining_ms(31 * MIN), Some(4 * MIN));
+
+        sim.tick(35 * MIN);
+        assert_eq!(sim.mode, AppMode::CountdownList);
+        assert_eq!(sim.alerts, vec!["Work expired!", "Rest expired!"]);
+    }
+
+    #[test]
+    fn test_countdown_repeat_restarts() {
+        let mut sim = Sim::new(AppMode::CountdownRun);
+        sim.countdown.add_entry("Sprint".to_string(), MIN);
+        sim.countdown.toggle_repeat_selected();
+        sim.countdown.start_selected();
+        sim.countdown.active_timer_mut().unwrap().start(0);
+
+        for round in 1..=3 {
+            let effects = sim.tick(round * MIN);
+            assert!(effects.contains(&Effect::StartCountdown(0)));
+            assert!(!effects.contains(&Effect::StopPump));
+            assert_eq!(sim.mode, AppMode::CountdownRun);
+            assert_eq!(sim.countdown.active_loops(), round as u32);
+        }
+        assert_eq!(sim.alerts.len(), 3);
+        let timer = sim.countdown.active_timer().unwrap();
+        assert_eq!(timer.state, TimerState::Running);
+        assert_eq!(timer.remaining_ms(3 * MIN + 1000), Some(MIN - 1000));
+    }
+
+    #[test]
+    fn test_interval_runs_through_rounds() {
+        let mut sim = Sim::new(AppMode::Interval);
+        sim.interval.timer.start(0);
+
+        // Work -> rest -> round 2 work, each starting on its own
+        let effects = sim.tick(30_000);
+        assert_eq!(
+            effects,
+            vec![Effect::Alert(EventSource::Interval, "Rest!".to_string()), Effect::StartInterval, Effect::Redraw]
+        );
+        assert_eq!(sim.interval.header(35_000), "Round 1/2 — REST 00:05");
+        sim.tick(40_000);
+        assert_eq!(sim.interval.header(40_000), "Round 2/2 — WORK 00:30");
+        assert!(sim.interval.is_running());
+
+        // The last work stretch ends the workout and the pump
+        let effects = sim.tick(70_000);
+        assert_eq!(
+            effects,
+            vec![
+                Effect::Expired(EventSource::Interval),
+                Effect::StopPump,
+                Effect::Alert(EventSource::Interval, "Workout done!".to_string()),
+                Effect::Redraw,
+            ]
+        );
+        assert!(sim.interval.finished);
+        assert!(!sim.interval.is_running());
+        assert_eq!(sim.alerts, vec!["Rest!", "Round 2/2: work!", "Workout done!"]);
+    }
+
+    #[test]
+    fn test_idle_modes_stop_pump() {
+        let mut sim = Sim::new(AppMode::ModeSelect);
+        assert_eq!(sim.tick(0), vec![Effect::StopPump]);
+    }
+}
@@ -1,11 +1,80 @@
-use timer_core::TimerCore;
+use timer_core::{format_auto, TimerCore};
 
-const MAX_LAPS: usize = 99;
+pub const MAX_LAPS: usize = 99;
+
+/// Lifetime stopwatch usage, persisted across launches.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct StopwatchStats {
+    pub sessions: u32,
+    pub laps: u32,
+}
+
+impl StopwatchStats {
+    /// Account for a finished stopwatch session. Sessions that never ran
+    /// are ignored. Returns true if the stats changed.
+    pub fn record_session(&mut self, elapsed_ms: u64, laps: usize) -> bool {
+        if elapsed_ms == 0 {
+            return false;
+        }
+        self.sessions = self.sessions.saturating_add(1);
+        self.laps = self.laps.saturating_add(laps as u32);
+        true
+    }
+}
+
+/// One recorded lap.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Lap {
+    /// Time of this lap alone.
+    pub split_ms: u64,
+    /// Total elapsed when the lap was recorded.
+    pub total_ms: u64,
+}
+
+/// Rebuild laps from their splits, e.g. after loading. The totals are the
+/// running sums, since the stopwatch only ever adds time.
+pub fn laps_from_splits(splits: &[u64]) -> Vec<Lap> {
+    let mut total_ms = 0u64;
+    splits
+        .iter()
+        .map(|&split_ms| {
+            total_ms = total_ms.saturating_add(split_ms);
+            Lap { split_ms, total_ms }
+        })
+        .collect()
+}
+
+/// Persisted stopwatch preferences.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StopwatchSettings {
+    /// 0 = off.
+    pub reminder_interval_ms: u64,
+    /// 0 = off.
+    pub lap_alert_ms: u64,
+    pub lap_on_stop: bool,
+    pub show_cumulative: bool,
+}
 
 pub struct StopwatchState {
     pub timer: TimerCore,
-    pub laps: Vec<u64>,
+    pub laps: Vec<Lap>,
     pub lap_scroll_offset: usize,
+    /// Periodic "still running" reminder interval, 0 = off.
+    pub reminder_interval_ms: u64,
+    reminders_fired: u64,
+    /// Target lap count for a workout, None = open-ended.
+    pub lap_goal: Option<usize>,
+    goal_alerted: bool,
+    /// Alert when the current lap runs this long without a split, 0 = off.
+    pub lap_alert_ms: u64,
+    lap_alert_fired: bool,
+    /// Record the segment in progress as a final lap when stopping.
+    pub lap_on_stop: bool,
+    /// List laps by total elapsed at each lap instead of by split.
+    pub show_cumulative: bool,
+    /// Race against this elapsed time, None = plain stopwatch.
+    pub target_ms: Option<u64>,
+    target_alerted: bool,
 }
 
 impl StopwatchState {
@@ -14,23 +83,548 @@ impl StopwatchState {
             timer: TimerCore::new_stopwatch(),
             laps: Vec::new(),
             lap_scroll_offset: 0,
+            reminder_interval_ms: 0,
+            reminders_fired: 0,
+            lap_goal: None,
+            goal_alerted: false,
+            lap_alert_ms: 0,
+            lap_alert_fired: false,
+            lap_on_stop: false,
+            show_cumulative: false,
+            target_ms: None,
+            target_alerted: false,
         }
     }
 
-    pub fn record_lap(&mut self, now_ms: u64) {
+    pub fn settings(&self) -> StopwatchSettings {
+        StopwatchSettings {
+            reminder_interval_ms: self.reminder_interval_ms,
+            lap_alert_ms: self.lap_alert_ms,
+            lap_on_stop: self.lap_on_stop,
+            show_cumulative: self.show_cumulative,
+        }
+    }
+
+    /// Change the reminder interval. Intervals already passed at
+    /// `elapsed_ms` are treated as fired so the change doesn't trigger
+    /// an immediate reminder.
+    pub fn set_reminder_interval(&mut self, interval_ms: u64, elapsed_ms: u64) {
+        self.reminder_interval_ms = interval_ms;
+        self.reminders_fired = elapsed_ms.checked_div(interval_ms).unwrap_or(0);
+    }
+
+    /// Returns true once each time `elapsed_ms` crosses another multiple of
+    /// the reminder interval. Several missed crossings collapse into one.
+    pub fn check_reminder(&mut self, elapsed_ms: u64) -> bool {
+        if self.reminder_interval_ms == 0 {
+            return false;
+        }
+        let crossed = elapsed_ms / self.reminder_interval_ms;
+        if crossed > self.reminders_fired {
+            self.reminders_fired = crossed;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Change the overdue-lap threshold. A lap already past it at `now_ms`
+    /// counts as alerted, so the change doesn't buzz straight away.
+    pub fn set_lap_alert(&mut self, lap_alert_ms: u64, now_ms: u64) {
+        self.lap_alert_ms = lap_alert_ms;
+        self.lap_alert_fired = lap_alert_ms > 0 && self.timer.lap_elapsed_ms(now_ms) >= lap_alert_ms;
+    }
+
+    /// Returns true once per lap when the lap in progress has run for
+    /// `lap_alert_ms` without a split. Recording a lap re-arms it.
+    pub fn check_lap_overdue(&mut self, now_ms: u64) -> bool {
+        if self.lap_alert_ms == 0 || self.lap_alert_fired || !self.timer.state.is_active() {
+            return false;
+        }
+        if self.timer.lap_elapsed_ms(now_ms) >= self.lap_alert_ms {
+            self.lap_alert_fired = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record a lap split. Returns true if this lap reached the lap goal;
+    /// that only happens once per run.
+    pub fn record_lap(&mut self, now_ms: u64) -> bool {
         if self.laps.len() >= MAX_LAPS {
-            return;
+            return false;
         }
-        let lap_time = self.timer.lap(now_ms);
-        if lap_time > 0 {
-            self.laps.push(lap_time);
+        let split_ms = self.timer.lap(now_ms);
+        if split_ms > 0 {
+            self.laps.push(Lap { split_ms, total_ms: self.timer.elapsed_ms(now_ms) });
         }
+        self.lap_alert_fired = false;
+        self.check_lap_goal()
+    }
+
+    /// Stop a running stopwatch, first closing the segment in progress as
+    /// a lap if `lap_on_stop` is set. Returns true if that lap reached the
+    /// lap goal.
+    pub fn stop(&mut self, now_ms: u64) -> bool {
+        if !self.timer.state.is_active() {
+            return false;
+        }
+        let goal_reached = self.lap_on_stop && self.record_lap(now_ms);
+        self.timer.pause(now_ms);
+        goal_reached
+    }
+
+    /// Set or clear the lap goal. A goal already met by the recorded laps
+    /// won't alert again.
+    pub fn set_lap_goal(&mut self, goal: Option<usize>) {
+        self.lap_goal = goal.filter(|&g| g > 0).map(|g| g.min(MAX_LAPS));
+        self.goal_alerted = matches!(self.lap_goal, Some(g) if self.laps.len() >= g);
+    }
+
+    /// Set or clear the target time. A target already passed at `now_ms`
+    /// won't alert.
+    pub fn set_target(&mut self, target_ms: Option<u64>, now_ms: u64) {
+        self.target_ms = target_ms.filter(|&t| t > 0);
+        self.target_alerted = self.past_target(now_ms);
+    }
+
+    /// Time left until the target, zero once it's passed; None without a
+    /// target.
+    pub fn remaining_to_target(&self, now_ms: u64) -> Option<u64> {
+        self.target_ms.map(|t| t.saturating_sub(self.timer.elapsed_ms(now_ms)))
+    }
+
+    /// Time run past the target, None while short of it or without one.
+    pub fn over_target(&self, now_ms: u64) -> Option<u64> {
+        let target = self.target_ms?;
+        self.timer.elapsed_ms(now_ms).checked_sub(target)
+    }
+
+    pub fn past_target(&self, now_ms: u64) -> bool {
+        self.over_target(now_ms).is_some()
+    }
+
+    /// Returns true once per run when the elapsed time reaches the target.
+    pub fn check_target(&mut self, now_ms: u64) -> bool {
+        if self.target_alerted || !self.past_target(now_ms) {
+            return false;
+        }
+        self.target_alerted = true;
+        true
+    }
+
+    fn check_lap_goal(&mut self) -> bool {
+        match self.lap_goal {
+            Some(goal) if !self.goal_alerted && self.laps.len() >= goal => {
+                self.goal_alerted = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Current lap number and laps still to go, if a goal is set.
+    pub fn lap_progress(&self) -> Option<(usize, usize)> {
+        self.lap_goal.map(|goal| {
+            let current = (self.laps.len() + 1).min(goal);
+            (current, goal.saturating_sub(self.laps.len()))
+        })
+    }
+
+    /// The time the lap list shows for `lap`.
+    pub fn lap_display_ms(&self, lap: &Lap) -> u64 {
+        if self.show_cumulative {
+            lap.total_ms
+        } else {
+            lap.split_ms
+        }
+    }
+
+    /// Mean lap split, truncated to the millisecond; None with no laps.
+    pub fn average_lap_ms(&self) -> Option<u64> {
+        if self.laps.is_empty() {
+            return None;
+        }
+        let total: u64 = self.laps.iter().map(|lap| lap.split_ms).sum();
+        Some(total / self.laps.len() as u64)
+    }
+
+    /// Lap with the shortest split; the earliest wins a tie.
+    pub fn fastest_lap_index(&self) -> Option<usize> {
+        self.laps.iter().enumerate().min_by_key(|(_, lap)| lap.split_ms).map(|(idx, _)| idx)
+    }
+
+    /// Lap with the longest split; the earliest wins a tie.
+    pub fn slowest_lap_index(&self) -> Option<usize> {
+        // max_by_key keeps the last of equals, so search from the end
+        self.laps.iter().enumerate().rev().max_by_key(|(_, lap)| lap.split_ms).map(|(idx, _)| idx)
+    }
+
+    /// Scroll the lap list up, towards older laps. Returns false at the
+    /// oldest.
+    pub fn scroll_up(&mut self) -> bool {
+        if self.lap_scroll_offset + 1 >= self.laps.len() {
+            return false;
+        }
+        self.lap_scroll_offset += 1;
+        true
+    }
+
+    /// Scroll the lap list down, towards newer laps. Returns false at the
+    /// newest.
+    pub fn scroll_down(&mut self) -> bool {
+        if self.lap_scroll_offset == 0 {
+            return false;
+        }
+        self.lap_scroll_offset -= 1;
+        true
+    }
+
+    /// Keep a remembered scroll position valid for the current lap list.
+    pub fn clamp_scroll(&mut self) {
+        if self.lap_scroll_offset >= self.laps.len() {
+            self.lap_scroll_offset = self.laps.len().saturating_sub(1);
+        }
+    }
+
+    /// One-line plain-text status, e.g. "Stopwatch, 4:07 elapsed, 3 laps, running."
+    pub fn status_line(&self, now_ms: u64) -> String {
+        let plural = if self.laps.len() == 1 { "" } else { "s" };
+        format!(
+            "Stopwatch, {} elapsed, {} lap{}, {}.",
+            format_auto(self.timer.elapsed_ms(now_ms)),
+            self.laps.len(),
+            plural,
+            self.timer.state.label().to_lowercase()
+        )
     }
 
     pub fn reset(&mut self) {
         self.timer.reset();
         self.laps.clear();
         self.lap_scroll_offset = 0;
+        self.reminders_fired = 0;
+        self.goal_alerted = false;
+        self.lap_alert_fired = false;
+        self.target_alerted = false;
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use timer_core::TimerState;
+
+    const MIN: u64 = 60 * 1000;
+
+    #[test]
+    fn test_status_line() {
+        let mut sw = StopwatchState::new();
+        assert_eq!(sw.status_line(0), "Stopwatch, 0:00 elapsed, 0 laps, stopped.");
+        sw.timer.start(0);
+        sw.record_lap(30_000);
+        assert_eq!(sw.status_line(247_000), "Stopwatch, 4:07 elapsed, 1 lap, running.");
+        sw.timer.pause(3_700_000);
+        assert_eq!(sw.status_line(4_000_000), "Stopwatch, 1:01:40 elapsed, 1 lap, paused.");
+    }
+
+    #[test]
+    fn test_reminder_fires_once_per_interval() {
+        let mut sw = StopwatchState::new();
+        sw.set_reminder_interval(45 * MIN, 0);
+        assert!(!sw.check_reminder(44 * MIN));
+        assert!(sw.check_reminder(45 * MIN));
+        assert!(!sw.check_reminder(45 * MIN + 1000));
+        assert!(!sw.check_reminder(89 * MIN));
+        assert!(sw.check_reminder(90 * MIN));
+        // Missed crossings collapse into a single reminder
+        assert!(sw.check_reminder(200 * MIN));
+        assert!(!sw.check_reminder(201 * MIN));
+    }
+
+    fn splits(sw: &StopwatchState) -> Vec<u64> {
+        sw.laps.iter().map(|lap| lap.split_ms).collect()
+    }
+
+    #[test]
+    fn test_laps_keep_split_and_total() {
+        let mut sw = StopwatchState::new();
+        sw.timer.start(0);
+        sw.record_lap(5000);
+        sw.record_lap(8000);
+        assert_eq!(splits(&sw), vec![5000, 3000]);
+        let totals: Vec<u64> = sw.laps.iter().map(|lap| lap.total_ms).collect();
+        assert_eq!(totals, vec![5000, 8000]);
+
+        assert_eq!(sw.lap_display_ms(&sw.laps[1]), 3000);
+        sw.show_cumulative = true;
+        assert_eq!(sw.lap_display_ms(&sw.laps[1]), 8000);
+
+        // Totals rebuilt from stored splits match
+        assert_eq!(laps_from_splits(&[5000, 3000]), sw.laps);
+    }
+
+    #[test]
+    fn test_average_lap() {
+        let mut sw = StopwatchState::new();
+        assert_eq!(sw.average_lap_ms(), None);
+
+        sw.laps = laps_from_splits(&[4321]);
+        assert_eq!(sw.average_lap_ms(), Some(4321));
+
+        // 10_001 / 3 = 3333.67, truncated
+        sw.laps = laps_from_splits(&[3000, 3500, 3501]);
+        assert_eq!(sw.average_lap_ms(), Some(3333));
+    }
+
+    #[test]
+    fn test_fastest_and_slowest_laps() {
+        let mut sw = StopwatchState::new();
+        assert_eq!(sw.fastest_lap_index(), None);
+        assert_eq!(sw.slowest_lap_index(), None);
+
+        sw.laps = laps_from_splits(&[4000]);
+        assert_eq!(sw.fastest_lap_index(), Some(0));
+        assert_eq!(sw.slowest_lap_index(), Some(0));
+
+        sw.laps = laps_from_splits(&[4000, 3000, 6000, 3000, 6000, 5000]);
+        assert_eq!(sw.fastest_lap_index(), Some(1));
+        assert_eq!(sw.slowest_lap_index(), Some(2));
+    }
+
+    #[test]
+    fn test_stop_records_final_segment() {
+        let mut sw = StopwatchState::new();
+        sw.lap_on_stop = true;
+        sw.timer.start(0);
+        sw.record_lap(40_000);
+        sw.stop(65_000);
+        assert_eq!(splits(&sw), vec![40_000, 25_000]);
+        assert_eq!(sw.timer.state, TimerState::Paused);
+        assert_eq!(sw.timer.elapsed_ms(90_000), 65_000);
+
+        // Stopping again while paused adds nothing
+        sw.stop(95_000);
+        assert_eq!(sw.laps.len(), 2);
+
+        // The next segment starts fresh after resuming
+        sw.timer.resume(100_000);
+        sw.stop(110_000);
+        assert_eq!(splits(&sw), vec![40_000, 25_000, 10_000]);
+    }
+
+    #[test]
+    fn test_stop_without_setting_keeps_laps() {
+        let mut sw = StopwatchState::new();
+        sw.timer.start(0);
+        sw.record_lap(40_000);
+        sw.stop(65_000);
+        assert_eq!(splits(&sw), vec![40_000]);
+        assert_eq!(sw.timer.state, TimerState::Paused);
+    }
+
+    #[test]
+    fn test_stop_final_lap_meets_goal() {
+        let mut sw = StopwatchState::new();
+        sw.lap_on_stop = true;
+        sw.set_lap_goal(Some(2));
+        sw.timer.start(0);
+        assert!(!sw.record_lap(1000));
+        assert!(sw.stop(2500));
+    }
+
+    #[test]
+    fn test_lap_overdue_fires_once_per_lap() {
+        let mut sw = StopwatchState::new();
+        sw.lap_alert_ms = 2 * MIN;
+        sw.timer.start(0);
+        assert!(!sw.check_lap_overdue(MIN));
+        assert!(sw.check_lap_overdue(2 * MIN));
+        assert!(!sw.check_lap_overdue(5 * MIN));
+
+        // Lapping re-arms it, measured from the new lap's start
+        sw.record_lap(5 * MIN);
+        assert!(!sw.check_lap_overdue(6 * MIN));
+        assert!(sw.check_lap_overdue(7 * MIN));
+
+        sw.reset();
+        sw.timer.start(0);
+        assert!(sw.check_lap_overdue(3 * MIN));
+
+        // Tightening the threshold mid-lap doesn't alert for the current lap
+        sw.record_lap(4 * MIN);
+        sw.set_lap_alert(MIN, 6 * MIN);
+        assert!(!sw.check_lap_overdue(6 * MIN));
+        sw.record_lap(6 * MIN);
+        assert!(sw.check_lap_overdue(7 * MIN));
+    }
+
+    #[test]
+    fn test_lap_overdue_off_or_paused() {
+        let mut sw = StopwatchState::new();
+        sw.timer.start(0);
+        assert!(!sw.check_lap_overdue(60 * MIN));
 
+        sw.set_lap_alert(MIN, 0);
+        sw.timer.pause(30_000);
+        assert!(!sw.check_lap_overdue(10 * MIN));
+    }
+
+    #[test]
+    fn test_reminder_off_and_reset() {
+        let mut sw = StopwatchState::new();
+        assert!(!sw.check_reminder(500 * MIN));
+
+        sw.set_reminder_interval(10 * MIN, 0);
+        assert!(sw.check_reminder(10 * MIN));
+        sw.reset();
+        assert!(!sw.check_reminder(5 * MIN));
+        assert!(sw.check_reminder(10 * MIN));
+    }
+
+    #[test]
+    fn test_reminder_change_mid_run() {
+        let mut sw = StopwatchState::new();
+        sw.set_reminder_interval(5 * MIN, 23 * MIN);
+        assert!(!sw.check_reminder(24 * MIN));
+        assert!(sw.check_reminder(25 * MIN));
+    }
+
+    #[test]
+    fn test_scroll_bounds() {
+        let mut sw = StopwatchState::new();
+        assert!(!sw.scroll_up());
+        assert!(!sw.scroll_down());
+
+        sw.laps = laps_from_splits(&[1000, 2000, 3000]);
+        assert!(sw.scroll_up());
+        assert!(sw.scroll_up());
+        assert_eq!(sw.lap_scroll_offset, 2);
+        // The oldest lap is already at the top
+        assert!(!sw.scroll_up());
+        assert_eq!(sw.lap_scroll_offset, 2);
+
+        assert!(sw.scroll_down());
+        assert!(sw.scroll_down());
+        assert!(!sw.scroll_down());
+        assert_eq!(sw.lap_scroll_offset, 0);
+
+        sw.scroll_up();
+        sw.reset();
+        assert_eq!(sw.lap_scroll_offset, 0);
+    }
+
+    #[test]
+    fn test_scroll_kept_on_reentry() {
+        let mut sw = StopwatchState::new();
+        sw.laps = laps_from_splits(&[1000, 2000, 3000, 4000]);
+        sw.lap_scroll_offset = 2;
+        sw.clamp_scroll();
+        assert_eq!(sw.lap_scroll_offset, 2);
+
+        sw.laps.truncate(2);
+        sw.clamp_scroll();
+        assert_eq!(sw.lap_scroll_offset, 1);
+
+        sw.laps.clear();
+        sw.clamp_scroll();
+        assert_eq!(sw.lap_scroll_offset, 0);
+    }
+
+    #[test]
+    fn test_lap_goal_fires_once() {
+        let mut sw = StopwatchState::new();
+        sw.set_lap_goal(Some(3));
+        sw.timer.start(0);
+        assert!(!sw.record_lap(1000));
+        assert_eq!(sw.lap_progress(), Some((2, 2)));
+        assert!(!sw.record_lap(2000));
+        assert!(sw.record_lap(3000));
+        assert_eq!(sw.lap_progress(), Some((3, 0)));
+        // Extra laps past the goal don't alert again
+        assert!(!sw.record_lap(4000));
+        assert_eq!(sw.lap_progress(), Some((3, 0)));
+    }
+
+    #[test]
+    fn test_lap_goal_reset_and_change() {
+        let mut sw = StopwatchState::new();
+        assert_eq!(sw.lap_progress(), None);
+        sw.timer.start(0);
+        assert!(!sw.record_lap(1000));
+        assert!(!sw.record_lap(2000));
+
+        // Goal already met when set: no alert
+        sw.set_lap_goal(Some(2));
+        assert!(!sw.record_lap(3000));
+
+        // Reset re-arms the goal
+        sw.reset();
+        sw.timer.start(0);
+        assert!(!sw.record_lap(1000));
+        assert!(sw.record_lap(2000));
+
+        sw.set_lap_goal(Some(0));
+        assert_eq!(sw.lap_goal, None);
+    }
+
+    #[test]
+    fn test_no_target_is_plain_stopwatch() {
+        let mut sw = StopwatchState::new();
+        sw.timer.start(0);
+        assert_eq!(sw.remaining_to_target(90 * MIN), None);
+        assert_eq!(sw.over_target(90 * MIN), None);
+        assert!(!sw.check_target(90 * MIN));
+        assert_eq!(sw.timer.elapsed_ms(90 * MIN), 90 * MIN);
+    }
+
+    #[test]
+    fn test_target_remaining_and_overrun() {
+        let mut sw = StopwatchState::new();
+        sw.set_target(Some(10 * MIN), 0);
+        sw.timer.start(0);
+        assert_eq!(sw.remaining_to_target(4 * MIN), Some(6 * MIN));
+        assert!(!sw.check_target(4 * MIN));
+        // Laps still record against the running total
+        sw.record_lap(4 * MIN);
+        assert_eq!(sw.laps[0].total_ms, 4 * MIN);
+        assert!(sw.check_target(10 * MIN));
+        assert!(!sw.check_target(11 * MIN));
+        assert_eq!(sw.remaining_to_target(11 * MIN), Some(0));
+        assert_eq!(sw.over_target(11 * MIN), Some(MIN));
+
+        // Reset keeps the target and re-arms the alert
+        sw.reset();
+        sw.timer.start(0);
+        assert!(sw.check_target(10 * MIN));
+
+        // A target already passed doesn't alert when set
+        sw.set_target(Some(5 * MIN), 12 * MIN);
+        assert!(!sw.check_target(12 * MIN));
+        sw.set_target(Some(0), 12 * MIN);
+        assert_eq!(sw.target_ms, None);
+    }
+
+    #[test]
+    fn test_stats_record_session() {
+        let mut stats = StopwatchStats::default();
+        assert!(stats.record_session(5000, 3));
+        assert!(stats.record_session(1200, 0));
+        assert_eq!(stats, StopwatchStats { sessions: 2, laps: 3 });
+    }
+
+    #[test]
+    fn test_stats_ignore_unused_session() {
+        let mut stats = StopwatchStats::default();
+        assert!(!stats.record_session(0, 0));
+        assert_eq!(stats, StopwatchStats::default());
+    }
+
+    #[test]
+    fn test_stats_saturate() {
+        let mut stats = StopwatchStats { sessions: u32::MAX, laps: u32::MAX - 1 };
+        stats.record_session(1, 5);
+        assert_eq!(stats, StopwatchStats { sessions: u32::MAX, laps: u32::MAX });
+    }
 }
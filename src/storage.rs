@@ -1,12 +1,124 @@
 use std::io::{Read, Write, Seek, SeekFrom};
 
-use crate::countdown::CountdownEntry;
-use crate::alerts::AlertConfig;
+use timer_core::{deserialize_u64, TimerCore, TIMER_SNAPSHOT_LEN, TIMER_SNAPSHOT_MIN_LEN};
+
+use crate::countdown::{CountdownChain, CountdownEntry, CountdownSettings};
+use crate::interval::IntervalSettings;
+use crate::alerts::{AlertConfig, AlertVibe, MAX_SNOOZE_MS, MINUTES_PER_DAY};
+use crate::modes::ModeOrder;
+use crate::pomodoro::{format_sequence, parse_sequence, PomPhase, PomodoroSettings, PomodoroStats, SetCompleteAction};
+use crate::stopwatch::{laps_from_splits, StopwatchSettings, StopwatchState, StopwatchStats, MAX_LAPS};
 
 const DICT_NAME: &str = "timers";
 const KEY_POMODORO: &str = "pomodoro_settings";
 const KEY_ALERTS: &str = "alert_config";
 const KEY_COUNTDOWNS: &str = "countdowns";
+const KEY_MODE_ORDER: &str = "mode_order";
+const KEY_STOPWATCH_STATS: &str = "stopwatch_stats";
+const KEY_STOPWATCH_SETTINGS: &str = "stopwatch_settings";
+const KEY_COUNTDOWN_SETTINGS: &str = "countdown_settings";
+const KEY_RESUME_PROMPT: &str = "resume_prompt";
+const KEY_BACKGROUND_PAUSE: &str = "background_pause";
+const KEY_STOPWATCH: &str = "stopwatch";
+const KEY_POMODORO_STATS: &str = "pomodoro_stats";
+const KEY_POMODORO_TASK: &str = "pomodoro_task";
+const KEY_POMODORO_SEQUENCE: &str = "pomodoro_sequence";
+const KEY_COUNTDOWN_CHAINS: &str = "countdown_chains";
+const KEY_INTERVAL_SETTINGS: &str = "interval_settings";
+
+/// Where the system clock keeps the user's timezone, as an i64 offset
+/// from UTC in seconds.
+const RTC_DICT_NAME: &str = "sys.rtc";
+const KEY_TZ_OFFSET: &str = "tz_offset";
+
+/// Layout versions written at the front of the versioned blobs. A layout
+/// change bumps these and adds a `migrate_v0_to_v1` step (and so on) that
+/// upgrades older bytes before they reach the current parser.
+const POMODORO_VERSION: u8 = 0;
+const ALERTS_VERSION: u8 = 0;
+const COUNTDOWNS_VERSION: u8 = 0;
+const STOPWATCH_VERSION: u8 = 1;
+/// Stopwatch layout with the timer snapshot from before time scaling.
+const STOPWATCH_V0: u8 = 0;
+const POMODORO_STATS_VERSION: u8 = 0;
+const POMODORO_TASK_VERSION: u8 = 0;
+const POMODORO_SEQUENCE_VERSION: u8 = 0;
+const COUNTDOWN_CHAINS_VERSION: u8 = 0;
+const INTERVAL_SETTINGS_VERSION: u8 = 0;
+
+// Payload lengths, not counting the version byte. The minimums are the
+// original layouts; later fields were appended and are optional on read.
+/// 3 * u64 durations + cycles + flags + end-of-set action.
+const POMODORO_LEN: usize = 27;
+/// Durations + cycles.
+const POMODORO_MIN_LEN: usize = 25;
+/// Vibration, audio, notification, soft start, escalate, vibe pattern,
+/// snooze u64, quiet hours start and end u16, LED flash.
+const ALERTS_LEN: usize = 19;
+/// Vibration, audio, notification.
+const ALERTS_MIN_LEN: usize = 3;
+/// Longest layout saved before the version byte: up to soft start.
+const ALERTS_UNVERSIONED_MAX_LEN: usize = 4;
+const CRC_LEN: usize = 4;
+/// Body length stored after the CRC.
+const LEN_LEN: usize = 4;
+
+/// Little-endian reader over a stored blob. Every read is bounds-checked
+/// and returns None past the end, so parsers can use `?`.
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8).and_then(deserialize_u64)
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+}
+
+/// Version byte, then the payload.
+fn write_versioned(version: u8, payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(1 + payload.len());
+    data.push(version);
+    data.extend_from_slice(payload);
+    data
+}
+
+/// Cursor over the payload if `data` starts with `version`.
+fn read_versioned(data: &[u8], version: u8) -> Option<Cursor<'_>> {
+    let mut cursor = Cursor::new(data);
+    if cursor.u8()? == version {
+        Some(cursor)
+    } else {
+        None
+    }
+}
 
 pub struct TimerStorage {
     pddb: pddb::Pddb,
@@ -19,17 +131,13 @@ impl TimerStorage {
         Self { pddb }
     }
 
-    pub fn load_pomodoro_settings(&self) -> Option<(u64, u64, u64, u8)> {
+    pub fn load_pomodoro_settings(&self) -> Option<PomodoroSettings> {
         match self.pddb.get(DICT_NAME, KEY_POMODORO, None, false, false, None, None::<fn()>) {
             Ok(mut key) => {
-                let mut buf = [0u8; 25]; // 3 * u64 + 1 * u8
+                let mut data = Vec::new();
                 key.seek(SeekFrom::Start(0)).ok();
-                if key.read_exact(&mut buf).is_ok() {
-                    let work = u64::from_le_bytes(buf[0..8].try_into().unwrap());
-                    let short = u64::from_le_bytes(buf[8..16].try_into().unwrap());
-                    let long = u64::from_le_bytes(buf[16..24].try_into().unwrap());
-                    let cycles = buf[24];
-                    Some((work, short, long, cycles))
+                if key.read_to_end(&mut data).is_ok() {
+                    deserialize_pomodoro_settings(&data)
                 } else {
                     None
                 }
@@ -38,14 +146,9 @@ impl TimerStorage {
         }
     }
 
-    pub fn save_pomodoro_settings(&self, work: u64, short: u64, long: u64, cycles: u8) {
-        let mut data = [0u8; 25];
-        data[0..8].copy_from_slice(&work.to_le_bytes());
-        data[8..16].copy_from_slice(&short.to_le_bytes());
-        data[16..24].copy_from_slice(&long.to_le_bytes());
-        data[24] = cycles;
-
-        match self.pddb.get(DICT_NAME, KEY_POMODORO, None, true, true, Some(25), None::<fn()>) {
+    pub fn save_pomodoro_settings(&self, settings: &PomodoroSettings) {
+        let data = serialize_pomodoro_settings(settings);
+        match self.pddb.get(DICT_NAME, KEY_POMODORO, None, true, true, Some(data.len()), None::<fn()>) {
             Ok(mut key) => {
                 key.seek(SeekFrom::Start(0)).ok();
                 key.write_all(&data).ok();
@@ -58,14 +161,10 @@ impl TimerStorage {
     pub fn load_alert_config(&self) -> AlertConfig {
         match self.pddb.get(DICT_NAME, KEY_ALERTS, None, false, false, None, None::<fn()>) {
             Ok(mut key) => {
-                let mut buf = [0u8; 3];
+                let mut buf = Vec::new();
                 key.seek(SeekFrom::Start(0)).ok();
-                if key.read_exact(&mut buf).is_ok() {
-                    AlertConfig {
-                        vibration: buf[0] != 0,
-                        audio: buf[1] != 0,
-                        notification: buf[2] != 0,
-                    }
+                if key.read_to_end(&mut buf).is_ok() {
+                    deserialize_alert_config(&buf).unwrap_or_else(AlertConfig::default)
                 } else {
                     AlertConfig::default()
                 }
@@ -75,13 +174,8 @@ impl TimerStorage {
     }
 
     pub fn save_alert_config(&self, config: &AlertConfig) {
-        let data = [
-            config.vibration as u8,
-            config.audio as u8,
-            config.notification as u8,
-        ];
-
-        match self.pddb.get(DICT_NAME, KEY_ALERTS, None, true, true, Some(3), None::<fn()>) {
+        let data = serialize_alert_config(config);
+        match self.pddb.get(DICT_NAME, KEY_ALERTS, None, true, true, Some(data.len()), None::<fn()>) {
             Ok(mut key) => {
                 key.seek(SeekFrom::Start(0)).ok();
                 key.write_all(&data).ok();
@@ -117,9 +211,625 @@ impl TimerStorage {
             Err(e) => log::error!("Failed to save countdowns: {:?}", e),
         }
     }
+
+    /// Saved countdown chains; none if unset or unreadable.
+    pub fn load_countdown_chains(&self) -> Vec<CountdownChain> {
+        match self.pddb.get(DICT_NAME, KEY_COUNTDOWN_CHAINS, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut data = Vec::new();
+                key.seek(SeekFrom::Start(0)).ok();
+                if key.read_to_end(&mut data).is_ok() {
+                    deserialize_countdown_chains(&data).unwrap_or_default()
+                } else {
+                    Vec::new()
+                }
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    pub fn save_countdown_chains(&self, chains: &[CountdownChain]) {
+        let data = serialize_countdown_chains(chains);
+        match self.pddb.get(DICT_NAME, KEY_COUNTDOWN_CHAINS, None, true, true, Some(data.len()), None::<fn()>) {
+            Ok(mut key) => {
+                key.seek(SeekFrom::Start(0)).ok();
+                key.write_all(&data).ok();
+                self.pddb.sync().ok();
+            }
+            Err(e) => log::error!("Failed to save countdown chains: {:?}", e),
+        }
+    }
+
+    pub fn load_interval_settings(&self) -> Option<IntervalSettings> {
+        match self.pddb.get(DICT_NAME, KEY_INTERVAL_SETTINGS, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut data = Vec::new();
+                key.seek(SeekFrom::Start(0)).ok();
+                if key.read_to_end(&mut data).is_ok() {
+                    deserialize_interval_settings(&data)
+                } else {
+                    None
+                }
+            }
+            Err(_) => None,
+        }
+    }
+
+    pub fn save_interval_settings(&self, settings: &IntervalSettings) {
+        let data = serialize_interval_settings(settings);
+        match self.pddb.get(DICT_NAME, KEY_INTERVAL_SETTINGS, None, true, true, Some(data.len()), None::<fn()>) {
+            Ok(mut key) => {
+                key.seek(SeekFrom::Start(0)).ok();
+                key.write_all(&data).ok();
+                self.pddb.sync().ok();
+            }
+            Err(e) => log::error!("Failed to save interval settings: {:?}", e),
+        }
+    }
+
+    /// The stopwatch run and its laps, as saved by `save_stopwatch`.
+    /// Comes back paused; None if nothing (valid) was stored.
+    pub fn load_stopwatch(&self) -> Option<StopwatchState> {
+        match self.pddb.get(DICT_NAME, KEY_STOPWATCH, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut data = Vec::new();
+                key.seek(SeekFrom::Start(0)).ok();
+                if key.read_to_end(&mut data).is_ok() {
+                    deserialize_stopwatch(&data)
+                } else {
+                    None
+                }
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Save the run so laps survive a restart or reboot. A running
+    /// stopwatch is stored as paused at `now_ms`, since its segment start
+    /// is a ticktimer reading that a reboot resets.
+    pub fn save_stopwatch(&self, state: &StopwatchState, now_ms: u64) {
+        let data = serialize_stopwatch(state, now_ms);
+        match self.pddb.get(DICT_NAME, KEY_STOPWATCH, None, true, true, Some(data.len()), None::<fn()>) {
+            Ok(mut key) => {
+                key.seek(SeekFrom::Start(0)).ok();
+                key.write_all(&data).ok();
+                self.pddb.sync().ok();
+            }
+            Err(e) => log::error!("Failed to save stopwatch: {:?}", e),
+        }
+    }
+
+    pub fn load_stopwatch_settings(&self) -> Option<StopwatchSettings> {
+        match self.pddb.get(DICT_NAME, KEY_STOPWATCH_SETTINGS, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut data = Vec::new();
+                key.seek(SeekFrom::Start(0)).ok();
+                // reminder interval u64, then optional lap alert u64 and flags
+                if key.read_to_end(&mut data).is_ok() {
+                    let lap_alert_ms = data.get(8..).and_then(deserialize_u64).unwrap_or(0);
+                    let flags = data.get(16).copied().unwrap_or(0);
+                    deserialize_u64(&data).map(|reminder_interval_ms| StopwatchSettings {
+                        reminder_interval_ms,
+                        lap_alert_ms,
+                        lap_on_stop: flags & 0x01 != 0,
+                        show_cumulative: flags & 0x02 != 0,
+                    })
+                } else {
+                    None
+                }
+            }
+            Err(_) => None,
+        }
+    }
+
+    pub fn save_stopwatch_settings(&self, settings: &StopwatchSettings) {
+        let mut data = [0u8; 17];
+        data[0..8].copy_from_slice(&settings.reminder_interval_ms.to_le_bytes());
+        data[8..16].copy_from_slice(&settings.lap_alert_ms.to_le_bytes());
+        data[16] = settings.lap_on_stop as u8 | (settings.show_cumulative as u8) << 1;
+        match self.pddb.get(DICT_NAME, KEY_STOPWATCH_SETTINGS, None, true, true, Some(17), None::<fn()>) {
+            Ok(mut key) => {
+                key.seek(SeekFrom::Start(0)).ok();
+                key.write_all(&data).ok();
+                self.pddb.sync().ok();
+            }
+            Err(e) => log::error!("Failed to save stopwatch settings: {:?}", e),
+        }
+    }
+
+    pub fn load_countdown_settings(&self) -> Option<CountdownSettings> {
+        match self.pddb.get(DICT_NAME, KEY_COUNTDOWN_SETTINGS, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut data = Vec::new();
+                key.seek(SeekFrom::Start(0)).ok();
+                // default duration u64, then an optional flags byte
+                if key.read_to_end(&mut data).is_ok() {
+                    let flags = data.get(8).copied();
+                    deserialize_u64(&data).map(|default_ms| CountdownSettings {
+                        default_ms,
+                        // Older records without flags get the elapsed line
+                        show_elapsed: flags.is_none_or(|f| f & 0x01 != 0),
+                    })
+                } else {
+                    None
+                }
+            }
+            Err(_) => None,
+        }
+    }
+
+    pub fn save_countdown_settings(&self, settings: &CountdownSettings) {
+        let mut data = [0u8; 9];
+        data[0..8].copy_from_slice(&settings.default_ms.to_le_bytes());
+        data[8] = settings.show_elapsed as u8;
+        match self.pddb.get(DICT_NAME, KEY_COUNTDOWN_SETTINGS, None, true, true, Some(9), None::<fn()>) {
+            Ok(mut key) => {
+                key.seek(SeekFrom::Start(0)).ok();
+                key.write_all(&data).ok();
+                self.pddb.sync().ok();
+            }
+            Err(e) => log::error!("Failed to save countdown settings: {:?}", e),
+        }
+    }
+
+    pub fn load_stopwatch_stats(&self) -> StopwatchStats {
+        match self.pddb.get(DICT_NAME, KEY_STOPWATCH_STATS, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut buf = [0u8; 8]; // 2 * u32
+                key.seek(SeekFrom::Start(0)).ok();
+                if key.read_exact(&mut buf).is_ok() {
+                    StopwatchStats {
+                        sessions: u32::from_le_bytes(buf[0..4].try_into().unwrap()),
+                        laps: u32::from_le_bytes(buf[4..8].try_into().unwrap()),
+                    }
+                } else {
+                    StopwatchStats::default()
+                }
+            }
+            Err(_) => StopwatchStats::default(),
+        }
+    }
+
+    pub fn save_stopwatch_stats(&self, stats: &StopwatchStats) {
+        let mut data = [0u8; 8];
+        data[0..4].copy_from_slice(&stats.sessions.to_le_bytes());
+        data[4..8].copy_from_slice(&stats.laps.to_le_bytes());
+
+        match self.pddb.get(DICT_NAME, KEY_STOPWATCH_STATS, None, true, true, Some(8), None::<fn()>) {
+            Ok(mut key) => {
+                key.seek(SeekFrom::Start(0)).ok();
+                key.write_all(&data).ok();
+                self.pddb.sync().ok();
+            }
+            Err(e) => log::error!("Failed to save stopwatch stats: {:?}", e),
+        }
+    }
+
+    pub fn load_pomodoro_stats(&self) -> PomodoroStats {
+        match self.pddb.get(DICT_NAME, KEY_POMODORO_STATS, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut data = Vec::new();
+                key.seek(SeekFrom::Start(0)).ok();
+                if key.read_to_end(&mut data).is_ok() {
+                    deserialize_pomodoro_stats(&data).unwrap_or_default()
+                } else {
+                    PomodoroStats::default()
+                }
+            }
+            Err(_) => PomodoroStats::default(),
+        }
+    }
+
+    pub fn save_pomodoro_stats(&self, stats: &PomodoroStats) {
+        let data = serialize_pomodoro_stats(stats);
+        match self.pddb.get(DICT_NAME, KEY_POMODORO_STATS, None, true, true, Some(data.len()), None::<fn()>) {
+            Ok(mut key) => {
+                key.seek(SeekFrom::Start(0)).ok();
+                key.write_all(&data).ok();
+                self.pddb.sync().ok();
+            }
+            Err(e) => log::error!("Failed to save pomodoro stats: {:?}", e),
+        }
+    }
+
+    /// The pomodoro task label, or None if unset or unreadable.
+    pub fn load_pomodoro_task(&self) -> Option<String> {
+        match self.pddb.get(DICT_NAME, KEY_POMODORO_TASK, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut data = Vec::new();
+                key.seek(SeekFrom::Start(0)).ok();
+                if key.read_to_end(&mut data).is_ok() {
+                    deserialize_pomodoro_task(&data)
+                } else {
+                    None
+                }
+            }
+            Err(_) => None,
+        }
+    }
+
+    pub fn save_pomodoro_task(&self, task: Option<&str>) {
+        let data = serialize_pomodoro_task(task);
+        match self.pddb.get(DICT_NAME, KEY_POMODORO_TASK, None, true, true, Some(data.len()), None::<fn()>) {
+            Ok(mut key) => {
+                key.seek(SeekFrom::Start(0)).ok();
+                key.write_all(&data).ok();
+                self.pddb.sync().ok();
+            }
+            Err(e) => log::error!("Failed to save pomodoro task: {:?}", e),
+        }
+    }
+
+    /// The custom pomodoro sequence; empty (the classic pattern) if unset
+    /// or unreadable.
+    pub fn load_pomodoro_sequence(&self) -> Vec<(PomPhase, u64)> {
+        match self.pddb.get(DICT_NAME, KEY_POMODORO_SEQUENCE, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut data = Vec::new();
+                key.seek(SeekFrom::Start(0)).ok();
+                if key.read_to_end(&mut data).is_ok() {
+                    deserialize_pomodoro_sequence(&data).unwrap_or_default()
+                } else {
+                    Vec::new()
+                }
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    pub fn save_pomodoro_sequence(&self, sequence: &[(PomPhase, u64)]) {
+        let data = serialize_pomodoro_sequence(sequence);
+        match self.pddb.get(DICT_NAME, KEY_POMODORO_SEQUENCE, None, true, true, Some(data.len()), None::<fn()>) {
+            Ok(mut key) => {
+                key.seek(SeekFrom::Start(0)).ok();
+                key.write_all(&data).ok();
+                self.pddb.sync().ok();
+            }
+            Err(e) => log::error!("Failed to save pomodoro sequence: {:?}", e),
+        }
+    }
+
+    pub fn load_resume_prompt(&self) -> bool {
+        self.load_flag(KEY_RESUME_PROMPT)
+    }
+
+    pub fn save_resume_prompt(&self, enabled: bool) {
+        self.save_flag(KEY_RESUME_PROMPT, enabled);
+    }
+
+    pub fn load_background_pause(&self) -> bool {
+        self.load_flag(KEY_BACKGROUND_PAUSE)
+    }
+
+    pub fn save_background_pause(&self, enabled: bool) {
+        self.save_flag(KEY_BACKGROUND_PAUSE, enabled);
+    }
+
+    /// The system timezone offset in seconds; UTC if it was never set.
+    pub fn load_tz_offset_s(&self) -> i64 {
+        match self.pddb.get(RTC_DICT_NAME, KEY_TZ_OFFSET, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut buf = [0u8; 8];
+                key.seek(SeekFrom::Start(0)).ok();
+                if key.read_exact(&mut buf).is_ok() {
+                    i64::from_le_bytes(buf)
+                } else {
+                    0
+                }
+            }
+            Err(_) => 0,
+        }
+    }
+
+    /// Single-byte on/off setting; missing means off.
+    fn load_flag(&self, key_name: &str) -> bool {
+        match self.pddb.get(DICT_NAME, key_name, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut buf = [0u8; 1];
+                key.seek(SeekFrom::Start(0)).ok();
+                key.read_exact(&mut buf).is_ok() && buf[0] != 0
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn save_flag(&self, key_name: &str, enabled: bool) {
+        match self.pddb.get(DICT_NAME, key_name, None, true, true, Some(1), None::<fn()>) {
+            Ok(mut key) => {
+                key.seek(SeekFrom::Start(0)).ok();
+                key.write_all(&[enabled as u8]).ok();
+                self.pddb.sync().ok();
+            }
+            Err(e) => log::error!("Failed to save {} setting: {:?}", key_name, e),
+        }
+    }
+
+    pub fn load_mode_order(&self) -> ModeOrder {
+        match self.pddb.get(DICT_NAME, KEY_MODE_ORDER, None, false, false, None, None::<fn()>) {
+            Ok(mut key) => {
+                let mut data = Vec::new();
+                key.seek(SeekFrom::Start(0)).ok();
+                if key.read_to_end(&mut data).is_ok() {
+                    ModeOrder::from_bytes(&data)
+                } else {
+                    ModeOrder::new()
+                }
+            }
+            Err(_) => ModeOrder::new(),
+        }
+    }
+
+    pub fn save_mode_order(&self, order: &ModeOrder) {
+        let data = order.to_bytes();
+        match self.pddb.get(DICT_NAME, KEY_MODE_ORDER, None, true, true, Some(data.len()), None::<fn()>) {
+            Ok(mut key) => {
+                key.seek(SeekFrom::Start(0)).ok();
+                key.write_all(&data).ok();
+                self.pddb.sync().ok();
+            }
+            Err(e) => log::error!("Failed to save mode order: {:?}", e),
+        }
+    }
 }
 
+fn serialize_pomodoro_settings(settings: &PomodoroSettings) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(POMODORO_LEN);
+    payload.extend_from_slice(&settings.work_ms.to_le_bytes());
+    payload.extend_from_slice(&settings.short_ms.to_le_bytes());
+    payload.extend_from_slice(&settings.long_ms.to_le_bytes());
+    payload.push(settings.cycles);
+    // Bit 2 is set when auto-start is off, so older blobs keep auto-starting
+    payload.push(
+        settings.auto_extend_break as u8
+            | (settings.flexible_break as u8) << 1
+            | (!settings.auto_start as u8) << 2,
+    );
+    payload.push(settings.on_set_complete.to_byte());
+    write_versioned(POMODORO_VERSION, &payload)
+}
+
+/// None means "use defaults": the blob is damaged or from a newer version.
+fn deserialize_pomodoro_settings(data: &[u8]) -> Option<PomodoroSettings> {
+    // Saved before the version byte existed: the bare v0 layout
+    if (POMODORO_MIN_LEN..=POMODORO_LEN).contains(&data.len()) {
+        return deserialize_pomodoro_v0(Cursor::new(data));
+    }
+    match read_versioned(data, POMODORO_VERSION) {
+        Some(cursor) => deserialize_pomodoro_v0(cursor),
+        None => {
+            log::warn!("Unknown pomodoro settings version");
+            None
+        }
+    }
+}
+
+/// Durations and cycle count are required; flags and end-of-set action
+/// were added later and default to off / `Loop`.
+fn deserialize_pomodoro_v0(mut cursor: Cursor) -> Option<PomodoroSettings> {
+    let work_ms = cursor.u64()?;
+    let short_ms = cursor.u64()?;
+    let long_ms = cursor.u64()?;
+    let cycles = cursor.u8()?;
+    let flags = cursor.u8().unwrap_or(0);
+    let on_set_complete = cursor.u8().unwrap_or(0);
+    Some(PomodoroSettings {
+        work_ms,
+        short_ms,
+        long_ms,
+        cycles,
+        auto_extend_break: flags & 0x01 != 0,
+        on_set_complete: SetCompleteAction::from_byte(on_set_complete),
+        flexible_break: flags & 0x02 != 0,
+        auto_start: flags & 0x04 == 0,
+    })
+}
+
+fn serialize_alert_config(config: &AlertConfig) -> Vec<u8> {
+    let mut payload = [0u8; ALERTS_LEN];
+    payload[..6].copy_from_slice(&[
+        config.vibration as u8,
+        config.audio as u8,
+        config.notification as u8,
+        config.soft_start_vibe as u8,
+        config.escalate as u8,
+        config.vibe.to_byte(),
+    ]);
+    payload[6..14].copy_from_slice(&config.snooze_ms.to_le_bytes());
+    payload[14..16].copy_from_slice(&config.quiet_start_min.to_le_bytes());
+    payload[16..18].copy_from_slice(&config.quiet_end_min.to_le_bytes());
+    payload[18] = config.led as u8;
+    write_versioned(ALERTS_VERSION, &payload)
+}
+
+fn deserialize_alert_config(data: &[u8]) -> Option<AlertConfig> {
+    let mut cursor = if (ALERTS_MIN_LEN..=ALERTS_UNVERSIONED_MAX_LEN).contains(&data.len()) {
+        // Saved before the version byte existed
+        Cursor::new(data)
+    } else {
+        read_versioned(data, ALERTS_VERSION)?
+    };
+    let defaults = AlertConfig::default();
+    Some(AlertConfig {
+        vibration: cursor.u8()? != 0,
+        audio: cursor.u8()? != 0,
+        notification: cursor.u8()? != 0,
+        soft_start_vibe: cursor.u8().map(|b| b != 0).unwrap_or(false),
+        escalate: cursor.u8().map(|b| b != 0).unwrap_or(false),
+        vibe: cursor.u8().map(AlertVibe::from_byte).unwrap_or(defaults.vibe),
+        snooze_ms: cursor.u64().map(|ms| ms.min(MAX_SNOOZE_MS)).unwrap_or(defaults.snooze_ms),
+        quiet_start_min: cursor.u16().map(|m| m % MINUTES_PER_DAY).unwrap_or(defaults.quiet_start_min),
+        quiet_end_min: cursor.u16().map(|m| m % MINUTES_PER_DAY).unwrap_or(defaults.quiet_end_min),
+        led: cursor.u8().map(|b| b != 0).unwrap_or(false),
+    })
+}
+
+/// Version byte, timer snapshot (paused at `now_ms`), lap count u8,
+/// then each lap's split u64. Totals are rebuilt on load.
+fn serialize_stopwatch(state: &StopwatchState, now_ms: u64) -> Vec<u8> {
+    let mut frozen = state.timer.clone();
+    frozen.pause(now_ms);
+    let snapshot = frozen.serialize();
+    let laps = &state.laps[..state.laps.len().min(MAX_LAPS)];
+    let mut payload = Vec::with_capacity(TIMER_SNAPSHOT_LEN + 1 + laps.len() * 8);
+    payload.extend_from_slice(&snapshot);
+    payload.push(laps.len() as u8);
+    for lap in laps {
+        payload.extend_from_slice(&lap.split_ms.to_le_bytes());
+    }
+    write_versioned(STOPWATCH_VERSION, &payload)
+}
+
+fn deserialize_stopwatch(data: &[u8]) -> Option<StopwatchState> {
+    let (mut cursor, snapshot_len) = match read_versioned(data, STOPWATCH_VERSION) {
+        Some(cursor) => (cursor, TIMER_SNAPSHOT_LEN),
+        None => (read_versioned(data, STOPWATCH_V0)?, TIMER_SNAPSHOT_MIN_LEN),
+    };
+    let timer = TimerCore::deserialize(cursor.take(snapshot_len)?)?;
+    let count = cursor.u8()? as usize;
+    if count > MAX_LAPS {
+        return None;
+    }
+    let mut splits = Vec::with_capacity(count);
+    for _ in 0..count {
+        splits.push(cursor.u64()?);
+    }
+    let mut state = StopwatchState::new();
+    state.timer = timer;
+    state.laps = laps_from_splits(&splits);
+    Some(state)
+}
+
+/// Version, completed work sessions u32, total focus ms u64.
+fn serialize_pomodoro_stats(stats: &PomodoroStats) -> Vec<u8> {
+    let mut payload = [0u8; 20];
+    payload[0..4].copy_from_slice(&stats.total_sessions.to_le_bytes());
+    payload[4..12].copy_from_slice(&stats.total_focus_ms.to_le_bytes());
+    payload[12..16].copy_from_slice(&stats.today_sessions.to_le_bytes());
+    payload[16..20].copy_from_slice(&stats.day.to_le_bytes());
+    write_versioned(POMODORO_STATS_VERSION, &payload)
+}
+
+/// The day's count and its date were added later; a blob without them
+/// starts today at zero, but one cut short inside them is refused.
+fn deserialize_pomodoro_stats(data: &[u8]) -> Option<PomodoroStats> {
+    let mut cursor = read_versioned(data, POMODORO_STATS_VERSION)?;
+    let total_sessions = cursor.u32()?;
+    let total_focus_ms = cursor.u64()?;
+    let (today_sessions, day) = if cursor.remaining() == 0 {
+        (0, 0)
+    } else {
+        (cursor.u32()?, cursor.u32()?)
+    };
+    Some(PomodoroStats { total_sessions, total_focus_ms, today_sessions, day })
+}
+
+/// Version, then the label as length u8 + UTF-8; length 0 means no task.
+fn serialize_pomodoro_task(task: Option<&str>) -> Vec<u8> {
+    let bytes = task.map(str::as_bytes).unwrap_or_default();
+    let len = bytes.len().min(u8::MAX as usize);
+    let mut payload = Vec::with_capacity(1 + len);
+    payload.push(len as u8);
+    payload.extend_from_slice(&bytes[..len]);
+    write_versioned(POMODORO_TASK_VERSION, &payload)
+}
+
+/// Version, then the sequence as `format_sequence` writes it: length u8 +
+/// UTF-8, e.g. "50/10 50/30"; length 0 means the classic pattern.
+fn serialize_pomodoro_sequence(sequence: &[(PomPhase, u64)]) -> Vec<u8> {
+    let text = format_sequence(sequence);
+    let len = text.len().min(u8::MAX as usize);
+    let mut payload = Vec::with_capacity(1 + len);
+    payload.push(len as u8);
+    payload.extend_from_slice(&text.as_bytes()[..len]);
+    write_versioned(POMODORO_SEQUENCE_VERSION, &payload)
+}
+
+/// None for a damaged or out-of-range sequence.
+fn deserialize_pomodoro_sequence(data: &[u8]) -> Option<Vec<(PomPhase, u64)>> {
+    let mut cursor = read_versioned(data, POMODORO_SEQUENCE_VERSION)?;
+    let len = cursor.u8()? as usize;
+    parse_sequence(std::str::from_utf8(cursor.take(len)?).ok()?)
+}
+
+fn deserialize_pomodoro_task(data: &[u8]) -> Option<String> {
+    let mut cursor = read_versioned(data, POMODORO_TASK_VERSION)?;
+    let len = cursor.u8()? as usize;
+    let task = std::str::from_utf8(cursor.take(len)?).ok()?;
+    if task.is_empty() {
+        None
+    } else {
+        Some(task.to_string())
+    }
+}
+
+/// Version, chain count u8, then per chain a step count u8 and one entry
+/// index u8 per step.
+fn serialize_countdown_chains(chains: &[CountdownChain]) -> Vec<u8> {
+    let mut payload = vec![chains.len() as u8];
+    for chain in chains {
+        payload.push(chain.steps.len() as u8);
+        payload.extend(chain.steps.iter().map(|&idx| idx as u8));
+    }
+    write_versioned(COUNTDOWN_CHAINS_VERSION, &payload)
+}
+
+fn deserialize_countdown_chains(data: &[u8]) -> Option<Vec<CountdownChain>> {
+    let mut cursor = read_versioned(data, COUNTDOWN_CHAINS_VERSION)?;
+    let count = cursor.u8()?;
+    let mut chains = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let len = cursor.u8()? as usize;
+        let steps = cursor.take(len)?.iter().map(|&idx| idx as usize).collect();
+        chains.push(CountdownChain::new(steps));
+    }
+    Some(chains)
+}
+
+/// Version byte, work u64, rest u64, rounds u8.
+fn serialize_interval_settings(settings: &IntervalSettings) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(17);
+    payload.extend_from_slice(&settings.work_ms.to_le_bytes());
+    payload.extend_from_slice(&settings.rest_ms.to_le_bytes());
+    payload.push(settings.rounds);
+    write_versioned(INTERVAL_SETTINGS_VERSION, &payload)
+}
+
+fn deserialize_interval_settings(data: &[u8]) -> Option<IntervalSettings> {
+    let mut cursor = read_versioned(data, INTERVAL_SETTINGS_VERSION)?;
+    let settings = IntervalSettings { work_ms: cursor.u64()?, rest_ms: cursor.u64()?, rounds: cursor.u8()? };
+    settings.is_valid().then_some(settings)
+}
+
+/// CRC-32 (IEEE, as used by zip and Ethernet), bit by bit; the blobs are
+/// small enough that a lookup table isn't worth the flash.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// CRC32 over what follows, the body's length, then the body: a version
+/// byte and the payload. PDDB doesn't truncate a key written shorter than
+/// before, so the length says where the body ends and any stale tail
+/// after it is ignored.
 fn serialize_countdowns(entries: &[CountdownEntry]) -> Vec<u8> {
+    let body = write_versioned(COUNTDOWNS_VERSION, &serialize_countdown_payload(entries));
+    let mut checked = Vec::with_capacity(LEN_LEN + body.len());
+    checked.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    checked.extend_from_slice(&body);
+    let mut data = Vec::with_capacity(CRC_LEN + checked.len());
+    data.extend_from_slice(&crc32(&checked).to_le_bytes());
+    data.extend_from_slice(&checked);
+    data
+}
+
+fn serialize_countdown_payload(entries: &[CountdownEntry]) -> Vec<u8> {
     let mut data = Vec::new();
     let count = entries.len() as u32;
     data.extend_from_slice(&count.to_le_bytes());
@@ -130,37 +840,511 @@ fn serialize_countdowns(entries: &[CountdownEntry]) -> Vec<u8> {
         data.extend_from_slice(name_bytes);
         data.extend_from_slice(&entry.duration_ms.to_le_bytes());
     }
+    // Link block: one byte per entry, 0 = none, else index + 1
+    for entry in entries {
+        data.push(entry.on_expiry_start.map(|i| i as u8 + 1).unwrap_or(0));
+    }
+    // Then a flags block, one byte per entry: bit 0 = repeat
+    for entry in entries {
+        data.push(entry.repeat as u8);
+    }
     data
 }
 
+/// Checked blob in, entries out. A checksum mismatch (e.g. a save cut
+/// short by power loss) loads no timers rather than garbage.
 fn deserialize_countdowns(data: &[u8]) -> Vec<CountdownEntry> {
-    let mut entries = Vec::new();
-    if data.len() < 4 {
-        return entries;
+    match checked_body(data) {
+        Some(body) => read_versioned(body, COUNTDOWNS_VERSION)
+            .and_then(deserialize_countdown_payload)
+            .unwrap_or_default(),
+        // Blobs saved before the checksum have none: just the count and
+        // entries, maybe followed by the tail of a longer earlier save
+        None => read_countdown_entries(&mut Cursor::new(data)).unwrap_or_default(),
     }
-    let count = u32::from_le_bytes(data[0..4].try_into().unwrap()) as usize;
-    let mut offset = 4;
+}
+
+/// The body of a checked blob, if its checksum matches.
+fn checked_body(data: &[u8]) -> Option<&[u8]> {
+    let mut cursor = Cursor::new(data);
+    let stored = cursor.u32()?;
+    let len = cursor.u32()?;
+    let body = cursor.take(len as usize)?;
+    (crc32(&data[CRC_LEN..cursor.pos]) == stored).then_some(body)
+}
 
+/// Entries with their link and flags blocks; None unless the payload is
+/// all there with nothing after it.
+fn deserialize_countdown_payload(mut cursor: Cursor) -> Option<Vec<CountdownEntry>> {
+    let mut entries = read_countdown_entries(&mut cursor)?;
+    for entry in entries.iter_mut() {
+        entry.on_expiry_start = match cursor.u8()? {
+            0 => None,
+            n => Some(n as usize - 1),
+        };
+    }
+    for entry in entries.iter_mut() {
+        entry.repeat = cursor.u8()? & 0x01 != 0;
+    }
+    (cursor.remaining() == 0).then_some(entries)
+}
+
+/// The count and that many names and durations. None if any is cut short.
+fn read_countdown_entries(cursor: &mut Cursor) -> Option<Vec<CountdownEntry>> {
+    let count = cursor.u32()?;
+    let mut entries = Vec::new();
     for _ in 0..count {
-        if offset + 2 > data.len() {
-            break;
+        let name_len = cursor.u16()?;
+        let name = String::from_utf8_lossy(cursor.take(name_len as usize)?).to_string();
+        let duration_ms = cursor.u64()?;
+        entries.push(CountdownEntry { name, duration_ms, on_expiry_start: None, repeat: false });
+    }
+    Some(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use timer_core::TimerState;
+
+    fn entry(name: &str, duration_ms: u64, on_expiry_start: Option<usize>) -> CountdownEntry {
+        CountdownEntry { name: name.to_string(), duration_ms, on_expiry_start, repeat: false }
+    }
+
+    #[test]
+    fn test_countdowns_repeat_round_trip() {
+        let mut entries = vec![entry("Sprint", 30_000, Some(1)), entry("Rest", 10_000, None)];
+        entries[1].repeat = true;
+        let restored = deserialize_countdowns(&serialize_countdowns(&entries));
+        assert_eq!(restored.len(), 2);
+        assert!(!restored[0].repeat);
+        assert!(restored[1].repeat);
+        assert_eq!(restored[0].on_expiry_start, Some(1));
+    }
+
+    #[test]
+    fn test_countdown_chains_round_trip() {
+        let chains = vec![CountdownChain::new(vec![0, 1, 0, 1]), CountdownChain::new(vec![2])];
+        let data = serialize_countdown_chains(&chains);
+        assert_eq!(deserialize_countdown_chains(&data), Some(chains));
+        // A save cut short loads nothing rather than a partial chain
+        assert_eq!(deserialize_countdown_chains(&data[..data.len() - 1]), None);
+    }
+
+    #[test]
+    fn test_interval_settings_round_trip() {
+        let settings = IntervalSettings { work_ms: 45_000, rest_ms: 0, rounds: 12 };
+        let data = serialize_interval_settings(&settings);
+        assert_eq!(data.len(), 18);
+        assert_eq!(deserialize_interval_settings(&data), Some(settings));
+        assert_eq!(deserialize_interval_settings(&data[..data.len() - 1]), None);
+
+        // Out-of-range values load nothing
+        let zero_rounds = serialize_interval_settings(&IntervalSettings { rounds: 0, ..settings });
+        assert_eq!(deserialize_interval_settings(&zero_rounds), None);
+    }
+
+    #[test]
+    fn test_countdowns_round_trip() {
+        let entries = vec![entry("Work", 1_800_000, Some(1)), entry("Rest", 300_000, None)];
+        let restored = deserialize_countdowns(&serialize_countdowns(&entries));
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[0].name, "Work");
+        assert_eq!(restored[0].duration_ms, 1_800_000);
+        assert_eq!(restored[0].on_expiry_start, Some(1));
+        assert_eq!(restored[1].on_expiry_start, None);
+    }
+
+    #[test]
+    fn test_cursor_reads_little_endian() {
+        let data = [0x01, 0x34, 0x12, 0x78, 0x56, 0x34, 0x12, 8, 7, 6, 5, 4, 3, 2, 1];
+        let mut cursor = Cursor::new(&data);
+        assert_eq!(cursor.u8(), Some(0x01));
+        assert_eq!(cursor.u16(), Some(0x1234));
+        assert_eq!(cursor.u32(), Some(0x1234_5678));
+        assert_eq!(cursor.remaining(), 8);
+        assert_eq!(cursor.u64(), Some(0x0102_0304_0506_0708));
+        assert_eq!(cursor.remaining(), 0);
+        assert_eq!(cursor.u8(), None);
+    }
+
+    #[test]
+    fn test_cursor_short_read_consumes_nothing() {
+        let mut cursor = Cursor::new(&[1, 2, 3]);
+        assert_eq!(cursor.u32(), None);
+        assert_eq!(cursor.remaining(), 3);
+        assert_eq!(cursor.take(usize::MAX), None);
+        assert_eq!(cursor.u16(), Some(0x0201));
+    }
+
+    #[test]
+    fn test_versioned_header() {
+        let data = write_versioned(3, &[9, 8]);
+        assert_eq!(data, vec![3, 9, 8]);
+        let mut cursor = read_versioned(&data, 3).unwrap();
+        assert_eq!(cursor.u8(), Some(9));
+        assert_eq!(cursor.remaining(), 1);
+        assert!(read_versioned(&data, 0).is_none());
+        assert!(read_versioned(&[], 0).is_none());
+    }
+
+    #[test]
+    fn test_stopwatch_round_trip() {
+        for lap_count in [0usize, 1, 99] {
+            let mut sw = StopwatchState::new();
+            sw.timer.start(0);
+            for i in 0..lap_count {
+                sw.record_lap((i as u64 + 1) * 1000 + i as u64);
+            }
+            assert_eq!(sw.laps.len(), lap_count);
+
+            let now = 200_000;
+            let restored = deserialize_stopwatch(&serialize_stopwatch(&sw, now)).unwrap();
+            assert_eq!(restored.laps, sw.laps);
+            // Stored paused with the time up to the save counted
+            assert_eq!(restored.timer.state, TimerState::Paused);
+            assert_eq!(restored.timer.elapsed_ms(900_000), 200_000);
+            assert_eq!(restored.timer.lap_elapsed_ms(0), sw.timer.lap_elapsed_ms(now));
         }
-        let name_len = u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap()) as usize;
-        offset += 2;
+    }
+
+    #[test]
+    fn test_stopwatch_survives_quit() {
+        // Quitting mid-run saves the live state rather than a reset one
+        let mut sw = StopwatchState::new();
+        sw.timer.start(0);
+        sw.record_lap(4000);
+        sw.record_lap(9000);
+        let quit_at = 12_000;
+        let restored = deserialize_stopwatch(&serialize_stopwatch(&sw, quit_at)).unwrap();
+        assert_eq!(restored.laps, sw.laps);
+        assert_eq!(restored.timer.state, TimerState::Paused);
+        assert_eq!(restored.timer.elapsed_ms(0), quit_at);
+
+        // A run the user paused before quitting keeps its paused time
+        sw.timer.pause(15_000);
+        let restored = deserialize_stopwatch(&serialize_stopwatch(&sw, 60_000)).unwrap();
+        assert_eq!(restored.laps.len(), 2);
+        assert_eq!(restored.timer.elapsed_ms(0), 15_000);
+    }
+
+    #[test]
+    fn test_stopwatch_v0_blob() {
+        let mut sw = StopwatchState::new();
+        sw.timer.start(0);
+        sw.record_lap(5000);
+        let data = serialize_stopwatch(&sw, 6000);
+        // Same blob with the shorter snapshot version 0 stored
+        let mut v0 = vec![STOPWATCH_V0];
+        v0.extend_from_slice(&data[1..1 + TIMER_SNAPSHOT_MIN_LEN]);
+        v0.extend_from_slice(&data[1 + TIMER_SNAPSHOT_LEN..]);
+        let restored = deserialize_stopwatch(&v0).unwrap();
+        assert_eq!(restored.laps, sw.laps);
+        assert_eq!(restored.timer.elapsed_ms(900_000), 6000);
+        assert_eq!(restored.timer.speed(), 1.0);
+    }
+
+    #[test]
+    fn test_stopwatch_bad_blobs() {
+        let mut sw = StopwatchState::new();
+        sw.timer.start(0);
+        sw.record_lap(5000);
+        let data = serialize_stopwatch(&sw, 6000);
+        assert!(deserialize_stopwatch(&data[..data.len() - 1]).is_none());
+        assert!(deserialize_stopwatch(&[]).is_none());
+
+        let mut future = data.clone();
+        future[0] = 9;
+        assert!(deserialize_stopwatch(&future).is_none());
+
+        let mut too_many = data;
+        too_many[1 + TIMER_SNAPSHOT_LEN] = 100;
+        assert!(deserialize_stopwatch(&too_many).is_none());
+    }
+
+    #[test]
+    fn test_alert_config_versions() {
+        let config = AlertConfig {
+            vibration: false,
+            audio: true,
+            notification: true,
+            soft_start_vibe: true,
+            escalate: true,
+            vibe: AlertVibe::Long,
+            snooze_ms: 90_000,
+            quiet_start_min: 22 * 60,
+            quiet_end_min: 6 * 60,
+            led: true,
+        };
+        let data = serialize_alert_config(&config);
+        assert_eq!(data.len(), 1 + ALERTS_LEN);
+        let loaded = deserialize_alert_config(&data).unwrap();
+        assert!(!loaded.vibration && loaded.audio && loaded.notification && loaded.soft_start_vibe && loaded.escalate);
+        assert_eq!(loaded.vibe, AlertVibe::Long);
+        assert_eq!(loaded.snooze_ms, 90_000);
+        assert_eq!((loaded.quiet_start_min, loaded.quiet_end_min), (22 * 60, 6 * 60));
+        assert!(loaded.led);
+
+        // Versioned blobs from before the snooze length
+        let loaded = deserialize_alert_config(&data[..1 + 6]).unwrap();
+        assert_eq!(loaded.vibe, AlertVibe::Long);
+        assert_eq!(loaded.snooze_ms, AlertConfig::default().snooze_ms);
+        assert!(!loaded.in_quiet_hours(23 * 60));
+        assert!(!loaded.led);
 
-        if offset + name_len > data.len() {
-            break;
+        // Versioned blobs from before escalation and the pattern choice
+        let loaded = deserialize_alert_config(&data[..1 + 4]).unwrap();
+        assert!(loaded.soft_start_vibe && !loaded.escalate);
+        assert_eq!(loaded.vibe, AlertVibe::Double);
+
+        // Unversioned blobs: the original 3 bytes and the later 4
+        let loaded = deserialize_alert_config(&[1, 0, 1]).unwrap();
+        assert!(loaded.vibration && !loaded.audio && loaded.notification && !loaded.soft_start_vibe);
+        assert!(deserialize_alert_config(&[0, 1, 1, 1]).unwrap().soft_start_vibe);
+
+        assert!(deserialize_alert_config(&[5, 1, 1, 1, 1]).is_none());
+        assert!(deserialize_alert_config(&[1, 1]).is_none());
+    }
+
+    #[test]
+    fn test_pomodoro_settings_round_trip() {
+        let settings = PomodoroSettings {
+            work_ms: 50 * 60_000,
+            short_ms: 10 * 60_000,
+            long_ms: 30 * 60_000,
+            cycles: 3,
+            auto_extend_break: false,
+            on_set_complete: SetCompleteAction::Exit,
+            flexible_break: true,
+            auto_start: false,
+        };
+        let data = serialize_pomodoro_settings(&settings);
+        assert_eq!(data[0], POMODORO_VERSION);
+        assert_eq!(deserialize_pomodoro_settings(&data), Some(settings));
+
+        let mut future = data.clone();
+        future[0] = 7;
+        assert_eq!(deserialize_pomodoro_settings(&future), None);
+    }
+
+    #[test]
+    fn test_pomodoro_settings_unversioned_v0() {
+        // Exactly what save_pomodoro_settings wrote before the version byte
+        let settings = PomodoroSettings {
+            work_ms: 25 * 60_000,
+            short_ms: 5 * 60_000,
+            long_ms: 15 * 60_000,
+            cycles: 4,
+            auto_extend_break: true,
+            on_set_complete: SetCompleteAction::Stop,
+            flexible_break: false,
+            auto_start: true,
+        };
+        let versioned = serialize_pomodoro_settings(&settings);
+        let old = &versioned[1..];
+        assert_eq!(old.len(), POMODORO_LEN);
+        assert_eq!(deserialize_pomodoro_settings(old), Some(settings));
+
+        // The original 25-byte layout, without flags or end-of-set action
+        let loaded = deserialize_pomodoro_settings(&old[..POMODORO_MIN_LEN]).unwrap();
+        assert_eq!(loaded.cycles, 4);
+        assert!(!loaded.auto_extend_break);
+        assert!(loaded.auto_start);
+        assert_eq!(loaded.on_set_complete, SetCompleteAction::Loop);
+    }
+
+    #[test]
+    fn test_pomodoro_settings_short_blob() {
+        let mut data = Vec::new();
+        for ms in [1_500_000u64, 300_000, 900_000] {
+            data.extend_from_slice(&ms.to_le_bytes());
         }
-        let name = String::from_utf8_lossy(&data[offset..offset + name_len]).to_string();
-        offset += name_len;
+        data.push(4);
+        let settings = deserialize_pomodoro_settings(&data).unwrap();
+        assert_eq!(settings.long_ms, 900_000);
+        assert_eq!(settings.cycles, 4);
+        assert_eq!(settings.on_set_complete, SetCompleteAction::Loop);
 
-        if offset + 8 > data.len() {
-            break;
+        // Truncated mid-duration or missing the cycle count: use defaults
+        assert_eq!(deserialize_pomodoro_settings(&data[..20]), None);
+        assert_eq!(deserialize_pomodoro_settings(&data[..24]), None);
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_countdowns_flipped_byte_rejected() {
+        let entries = vec![entry("Work", 1_800_000, Some(1)), entry("Rest", 300_000, None)];
+        let data = serialize_countdowns(&entries);
+        for i in 4..data.len() {
+            let mut damaged = data.clone();
+            damaged[i] ^= 0x10;
+            assert!(deserialize_countdowns(&damaged).is_empty(), "byte {} accepted", i);
         }
-        let duration_ms = u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
-        offset += 8;
+    }
+
+    #[test]
+    fn test_countdowns_unknown_version_rejected() {
+        let entries = vec![entry("Work", 1_800_000, None)];
+        let body = write_versioned(COUNTDOWNS_VERSION + 1, &serialize_countdown_payload(&entries));
+        let mut checked = (body.len() as u32).to_le_bytes().to_vec();
+        checked.extend_from_slice(&body);
+        let mut data = crc32(&checked).to_le_bytes().to_vec();
+        data.extend_from_slice(&checked);
+        assert!(deserialize_countdowns(&data).is_empty());
+    }
+
+    #[test]
+    fn test_countdowns_truncated_rejected() {
+        let entries = vec![entry("Work", 1_800_000, None), entry("Rest", 300_000, None)];
+        let data = serialize_countdowns(&entries);
+        assert!(deserialize_countdowns(&data[..data.len() - 5]).is_empty());
+    }
+
+    /// The layout saved before the checksum: count, then names and
+    /// durations only.
+    fn legacy_countdowns(entries: &[CountdownEntry]) -> Vec<u8> {
+        let mut data = serialize_countdown_payload(entries);
+        data.truncate(data.len() - 2 * entries.len());
+        data
+    }
+
+    #[test]
+    fn test_countdowns_shorter_blob_over_longer() {
+        // The key isn't truncated on save, so the old tail stays behind
+        let long = vec![entry("Work", 1_800_000, Some(1)), entry("Rest", 300_000, None)];
+        let short = vec![entry("Tea", 180_000, None)];
+        let mut data = serialize_countdowns(&long);
+        let saved = serialize_countdowns(&short);
+        data[..saved.len()].copy_from_slice(&saved);
+        let restored = deserialize_countdowns(&data);
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].name, "Tea");
+
+        // Same for an empty list
+        let saved = serialize_countdowns(&[]);
+        data[..saved.len()].copy_from_slice(&saved);
+        assert!(deserialize_countdowns(&data).is_empty());
+    }
+
+    #[test]
+    fn test_countdowns_legacy_unchecked_blob() {
+        let entries = vec![entry("Work", 1_800_000, None), entry("Rest", 300_000, None)];
+        let restored = deserialize_countdowns(&legacy_countdowns(&entries));
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[1].name, "Rest");
+        assert_eq!(restored[1].duration_ms, 300_000);
+
+        // A shorter list saved over a longer one leaves its tail behind
+        let mut data = legacy_countdowns(&[entry("Sprint", 30_000, None), entry("Cool down", 600_000, None)]);
+        let short = legacy_countdowns(&entries[..1]);
+        data[..short.len()].copy_from_slice(&short);
+        let restored = deserialize_countdowns(&data);
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].name, "Work");
+
+        // A legacy blob cut short is still refused
+        let legacy = legacy_countdowns(&entries);
+        assert!(deserialize_countdowns(&legacy[..legacy.len() - 5]).is_empty());
+    }
+
+    #[test]
+    fn test_pomodoro_stats_accumulate_across_reload() {
+        use crate::pomodoro::PomodoroState;
+        const MIN: u64 = 60 * 1000;
+
+        let mut state = PomodoroState::from_settings(25 * MIN, 5 * MIN, 15 * MIN, 4);
+        state.advance_phase();
+        state.advance_phase();
+        let saved = serialize_pomodoro_stats(&state.stats);
+
+        // Next launch picks up where the last one left off
+        let mut state = PomodoroState::from_settings(50 * MIN, 5 * MIN, 15 * MIN, 4);
+        state.stats = deserialize_pomodoro_stats(&saved).unwrap();
+        state.advance_phase();
+        let restored = deserialize_pomodoro_stats(&serialize_pomodoro_stats(&state.stats)).unwrap();
+        assert_eq!(restored, PomodoroStats { total_sessions: 2, total_focus_ms: 75 * MIN, ..PomodoroStats::default() });
+        assert_eq!(state.total_completed, 1);
+    }
+
+    #[test]
+    fn test_pomodoro_stats_today_round_trip() {
+        let stats = PomodoroStats { total_sessions: 40, total_focus_ms: 1_000_000, today_sessions: 3, day: 19_723 };
+        assert_eq!(deserialize_pomodoro_stats(&serialize_pomodoro_stats(&stats)), Some(stats));
+
+        // Saved before the day's count was stored
+        let data = serialize_pomodoro_stats(&stats);
+        let old = deserialize_pomodoro_stats(&data[..13]).unwrap();
+        assert_eq!((old.total_sessions, old.today_sessions, old.day), (40, 0, 0));
+    }
+
+    #[test]
+    fn test_pomodoro_stats_bad_blob() {
+        let data = serialize_pomodoro_stats(&PomodoroStats { total_sessions: 1, total_focus_ms: 1, today_sessions: 1, day: 2 });
+        assert_eq!(deserialize_pomodoro_stats(&data[..data.len() - 1]), None);
+        assert_eq!(deserialize_pomodoro_stats(&[]), None);
+        let mut wrong_version = data.clone();
+        wrong_version[0] = POMODORO_STATS_VERSION + 1;
+        assert_eq!(deserialize_pomodoro_stats(&wrong_version), None);
+    }
+
+    #[test]
+    fn test_pomodoro_task_round_trip() {
+        let data = serialize_pomodoro_task(Some("Write report"));
+        assert_eq!(data[0], POMODORO_TASK_VERSION);
+        assert_eq!(deserialize_pomodoro_task(&data).as_deref(), Some("Write report"));
+
+        let data = serialize_pomodoro_task(Some("Café ☕"));
+        assert_eq!(deserialize_pomodoro_task(&data).as_deref(), Some("Café ☕"));
+
+        assert_eq!(deserialize_pomodoro_task(&serialize_pomodoro_task(None)), None);
+    }
+
+    #[test]
+    fn test_pomodoro_task_shorter_label_over_longer() {
+        // The key isn't truncated on save, so the old tail stays behind
+        let mut data = serialize_pomodoro_task(Some("Write the quarterly report"));
+        let short = serialize_pomodoro_task(Some("Email"));
+        data[..short.len()].copy_from_slice(&short);
+        assert_eq!(deserialize_pomodoro_task(&data).as_deref(), Some("Email"));
+
+        let cleared = serialize_pomodoro_task(None);
+        data[..cleared.len()].copy_from_slice(&cleared);
+        assert_eq!(deserialize_pomodoro_task(&data), None);
+    }
+
+    #[test]
+    fn test_pomodoro_sequence_round_trip() {
+        let sequence = parse_sequence("50/10 50/10 50/30").unwrap();
+        let mut data = serialize_pomodoro_sequence(&sequence);
+        assert_eq!(deserialize_pomodoro_sequence(&data), Some(sequence));
+
+        // A shorter sequence saved over it leaves the old tail behind
+        let short = serialize_pomodoro_sequence(&parse_sequence("25/5").unwrap());
+        data[..short.len()].copy_from_slice(&short);
+        assert_eq!(deserialize_pomodoro_sequence(&data), parse_sequence("25/5"));
+
+        let cleared = serialize_pomodoro_sequence(&[]);
+        data[..cleared.len()].copy_from_slice(&cleared);
+        assert_eq!(deserialize_pomodoro_sequence(&data), Some(Vec::new()));
+
+        // Out-of-range or cut short loads nothing
+        let mut bad = serialize_pomodoro_sequence(&parse_sequence("25/5").unwrap());
+        bad[5] = b'0';
+        assert_eq!(deserialize_pomodoro_sequence(&bad), None);
+        let full = serialize_pomodoro_sequence(&parse_sequence("25/5").unwrap());
+        assert_eq!(deserialize_pomodoro_sequence(&full[..full.len() - 1]), None);
+    }
 
-        entries.push(CountdownEntry { name, duration_ms });
+    #[test]
+    fn test_pomodoro_task_bad_blob() {
+        let data = serialize_pomodoro_task(Some("Write report"));
+        assert_eq!(deserialize_pomodoro_task(&data[..data.len() - 1]), None);
+        assert_eq!(deserialize_pomodoro_task(&[POMODORO_TASK_VERSION, 2, 0xFF, 0xFE]), None);
+        assert_eq!(deserialize_pomodoro_task(&[]), None);
     }
-    entries
 }
@@ -3,11 +3,15 @@ use std::fmt::Write;
 use gam::{Gam, GlyphStyle, Gid};
 use gam::menu::*;
 
-use crate::pomodoro::PomodoroState;
-use crate::stopwatch::StopwatchState;
-use crate::countdown::CountdownState;
+use crate::pomodoro::{PomPhase, PomodoroState, PomodoroStats};
+use crate::stopwatch::{StopwatchState, StopwatchStats};
+use crate::countdown::{visible_window, CountdownState};
+use crate::interval::IntervalState;
 use crate::alerts::AlertConfig;
-use timer_core::{format_ms, format_hms_cs};
+use crate::clock::format_time_of_day;
+use crate::modes::ModeEntry;
+use crate::eventlog::EventLog;
+use timer_core::{format_auto, format_duration_long, format_hms, DurationStyle, format_ms, format_ms_cs, format_hms_cs_into, format_hms_millis, TimerState, FORMAT_BUF_LEN};
 
 pub fn clear_screen(gam: &Gam, content: Gid, screensize: Point) {
     gam.draw_rectangle(
@@ -25,6 +29,48 @@ pub fn clear_screen(gam: &Gam, content: Gid, screensize: Point) {
     .expect("can't clear");
 }
 
+/// Small alert-channel indicator in the top-right corner of a timer header.
+fn draw_alert_channels(gam: &Gam, content: Gid, screensize: Point, alerts: &AlertConfig) {
+    let mut tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(screensize.x - 48, 12, screensize.x - 8, 32)),
+    );
+    tv.style = GlyphStyle::Small;
+    tv.clear_area = true;
+    write!(tv.text, "{}", alerts.channel_summary()).unwrap();
+    gam.post_textview(&mut tv).expect("can't post alert channels");
+}
+
+/// "Timer finished" badge above the footer, shown on every screen until
+/// the finished timers are looked at.
+pub fn draw_expiry_badge(gam: &Gam, content: Gid, screensize: Point, count: u32) {
+    let mut tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, screensize.y - 76, screensize.x - 12, screensize.y - 54)),
+    );
+    tv.style = GlyphStyle::Small;
+    tv.clear_area = true;
+    tv.invert = true;
+    let noun = if count == 1 { "timer" } else { "timers" };
+    write!(tv.text, " (!) {} {} finished", count, noun).unwrap();
+    gam.post_textview(&mut tv).expect("can't post expiry badge");
+    gam.redraw().expect("can't redraw");
+}
+
+/// Inverted "PAUSED" tag at the right edge, `top` pixels down, so a
+/// paused timer doesn't look like a running one that froze.
+fn draw_paused_badge(gam: &Gam, content: Gid, screensize: Point, top: isize) {
+    let mut tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(screensize.x - 84, top, screensize.x - 12, top + 22)),
+    );
+    tv.style = GlyphStyle::Small;
+    tv.clear_area = true;
+    tv.invert = true;
+    write!(tv.text, " PAUSED ").unwrap();
+    gam.post_textview(&mut tv).expect("can't post paused badge");
+}
+
 pub fn draw_menu(
     gam: &Gam,
     content: Gid,
@@ -139,7 +185,40 @@ pub fn draw_confirm_exit(gam: &Gam, content: Gid, screensize: Point) {
     gam.redraw().expect("can't redraw");
 }
 
-pub fn draw_mode_select(gam: &Gam, content: Gid, screensize: Point, cursor: usize) {
+pub fn draw_confirm_delete(gam: &Gam, content: Gid, screensize: Point, name: &str) {
+    clear_screen(gam, content, screensize);
+
+    let mut title_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 40, screensize.x - 12, 70)),
+    );
+    title_tv.style = GlyphStyle::Bold;
+    title_tv.clear_area = true;
+    write!(title_tv.text, "Delete Timer").unwrap();
+    gam.post_textview(&mut title_tv).expect("can't post title");
+
+    let mut msg_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 90, screensize.x - 12, 150)),
+    );
+    msg_tv.style = GlyphStyle::Regular;
+    msg_tv.clear_area = true;
+    write!(msg_tv.text, "Delete \"{}\"?\nThis can't be undone.", name).unwrap();
+    gam.post_textview(&mut msg_tv).expect("can't post message");
+
+    let mut nav_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 170, screensize.x - 12, 210)),
+    );
+    nav_tv.style = GlyphStyle::Regular;
+    nav_tv.clear_area = true;
+    write!(nav_tv.text, "  y = Delete\n  n = Cancel\n  F4 = Cancel").unwrap();
+    gam.post_textview(&mut nav_tv).expect("can't post options");
+
+    gam.redraw().expect("can't redraw");
+}
+
+pub fn draw_mode_select(gam: &Gam, content: Gid, screensize: Point, modes: &[ModeEntry], cursor: usize, next_at: Option<u32>) {
     clear_screen(gam, content, screensize);
 
     let mut title_tv = TextView::new(
@@ -151,7 +230,6 @@ pub fn draw_mode_select(gam: &Gam, content: Gid, screensize: Point, cursor: usiz
     write!(title_tv.text, "TIMERS").unwrap();
     gam.post_textview(&mut title_tv).expect("can't post title");
 
-    let modes = ["Pomodoro", "Stopwatch", "Countdown"];
     let line_height = 32;
     let list_top = 60;
 
@@ -165,29 +243,41 @@ pub fn draw_mode_select(gam: &Gam, content: Gid, screensize: Point, cursor: usiz
         );
         tv.style = GlyphStyle::Regular;
         tv.clear_area = true;
-        write!(tv.text, "{}{}", marker, mode).unwrap();
+        write!(tv.text, "{}{}", marker, mode.label()).unwrap();
         gam.post_textview(&mut tv).expect("can't post mode item");
     }
 
+    // When the first running timer will be done, clear of the expiry badge
+    if let Some(seconds) = next_at {
+        let mut next_tv = TextView::new(
+            content,
+            TextBounds::BoundingBox(Rectangle::new_coords(12, screensize.y - 100, screensize.x - 12, screensize.y - 78)),
+        );
+        next_tv.style = GlyphStyle::Small;
+        next_tv.clear_area = true;
+        write!(next_tv.text, "Next timer done at {}", format_time_of_day(seconds)).unwrap();
+        gam.post_textview(&mut next_tv).expect("can't post next expiry");
+    }
+
     let mut nav_tv = TextView::new(
         content,
         TextBounds::BoundingBox(Rectangle::new_coords(12, screensize.y - 50, screensize.x - 12, screensize.y - 10)),
     );
     nav_tv.style = GlyphStyle::Small;
     nav_tv.clear_area = true;
-    write!(nav_tv.text, "F1=menu F4=quit  ENTER=open  s=settings").unwrap();
+    write!(nav_tv.text, "F1=menu F4=quit  ENTER=open  s=settings\n[ ]=move mode up/down").unwrap();
     gam.post_textview(&mut nav_tv).expect("can't post footer");
 
     gam.redraw().expect("can't redraw");
 }
 
-pub fn draw_pomodoro(gam: &Gam, content: Gid, screensize: Point, state: &PomodoroState, now_ms: u64) {
+pub fn draw_pomodoro(gam: &Gam, content: Gid, screensize: Point, state: &PomodoroState, alerts: &AlertConfig, now_ms: u64) {
     clear_screen(gam, content, screensize);
 
     // Header
     let mut title_tv = TextView::new(
         content,
-        TextBounds::BoundingBox(Rectangle::new_coords(12, 8, screensize.x - 12, 36)),
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 8, screensize.x - 52, 36)),
     );
     title_tv.style = GlyphStyle::Bold;
     title_tv.clear_area = true;
@@ -195,21 +285,46 @@ pub fn draw_pomodoro(gam: &Gam, content: Gid, screensize: Point, state: &Pomodor
         title_tv.text, "POMODORO  [{} {}/{}]",
         state.phase_label(),
         state.current_cycle + 1,
-        state.cycles_before_long
+        state.cycles_per_set()
     ).unwrap();
+    if state.break_extensions > 0 {
+        write!(title_tv.text, " +{}m", state.break_extensions).unwrap();
+    }
     gam.post_textview(&mut title_tv).expect("can't post title");
+    draw_alert_channels(gam, content, screensize, alerts);
 
-    // Time display
-    let remaining = state.timer.remaining_ms(now_ms).unwrap_or(0);
-    let time_str = format_ms(remaining);
+    if let Some(task) = &state.task {
+        let mut task_tv = TextView::new(
+            content,
+            TextBounds::BoundingBox(Rectangle::new_coords(12, 38, screensize.x - 12, 64)),
+        );
+        task_tv.style = GlyphStyle::Small;
+        task_tv.clear_area = true;
+        write!(task_tv.text, "Task: {}", task).unwrap();
+        gam.post_textview(&mut task_tv).expect("can't post task");
+    }
+
+    // Time display: a flexible break counts up instead of down
+    let time_str = if state.in_flexible_break() {
+        format_auto(state.timer.elapsed_ms(now_ms))
+    } else {
+        format_auto(state.timer.remaining_ms(now_ms).unwrap_or(0))
+    };
     let mut time_tv = TextView::new(
         content,
         TextBounds::BoundingBox(Rectangle::new_coords(40, 70, screensize.x - 40, 120)),
     );
     time_tv.style = GlyphStyle::Bold;
     time_tv.clear_area = true;
-    write!(time_tv.text, "     {}", time_str).unwrap();
+    if state.set_complete && state.timer.state == TimerState::Stopped {
+        write!(time_tv.text, "  Set complete!").unwrap();
+    } else {
+        write!(time_tv.text, "     {}", time_str).unwrap();
+    }
     gam.post_textview(&mut time_tv).expect("can't post time");
+    if state.timer.state == TimerState::Paused {
+        draw_paused_badge(gam, content, screensize, 96);
+    }
 
     // Progress bar
     let bar_left = 30;
@@ -233,8 +348,8 @@ pub fn draw_pomodoro(gam: &Gam, content: Gid, screensize: Point, state: &Pomodor
     ).expect("can't draw bar outline");
 
     // Bar fill
-    let progress = state.progress_fraction(now_ms);
-    let fill_width = (bar_width as f32 * progress) as isize;
+    let progress = state.progress_permille(now_ms) as isize;
+    let fill_width = bar_width * progress / 1000;
     if fill_width > 0 {
         gam.draw_rectangle(
             content,
@@ -258,8 +373,32 @@ pub fn draw_pomodoro(gam: &Gam, content: Gid, screensize: Point, state: &Pomodor
     session_tv.style = GlyphStyle::Small;
     session_tv.clear_area = true;
     write!(session_tv.text, "Sessions completed: {}", state.total_completed).unwrap();
+    if let Some(last_break) = state.last_break_ms {
+        write!(session_tv.text, "  Last break: {}", format_duration_long(last_break, DurationStyle::Abbrev)).unwrap();
+    }
     gam.post_textview(&mut session_tv).expect("can't post session");
 
+    // Upcoming phases
+    let mut next_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 195, screensize.x - 12, 220)),
+    );
+    next_tv.style = GlyphStyle::Small;
+    next_tv.clear_area = true;
+    if let Some((step, steps)) = state.sequence_step() {
+        write!(next_tv.text, "Step {}/{}  ", step, steps).unwrap();
+    }
+    write!(next_tv.text, "Next:").unwrap();
+    for (i, (phase, duration)) in state.upcoming_phases(2).iter().enumerate() {
+        let sep = if i == 0 { " " } else { ", " };
+        if state.flexible_break && *phase != PomPhase::Work {
+            write!(next_tv.text, "{}{} open", sep, phase.label()).unwrap();
+        } else {
+            write!(next_tv.text, "{}{} {}m", sep, phase.label(), duration / 60_000).unwrap();
+        }
+    }
+    gam.post_textview(&mut next_tv).expect("can't post upcoming");
+
     // Footer
     let mut nav_tv = TextView::new(
         content,
@@ -267,44 +406,125 @@ pub fn draw_pomodoro(gam: &Gam, content: Gid, screensize: Point, state: &Pomodor
     );
     nav_tv.style = GlyphStyle::Small;
     nav_tv.clear_area = true;
-    write!(nav_tv.text, "F2=start/pause  F3=reset  F4=back\nF1=menu  s=settings").unwrap();
+    let start_key = if state.in_flexible_break() { "F2=end break" } else { "F2=start/pause" };
+    let skip = if state.is_break() { "  n=skip break" } else { "" };
+    write!(nav_tv.text, "{}  F3=reset  F4=back\nF1=menu  s=settings  t=task{}", start_key, skip).unwrap();
     gam.post_textview(&mut nav_tv).expect("can't post footer");
 
     gam.redraw().expect("can't redraw");
 }
 
-pub fn draw_stopwatch(gam: &Gam, content: Gid, screensize: Point, state: &StopwatchState, now_ms: u64) {
+pub fn draw_stopwatch(gam: &Gam, content: Gid, screensize: Point, state: &StopwatchState, alerts: &AlertConfig, now_ms: u64) {
     clear_screen(gam, content, screensize);
 
     // Header
     let mut title_tv = TextView::new(
         content,
-        TextBounds::BoundingBox(Rectangle::new_coords(12, 8, screensize.x - 12, 36)),
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 8, screensize.x - 52, 36)),
     );
     title_tv.style = GlyphStyle::Bold;
     title_tv.clear_area = true;
     write!(title_tv.text, "STOPWATCH").unwrap();
     gam.post_textview(&mut title_tv).expect("can't post title");
+    draw_alert_channels(gam, content, screensize, alerts);
 
-    // Time display
+    // Time display: the running total, or what's left of the target,
+    // inverted and counting up once the target is passed
     let elapsed = state.timer.elapsed_ms(now_ms);
-    let time_str = format_hms_cs(elapsed);
+    let over = state.over_target(now_ms);
+    let (sign, shown) = match (over, state.remaining_to_target(now_ms)) {
+        (Some(over), _) => ("+", over),
+        (None, Some(remaining)) => ("-", remaining),
+        (None, None) => ("", elapsed),
+    };
+    let time_str = format!("{}{}.{:02}", sign, format_auto(shown), (shown % 1000) / 10);
     let mut time_tv = TextView::new(
         content,
         TextBounds::BoundingBox(Rectangle::new_coords(20, 50, screensize.x - 20, 90)),
     );
     time_tv.style = GlyphStyle::Bold;
     time_tv.clear_area = true;
+    time_tv.invert = over.is_some();
     write!(time_tv.text, "  {}", time_str).unwrap();
     gam.post_textview(&mut time_tv).expect("can't post time");
+    if state.timer.state == TimerState::Paused {
+        draw_paused_badge(gam, content, screensize, 54);
+    }
+
+    let mut list_top = 100;
+
+    // The total is still what the laps add up to
+    if let Some(target) = state.target_ms {
+        let mut target_tv = TextView::new(
+            content,
+            TextBounds::BoundingBox(Rectangle::new_coords(20, list_top - 6, screensize.x - 20, list_top + 16)),
+        );
+        target_tv.style = GlyphStyle::Small;
+        target_tv.clear_area = true;
+        let status = if over.is_some() { "passed" } else { "target" };
+        write!(target_tv.text, "  {} of {} {}", format_auto(elapsed), format_auto(target), status).unwrap();
+        gam.post_textview(&mut target_tv).expect("can't post target");
+        list_top += 22;
+    }
+
+    // Current split under the running total
+    if !state.laps.is_empty() {
+        let mut split_tv = TextView::new(
+            content,
+            TextBounds::BoundingBox(Rectangle::new_coords(20, list_top - 6, screensize.x - 20, list_top + 16)),
+        );
+        split_tv.style = GlyphStyle::Small;
+        split_tv.clear_area = true;
+        // Redrawn every 100 ms while running, so format without allocating
+        let mut buf = [0u8; FORMAT_BUF_LEN];
+        let split = format_hms_cs_into(state.timer.lap_elapsed_ms(now_ms), &mut buf).unwrap_or_default();
+        write!(split_tv.text, "  Lap {:2}: {}", state.laps.len() + 1, split).unwrap();
+        gam.post_textview(&mut split_tv).expect("can't post split");
+        list_top += 22;
+    }
+
+    if let Some(avg) = state.average_lap_ms() {
+        let mut avg_tv = TextView::new(
+            content,
+            TextBounds::BoundingBox(Rectangle::new_coords(20, list_top - 6, screensize.x - 20, list_top + 16)),
+        );
+        avg_tv.style = GlyphStyle::Small;
+        avg_tv.clear_area = true;
+        write!(avg_tv.text, "  Avg {}", format_ms_cs(avg)).unwrap();
+        gam.post_textview(&mut avg_tv).expect("can't post average");
+        list_top += 22;
+    }
+
+    // Lap goal progress
+    if let Some((current, remaining)) = state.lap_progress() {
+        let goal = state.lap_goal.unwrap_or(0);
+        let mut goal_tv = TextView::new(
+            content,
+            TextBounds::BoundingBox(Rectangle::new_coords(20, list_top - 6, screensize.x - 20, list_top + 16)),
+        );
+        goal_tv.style = GlyphStyle::Regular;
+        goal_tv.clear_area = true;
+        if remaining == 0 {
+            write!(goal_tv.text, "Lap {} of {} - done!", current, goal).unwrap();
+        } else {
+            write!(goal_tv.text, "Lap {} of {} ({} to go)", current, goal, remaining).unwrap();
+        }
+        gam.post_textview(&mut goal_tv).expect("can't post lap goal");
+        list_top += 22;
+    }
 
     // Lap list (most recent first)
     let line_height = 22;
-    let list_top = 100;
     let list_bottom = screensize.y - 60;
     let max_visible = ((list_bottom - list_top) / line_height) as usize;
 
     if !state.laps.is_empty() {
+        // Best and worst only mean something once there are two laps
+        let (fastest, slowest) = if state.laps.len() > 1 {
+            (state.fastest_lap_index(), state.slowest_lap_index())
+        } else {
+            (None, None)
+        };
         let visible_count = max_visible.min(state.laps.len());
         let start = if state.laps.len() > state.lap_scroll_offset {
             state.laps.len() - state.lap_scroll_offset
@@ -318,7 +538,7 @@ pub fn draw_stopwatch(gam: &Gam, content: Gid, screensize: Point, state: &Stopwa
                 break;
             }
             let y = list_top + (i as isize) * line_height;
-            let lap_time = format_hms_cs(state.laps[lap_idx]);
+            let lap_time = format_hms_millis(state.lap_display_ms(&state.laps[lap_idx]));
 
             let mut tv = TextView::new(
                 content,
@@ -327,6 +547,13 @@ pub fn draw_stopwatch(gam: &Gam, content: Gid, screensize: Point, state: &Stopwa
             tv.style = GlyphStyle::Small;
             tv.clear_area = true;
             write!(tv.text, "Lap {:2}: {}", lap_idx + 1, lap_time).unwrap();
+            if fastest == Some(lap_idx) {
+                tv.style = GlyphStyle::Bold;
+                write!(tv.text, " *").unwrap();
+            } else if slowest == Some(lap_idx) {
+                tv.style = GlyphStyle::Bold;
+                write!(tv.text, " v").unwrap();
+            }
             gam.post_textview(&mut tv).expect("can't post lap");
         }
     }
@@ -338,13 +565,14 @@ pub fn draw_stopwatch(gam: &Gam, content: Gid, screensize: Point, state: &Stopwa
     );
     nav_tv.style = GlyphStyle::Small;
     nav_tv.clear_area = true;
-    write!(nav_tv.text, "F2=start/pause  F3=reset  F4=back\nF1=menu  l=lap").unwrap();
+    let lap_view = if state.show_cumulative { "split" } else { "total" };
+    write!(nav_tv.text, "F2=start/pause  F3=reset  F4=back\nF1=menu  l=lap  g=lap goal  c={}", lap_view).unwrap();
     gam.post_textview(&mut nav_tv).expect("can't post footer");
 
     gam.redraw().expect("can't redraw");
 }
 
-pub fn draw_countdown_list(gam: &Gam, content: Gid, screensize: Point, state: &CountdownState) {
+pub fn draw_countdown_list(gam: &Gam, content: Gid, screensize: Point, state: &CountdownState, now_ms: u64) {
     clear_screen(gam, content, screensize);
 
     // Header
@@ -373,19 +601,33 @@ pub fn draw_countdown_list(gam: &Gam, content: Gid, screensize: Point, state: &C
         write!(tv.text, "No timers. Press 'n' to add.").unwrap();
         gam.post_textview(&mut tv).expect("can't post empty");
     } else {
-        let visible_end = max_visible.min(state.entries.len());
-        for (i, entry) in state.entries[..visible_end].iter().enumerate() {
-            let y = list_top + (i as isize) * line_height;
-            let marker = if i == state.cursor { "> " } else { "  " };
-            let duration_str = format_ms(entry.duration_ms);
-
+        let total = state.entries.len();
+        let (start, end) = visible_window(state.cursor, total, max_visible);
+        let hint = match (start > 0, end < total) {
+            (true, true) => "▲▼ more",
+            (true, false) => "▲ more",
+            (false, true) => "▼ more",
+            (false, false) => "",
+        };
+        if !hint.is_empty() {
+            let mut hint_tv = TextView::new(
+                content,
+                TextBounds::BoundingBox(Rectangle::new_coords(screensize.x - 100, 12, screensize.x - 12, 34)),
+            );
+            hint_tv.style = GlyphStyle::Small;
+            hint_tv.clear_area = true;
+            write!(hint_tv.text, "{}", hint).unwrap();
+            gam.post_textview(&mut hint_tv).expect("can't post scroll hint");
+        }
+        for (row, i) in (start..end).enumerate() {
+            let y = list_top + (row as isize) * line_height;
             let mut tv = TextView::new(
                 content,
                 TextBounds::BoundingBox(Rectangle::new_coords(12, y, screensize.x - 12, y + line_height - 2)),
             );
             tv.style = GlyphStyle::Regular;
             tv.clear_area = true;
-            write!(tv.text, "{}{:<14} {}", marker, entry.name, duration_str).unwrap();
+            write!(tv.text, "{}", state.list_line(i, now_ms).unwrap_or_default()).unwrap();
             gam.post_textview(&mut tv).expect("can't post entry");
         }
     }
@@ -397,13 +639,13 @@ pub fn draw_countdown_list(gam: &Gam, content: Gid, screensize: Point, state: &C
     );
     nav_tv.style = GlyphStyle::Small;
     nav_tv.clear_area = true;
-    write!(nav_tv.text, "F1=menu F4=back  ENTER=start\nn=new  d=delete").unwrap();
+    write!(nav_tv.text, "F1=menu F4=back  ENTER=start\nn=new  d=delete  a=start after  l=loop  c=chain").unwrap();
     gam.post_textview(&mut nav_tv).expect("can't post footer");
 
     gam.redraw().expect("can't redraw");
 }
 
-pub fn draw_countdown_running(gam: &Gam, content: Gid, screensize: Point, state: &CountdownState, now_ms: u64) {
+pub fn draw_countdown_running(gam: &Gam, content: Gid, screensize: Point, state: &CountdownState, alerts: &AlertConfig, now_ms: u64) {
     clear_screen(gam, content, screensize);
 
     let name = state.active_name().unwrap_or("Timer");
@@ -411,26 +653,50 @@ pub fn draw_countdown_running(gam: &Gam, content: Gid, screensize: Point, state:
     // Header
     let mut title_tv = TextView::new(
         content,
-        TextBounds::BoundingBox(Rectangle::new_coords(12, 8, screensize.x - 12, 36)),
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 8, screensize.x - 52, 36)),
     );
     title_tv.style = GlyphStyle::Bold;
     title_tv.clear_area = true;
     write!(title_tv.text, "COUNTDOWN: {}", name).unwrap();
+    let repeating = state.active_index
+        .and_then(|idx| state.entries.get(idx))
+        .is_some_and(|e| e.repeat);
+    if repeating {
+        write!(title_tv.text, " [{}]", state.active_loops() + 1).unwrap();
+    }
+    if let Some((step, steps)) = state.active_chain_step() {
+        write!(title_tv.text, " {}/{}", step, steps).unwrap();
+    }
     gam.post_textview(&mut title_tv).expect("can't post title");
+    draw_alert_channels(gam, content, screensize, alerts);
+
+    let paused = state.active_timer().is_some_and(|t| t.state == TimerState::Paused);
+    if state.bar_only {
+        if paused {
+            draw_paused_badge(gam, content, screensize, 36);
+        }
+        draw_countdown_bar_only(gam, content, screensize, state, now_ms);
+        return;
+    }
 
-    // Time display
-    let remaining = state.active_timer.as_ref()
-        .and_then(|t| t.remaining_ms(now_ms))
-        .unwrap_or(0);
-    let time_str = format_ms(remaining);
+    // Time display, counting up once past expiry
+    let frame = state.active_timer().map(|t| t.frame(now_ms));
+    let overtime = state.active_timer().and_then(|t| t.overtime_ms(now_ms));
+    let remaining = frame.and_then(|f| f.remaining_ms).unwrap_or(0);
     let mut time_tv = TextView::new(
         content,
         TextBounds::BoundingBox(Rectangle::new_coords(40, 70, screensize.x - 40, 120)),
     );
     time_tv.style = GlyphStyle::Bold;
     time_tv.clear_area = true;
-    write!(time_tv.text, "     {}", time_str).unwrap();
+    match overtime {
+        Some(over) if over > 0 => write!(time_tv.text, "expired {} ago", format_ms(over)).unwrap(),
+        _ => write!(time_tv.text, "     {}", format_ms(remaining)).unwrap(),
+    }
     gam.post_textview(&mut time_tv).expect("can't post time");
+    if paused {
+        draw_paused_badge(gam, content, screensize, 96);
+    }
 
     // Progress bar
     let bar_left = 30;
@@ -452,8 +718,8 @@ pub fn draw_countdown_running(gam: &Gam, content: Gid, screensize: Point, state:
         ),
     ).expect("can't draw bar outline");
 
-    let progress = state.progress_fraction(now_ms);
-    let fill_width = (bar_width as f32 * progress) as isize;
+    let progress = frame.map(|f| f.permille).unwrap_or(0) as isize;
+    let fill_width = bar_width * progress / 1000;
     if fill_width > 0 {
         gam.draw_rectangle(
             content,
@@ -469,6 +735,19 @@ pub fn draw_countdown_running(gam: &Gam, content: Gid, screensize: Point, state:
         ).expect("can't draw bar fill");
     }
 
+    // Elapsed line (on unless turned off); frozen while paused like the remaining time
+    if state.show_elapsed {
+        let elapsed = frame.map(|f| f.elapsed_ms).unwrap_or(0);
+        let mut elapsed_tv = TextView::new(
+            content,
+            TextBounds::BoundingBox(Rectangle::new_coords(30, bar_bottom + 8, screensize.x - 30, bar_bottom + 30)),
+        );
+        elapsed_tv.style = GlyphStyle::Small;
+        elapsed_tv.clear_area = true;
+        write!(elapsed_tv.text, "elapsed {}", format_ms(elapsed)).unwrap();
+        gam.post_textview(&mut elapsed_tv).expect("can't post elapsed");
+    }
+
     // Footer
     let mut nav_tv = TextView::new(
         content,
@@ -476,13 +755,63 @@ pub fn draw_countdown_running(gam: &Gam, content: Gid, screensize: Point, state:
     );
     nav_tv.style = GlyphStyle::Small;
     nav_tv.clear_area = true;
-    write!(nav_tv.text, "F2=pause/resume  F3=reset\nF4=back  F1=menu").unwrap();
+    write!(nav_tv.text, "F2=pause/resume  F3=reset  +/-=1m\nF4=back  F1=menu  b=bar only").unwrap();
+    gam.post_textview(&mut nav_tv).expect("can't post footer");
+
+    gam.redraw().expect("can't redraw");
+}
+
+/// Digit-free countdown: a full-width dark bar that shrinks as time runs out.
+fn draw_countdown_bar_only(gam: &Gam, content: Gid, screensize: Point, state: &CountdownState, now_ms: u64) {
+    let bar_left = 12;
+    let bar_right = screensize.x - 12;
+    let bar_top = 60;
+    let bar_bottom = screensize.y - 70;
+    let bar_width = bar_right - bar_left;
+
+    gam.draw_rectangle(
+        content,
+        Rectangle::new_with_style(
+            Point::new(bar_left, bar_top),
+            Point::new(bar_right, bar_bottom),
+            DrawStyle {
+                fill_color: None,
+                stroke_color: Some(PixelColor::Dark),
+                stroke_width: 1,
+            },
+        ),
+    ).expect("can't draw bar outline");
+
+    let remaining = 1000 - state.progress_permille(now_ms) as isize;
+    let fill_width = bar_width * remaining / 1000;
+    if fill_width > 0 {
+        gam.draw_rectangle(
+            content,
+            Rectangle::new_with_style(
+                Point::new(bar_left + 1, bar_top + 1),
+                Point::new(bar_left + 1 + fill_width, bar_bottom - 1),
+                DrawStyle {
+                    fill_color: Some(PixelColor::Dark),
+                    stroke_color: None,
+                    stroke_width: 0,
+                },
+            ),
+        ).expect("can't draw bar fill");
+    }
+
+    let mut nav_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, screensize.y - 50, screensize.x - 12, screensize.y - 10)),
+    );
+    nav_tv.style = GlyphStyle::Small;
+    nav_tv.clear_area = true;
+    write!(nav_tv.text, "F2=pause/resume  F3=reset\nF4=back  F1=menu  b=digits").unwrap();
     gam.post_textview(&mut nav_tv).expect("can't post footer");
 
     gam.redraw().expect("can't redraw");
 }
 
-pub fn draw_settings(gam: &Gam, content: Gid, screensize: Point, config: &AlertConfig, cursor: usize) {
+pub fn draw_settings(gam: &Gam, content: Gid, screensize: Point, items: &[(&str, String)], cursor: usize) {
     clear_screen(gam, content, screensize);
 
     let mut title_tv = TextView::new(
@@ -497,17 +826,10 @@ pub fn draw_settings(gam: &Gam, content: Gid, screensize: Point, config: &AlertC
     let line_height = 30;
     let list_top = 60;
 
-    // Alert settings
-    let alert_items = [
-        ("Vibration", config.vibration),
-        ("Notification", config.notification),
-        ("Audio", config.audio),
-    ];
-
-    for (i, (label, enabled)) in alert_items.iter().enumerate() {
+    // Rows with an empty value are actions (e.g. "Configure Pomodoro...")
+    for (i, (label, value)) in items.iter().enumerate() {
         let y = list_top + (i as isize) * line_height;
         let marker = if i == cursor { "> " } else { "  " };
-        let status = if *enabled { "[ON]" } else { "[OFF]" };
 
         let mut tv = TextView::new(
             content,
@@ -515,21 +837,66 @@ pub fn draw_settings(gam: &Gam, content: Gid, screensize: Point, config: &AlertC
         );
         tv.style = GlyphStyle::Regular;
         tv.clear_area = true;
-        write!(tv.text, "{}{:<16} {}", marker, label, status).unwrap();
+        if value.is_empty() {
+            write!(tv.text, "{}{}", marker, label).unwrap();
+        } else {
+            write!(tv.text, "{}{:<16} {}", marker, label, value).unwrap();
+        }
         gam.post_textview(&mut tv).expect("can't post setting");
     }
 
-    // Configure Pomodoro option
-    let pom_y = list_top + 3 * line_height;
-    let pom_marker = if cursor == 3 { "> " } else { "  " };
+    let mut nav_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, screensize.y - 50, screensize.x - 12, screensize.y - 10)),
+    );
+    nav_tv.style = GlyphStyle::Small;
+    nav_tv.clear_area = true;
+    write!(nav_tv.text, "F1=menu F4=back  ENTER=toggle/edit").unwrap();
+    gam.post_textview(&mut nav_tv).expect("can't post footer");
+
+    gam.redraw().expect("can't redraw");
+}
+
+/// `today` is the number of pomodoros finished today.
+pub fn draw_stats(gam: &Gam, content: Gid, screensize: Point, pomodoro: &PomodoroStats, today: u32, stopwatch: &StopwatchStats) {
+    clear_screen(gam, content, screensize);
+
+    let mut title_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 8, screensize.x - 12, 36)),
+    );
+    title_tv.style = GlyphStyle::Bold;
+    title_tv.clear_area = true;
+    write!(title_tv.text, "STATS").unwrap();
+    gam.post_textview(&mut title_tv).expect("can't post title");
+
     let mut pom_tv = TextView::new(
         content,
-        TextBounds::BoundingBox(Rectangle::new_coords(12, pom_y, screensize.x - 12, pom_y + line_height - 2)),
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 50, screensize.x - 12, 150)),
     );
     pom_tv.style = GlyphStyle::Regular;
     pom_tv.clear_area = true;
-    write!(pom_tv.text, "{}Configure Pomodoro...", pom_marker).unwrap();
-    gam.post_textview(&mut pom_tv).expect("can't post pom setting");
+    write!(
+        pom_tv.text,
+        "Pomodoro\n  Sessions: {}\n  Today: {}\n  Focus time: {}",
+        pomodoro.total_sessions,
+        today,
+        format_duration_long(pomodoro.total_focus_ms, DurationStyle::Abbrev)
+    ).unwrap();
+    gam.post_textview(&mut pom_tv).expect("can't post pomodoro stats");
+
+    let mut sw_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 160, screensize.x - 12, 240)),
+    );
+    sw_tv.style = GlyphStyle::Regular;
+    sw_tv.clear_area = true;
+    write!(
+        sw_tv.text,
+        "Stopwatch\n  Sessions: {}\n  Laps recorded: {}",
+        stopwatch.sessions, stopwatch.laps
+    ).unwrap();
+    gam.post_textview(&mut sw_tv).expect("can't post stopwatch stats");
 
     let mut nav_tv = TextView::new(
         content,
@@ -537,7 +904,185 @@ pub fn draw_settings(gam: &Gam, content: Gid, screensize: Point, config: &AlertC
     );
     nav_tv.style = GlyphStyle::Small;
     nav_tv.clear_area = true;
-    write!(nav_tv.text, "F1=menu F4=back  ENTER=toggle/edit").unwrap();
+    write!(nav_tv.text, "F1=menu F4=back").unwrap();
+    gam.post_textview(&mut nav_tv).expect("can't post footer");
+
+    gam.redraw().expect("can't redraw");
+}
+
+pub fn draw_interval(gam: &Gam, content: Gid, screensize: Point, state: &IntervalState, alerts: &AlertConfig, now_ms: u64) {
+    clear_screen(gam, content, screensize);
+
+    let mut title_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 8, screensize.x - 52, 36)),
+    );
+    title_tv.style = GlyphStyle::Bold;
+    title_tv.clear_area = true;
+    write!(title_tv.text, "INTERVAL").unwrap();
+    gam.post_textview(&mut title_tv).expect("can't post title");
+    draw_alert_channels(gam, content, screensize, alerts);
+
+    let mut time_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(20, 50, screensize.x - 20, 90)),
+    );
+    time_tv.style = GlyphStyle::Bold;
+    time_tv.clear_area = true;
+    write!(time_tv.text, "  {}", state.header(now_ms)).unwrap();
+    if state.timer.state == TimerState::Paused {
+        write!(time_tv.text, " (paused)").unwrap();
+    }
+    gam.post_textview(&mut time_tv).expect("can't post time");
+
+    let mut plan_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(20, 100, screensize.x - 20, 150)),
+    );
+    plan_tv.style = GlyphStyle::Small;
+    plan_tv.clear_area = true;
+    write!(
+        plan_tv.text,
+        "  Work {}  Rest {}\n  {} rounds left after this one",
+        format_ms(state.work_ms),
+        format_ms(state.rest_ms),
+        state.rounds_left()
+    ).unwrap();
+    gam.post_textview(&mut plan_tv).expect("can't post plan");
+
+    let mut nav_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, screensize.y - 50, screensize.x - 12, screensize.y - 10)),
+    );
+    nav_tv.style = GlyphStyle::Small;
+    nav_tv.clear_area = true;
+    write!(nav_tv.text, "F1=menu F4=back  ENTER=start/pause\nr=reset  c=configure").unwrap();
+    gam.post_textview(&mut nav_tv).expect("can't post footer");
+
+    gam.redraw().expect("can't redraw");
+}
+
+/// Just `time`, large, with a small `label` above it, for the focus display.
+pub fn draw_focus_display(gam: &Gam, content: Gid, screensize: Point, label: &str, time: &str) {
+    clear_screen(gam, content, screensize);
+    let mid = screensize.y / 2;
+
+    let mut label_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, mid - 70, screensize.x - 12, mid - 40)),
+    );
+    label_tv.style = GlyphStyle::Regular;
+    label_tv.clear_area = true;
+    write!(label_tv.text, "{}", label).unwrap();
+    gam.post_textview(&mut label_tv).expect("can't post label");
+
+    let mut time_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, mid - 30, screensize.x - 12, mid + 30)),
+    );
+    time_tv.style = GlyphStyle::ExtraLarge;
+    time_tv.clear_area = true;
+    write!(time_tv.text, "{}", time).unwrap();
+    gam.post_textview(&mut time_tv).expect("can't post time");
+
+    let mut nav_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, screensize.y - 50, screensize.x - 12, screensize.y - 10)),
+    );
+    nav_tv.style = GlyphStyle::Small;
+    nav_tv.clear_area = true;
+    write!(nav_tv.text, "f/q/F4=full view").unwrap();
+    gam.post_textview(&mut nav_tv).expect("can't post footer");
+
+    gam.redraw().expect("can't redraw");
+}
+
+pub fn draw_clock(gam: &Gam, content: Gid, screensize: Point, seconds_of_day: u32) {
+    clear_screen(gam, content, screensize);
+
+    let mut title_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 8, screensize.x - 12, 36)),
+    );
+    title_tv.style = GlyphStyle::Bold;
+    title_tv.clear_area = true;
+    write!(title_tv.text, "CLOCK").unwrap();
+    gam.post_textview(&mut title_tv).expect("can't post title");
+
+    let mut time_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(20, 50, screensize.x - 20, 90)),
+    );
+    time_tv.style = GlyphStyle::Bold;
+    time_tv.clear_area = true;
+    write!(time_tv.text, "  {}", format_time_of_day(seconds_of_day)).unwrap();
+    gam.post_textview(&mut time_tv).expect("can't post time");
+
+    let mut nav_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, screensize.y - 50, screensize.x - 12, screensize.y - 10)),
+    );
+    nav_tv.style = GlyphStyle::Small;
+    nav_tv.clear_area = true;
+    write!(nav_tv.text, "F1=menu F4=back").unwrap();
+    gam.post_textview(&mut nav_tv).expect("can't post footer");
+
+    gam.redraw().expect("can't redraw");
+}
+
+pub fn draw_event_log(gam: &Gam, content: Gid, screensize: Point, log: &EventLog) {
+    clear_screen(gam, content, screensize);
+
+    let mut title_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, 8, screensize.x - 12, 36)),
+    );
+    title_tv.style = GlyphStyle::Bold;
+    title_tv.clear_area = true;
+    write!(title_tv.text, "EVENT LOG").unwrap();
+    gam.post_textview(&mut title_tv).expect("can't post title");
+
+    let line_height = 20;
+    let list_top = 44;
+    let list_bottom = screensize.y - 60;
+    let max_visible = ((list_bottom - list_top) / line_height) as usize;
+
+    if log.len() == 0 {
+        let mut empty_tv = TextView::new(
+            content,
+            TextBounds::BoundingBox(Rectangle::new_coords(20, list_top, screensize.x - 20, list_top + 30)),
+        );
+        empty_tv.style = GlyphStyle::Regular;
+        empty_tv.clear_area = true;
+        write!(empty_tv.text, "No events yet.").unwrap();
+        gam.post_textview(&mut empty_tv).expect("can't post empty message");
+    }
+
+    for (i, event) in log.recent().take(max_visible).enumerate() {
+        let y = list_top + (i as isize) * line_height;
+        let mut tv = TextView::new(
+            content,
+            TextBounds::BoundingBox(Rectangle::new_coords(12, y, screensize.x - 12, y + line_height - 2)),
+        );
+        tv.style = GlyphStyle::Small;
+        tv.clear_area = true;
+        write!(
+            tv.text,
+            "{} {:<9} {}",
+            format_hms(event.now_ms),
+            event.source.label(),
+            event.kind.label()
+        ).unwrap();
+        gam.post_textview(&mut tv).expect("can't post event");
+    }
+
+    let mut nav_tv = TextView::new(
+        content,
+        TextBounds::BoundingBox(Rectangle::new_coords(12, screensize.y - 50, screensize.x - 12, screensize.y - 10)),
+    );
+    nav_tv.style = GlyphStyle::Small;
+    nav_tv.clear_area = true;
+    write!(nav_tv.text, "F1=menu F4=back").unwrap();
     gam.post_textview(&mut nav_tv).expect("can't post footer");
 
     gam.redraw().expect("can't redraw");
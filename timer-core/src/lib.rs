@@ -1,7 +1,25 @@
 //! Pure timing logic library with no platform dependencies.
 //! Testable on host, usable on Xous target.
+//!
+//! Only `core` and `alloc` are needed: `TimerCore` never allocates, and
+//! the `format_*` helpers return `alloc` strings (the `format_hms*_into`
+//! variants write into a caller's buffer instead). The default `std`
+//! feature just links std; `cargo build --no-default-features` checks
+//! that the crate still builds without it.
+//!
+//! The off-by-default `serde` feature derives `Serialize`/`Deserialize`
+//! on `TimerState` and `TimerSnapshot`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TimerState {
     Stopped,
     Running,
@@ -9,11 +27,93 @@ pub enum TimerState {
     Expired,
 }
 
+/// Size of a `TimerCore::serialize` snapshot.
+pub const TIMER_SNAPSHOT_LEN: usize = 38;
+/// Snapshot layout from before the speed field; decodes at normal speed.
+pub const TIMER_SNAPSHOT_MIN_LEN: usize = 34;
+
+impl TimerState {
+    /// Name for showing to the user: "Stopped", "Running", "Paused" or
+    /// "Done".
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimerState::Stopped => "Stopped",
+            TimerState::Running => "Running",
+            TimerState::Paused => "Paused",
+            TimerState::Expired => "Done",
+        }
+    }
+
+    /// Time is passing: the timer is running.
+    pub fn is_active(&self) -> bool {
+        *self == TimerState::Running
+    }
+
+    /// A countdown that reached its target.
+    pub fn is_finished(&self) -> bool {
+        *self == TimerState::Expired
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            TimerState::Stopped => 0,
+            TimerState::Running => 1,
+            TimerState::Paused => 2,
+            TimerState::Expired => 3,
+        }
+    }
+
+    fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            0 => Some(TimerState::Stopped),
+            1 => Some(TimerState::Running),
+            2 => Some(TimerState::Paused),
+            3 => Some(TimerState::Expired),
+            _ => None,
+        }
+    }
+}
+
+/// A timer's readings at one instant, so draw code can compute them once
+/// per frame instead of calling each accessor separately.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TimerFrame {
+    pub elapsed_ms: u64,
+    /// `None` for stopwatches.
+    pub remaining_ms: Option<u64>,
+    pub expired: bool,
+    /// Fraction of the target elapsed, in thousandths (see `elapsed_permille`).
+    pub permille: u16,
+}
+
+/// Field-by-field view of a `TimerCore`: the same state `serialize` packs
+/// into bytes, in a form serde can derive on.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimerSnapshot {
+    pub state: TimerState,
+    pub accumulated_ms: u64,
+    pub segment_start_ms: u64,
+    pub target_ms: Option<u64>,
+    pub lap_mark_ms: u64,
+    pub speed: f32,
+}
+
+#[derive(Clone)]
 pub struct TimerCore {
     pub state: TimerState,
     accumulated_ms: u64,
     segment_start_ms: u64,
     target_ms: Option<u64>,
+    /// Latest timestamp seen by a state change, so a clock that steps
+    /// backwards can't rewind elapsed time. Reads never move it.
+    last_seen_ms: u64,
+    /// Total elapsed when the current lap began.
+    lap_mark_ms: u64,
+    /// Virtual milliseconds per real millisecond, for fast-forwarded
+    /// tests and demos. `accumulated_ms` is already scaled; the running
+    /// segment is scaled as it's read.
+    speed: f32,
 }
 
 impl TimerCore {
@@ -23,6 +123,9 @@ impl TimerCore {
             accumulated_ms: 0,
             segment_start_ms: 0,
             target_ms: None,
+            last_seen_ms: 0,
+            lap_mark_ms: 0,
+            speed: 1.0,
         }
     }
 
@@ -32,35 +135,130 @@ impl TimerCore {
             accumulated_ms: 0,
             segment_start_ms: 0,
             target_ms: Some(duration_ms),
+            last_seen_ms: 0,
+            lap_mark_ms: 0,
+            speed: 1.0,
+        }
+    }
+
+    /// Like `new_countdown`, but None for a zero duration, which would
+    /// expire the instant it started.
+    pub fn try_new_countdown(duration_ms: u64) -> Option<Self> {
+        if duration_ms == 0 {
+            None
+        } else {
+            Some(Self::new_countdown(duration_ms))
+        }
+    }
+
+    /// Clamp `now_ms` so it never goes below an earlier state change, and
+    /// remember it. Only the `&mut self` transitions call this, so a stray
+    /// reading can't push the clock forward.
+    fn observe(&mut self, now_ms: u64) -> u64 {
+        let now_ms = self.clamped(now_ms);
+        self.last_seen_ms = now_ms;
+        now_ms
+    }
+
+    /// `now_ms`, but never below the last state change.
+    fn clamped(&self, now_ms: u64) -> u64 {
+        now_ms.max(self.last_seen_ms)
+    }
+
+    /// Virtual time for `real_ms` of wall time at the current speed.
+    fn scaled(&self, real_ms: u64) -> u64 {
+        if self.speed == 1.0 {
+            real_ms
+        } else {
+            (real_ms as f64 * self.speed as f64) as u64
         }
     }
 
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Run at `speed` virtual seconds per real second from `now_ms` on.
+    /// Time already run keeps the speed it ran at. Returns false (and
+    /// changes nothing) unless `speed` is finite and positive.
+    pub fn set_speed(&mut self, speed: f32, now_ms: u64) -> bool {
+        if !valid_speed(speed) {
+            return false;
+        }
+        if matches!(self.state, TimerState::Running | TimerState::Expired) {
+            self.accumulated_ms = self.elapsed_ms(now_ms);
+            self.segment_start_ms = self.observe(now_ms);
+        }
+        self.speed = speed;
+        true
+    }
+
+    /// Begin a run. Meant for fresh starts from `Stopped`; use `resume`
+    /// to continue a paused timer.
     pub fn start(&mut self, now_ms: u64) {
-        if self.state == TimerState::Running {
+        if matches!(self.state, TimerState::Running | TimerState::Expired) {
             return;
         }
+        let now_ms = self.observe(now_ms);
         self.segment_start_ms = now_ms;
         self.state = TimerState::Running;
     }
 
+    /// Continue a paused timer, keeping the time already accumulated.
+    /// No-op unless the timer is `Paused`.
+    pub fn resume(&mut self, now_ms: u64) {
+        if self.state != TimerState::Paused {
+            return;
+        }
+        self.segment_start_ms = self.observe(now_ms);
+        self.state = TimerState::Running;
+    }
+
     pub fn pause(&mut self, now_ms: u64) {
         if self.state != TimerState::Running {
             return;
         }
-        self.accumulated_ms += now_ms.saturating_sub(self.segment_start_ms);
+        let now_ms = self.observe(now_ms);
+        self.accumulated_ms += self.scaled(now_ms.saturating_sub(self.segment_start_ms));
         self.state = TimerState::Paused;
     }
 
+    /// Back to zero. A fresh run starts a fresh clock history; the speed
+    /// is kept.
     pub fn reset(&mut self) {
         self.accumulated_ms = 0;
         self.segment_start_ms = 0;
+        self.last_seen_ms = 0;
+        self.lap_mark_ms = 0;
         self.state = TimerState::Stopped;
     }
 
+    /// Reset and start again at `now_ms` in one step, from any state.
+    /// Keeps the target and speed.
+    pub fn restart(&mut self, now_ms: u64) {
+        self.reset();
+        self.start(now_ms);
+    }
+
+    /// Advance the state machine: a running countdown becomes `Expired`
+    /// once it reaches its target. Returns the state after the update.
+    /// Stopwatches never expire.
+    pub fn tick(&mut self, now_ms: u64) -> TimerState {
+        if self.state == TimerState::Running {
+            let now_ms = self.observe(now_ms);
+            if self.is_expired(now_ms) {
+                self.state = TimerState::Expired;
+            }
+        }
+        self.state
+    }
+
+    /// Time run so far. An expired countdown keeps counting past its target.
     pub fn elapsed_ms(&self, now_ms: u64) -> u64 {
+        let now_ms = self.clamped(now_ms);
         match self.state {
-            TimerState::Running => {
-                self.accumulated_ms + now_ms.saturating_sub(self.segment_start_ms)
+            TimerState::Running | TimerState::Expired => {
+                self.accumulated_ms + self.scaled(now_ms.saturating_sub(self.segment_start_ms))
             }
             _ => self.accumulated_ms,
         }
@@ -72,6 +270,26 @@ impl TimerCore {
         })
     }
 
+    /// `elapsed_ms` in whole seconds, truncated like the formatters.
+    pub fn elapsed_secs(&self, now_ms: u64) -> u64 {
+        self.elapsed_ms(now_ms) / 1000
+    }
+
+    /// `remaining_ms` in whole seconds, truncated like the formatters, so
+    /// it matches the digits on screen (4999 ms left reads 4). `None` for
+    /// stopwatches.
+    pub fn remaining_secs(&self, now_ms: u64) -> Option<u64> {
+        self.remaining_ms(now_ms).map(|ms| ms / 1000)
+    }
+
+    /// How far a countdown has run past its target: `Some(0)` at the
+    /// expiry instant, growing while the timer keeps running. `None`
+    /// before expiry and for stopwatches.
+    pub fn overtime_ms(&self, now_ms: u64) -> Option<u64> {
+        let target = self.target_ms?;
+        self.elapsed_ms(now_ms).checked_sub(target)
+    }
+
     pub fn is_expired(&self, now_ms: u64) -> bool {
         match self.target_ms {
             Some(target) => self.elapsed_ms(now_ms) >= target,
@@ -79,39 +297,243 @@ impl TimerCore {
         }
     }
 
+    /// Close the current lap and return its split. The total keeps
+    /// running; only the split restarts.
     pub fn lap(&mut self, now_ms: u64) -> u64 {
         if self.state != TimerState::Running {
             return 0;
         }
         let elapsed = self.elapsed_ms(now_ms);
-        // Reset accumulator but keep running from now
-        self.accumulated_ms = 0;
-        self.segment_start_ms = now_ms;
-        elapsed
+        let split = elapsed.saturating_sub(self.lap_mark_ms);
+        self.lap_mark_ms = elapsed;
+        split
+    }
+
+    /// Time since the last lap (or since the start if there were none).
+    pub fn lap_elapsed_ms(&self, now_ms: u64) -> u64 {
+        self.elapsed_ms(now_ms).saturating_sub(self.lap_mark_ms)
     }
 
     pub fn target_ms(&self) -> Option<u64> {
         self.target_ms
     }
+
+    /// Add time to a countdown's target. No-op for stopwatches.
+    pub fn extend_ms(&mut self, delta_ms: u64) {
+        if let Some(target) = self.target_ms.as_mut() {
+            *target = target.saturating_add(delta_ms);
+        }
+    }
+
+    /// Take time off a countdown's target, never below the time already
+    /// elapsed at `now_ms`, so shrinking too far expires it now rather than
+    /// in the past. No-op for stopwatches.
+    pub fn shrink_ms(&mut self, delta_ms: u64, now_ms: u64) {
+        let elapsed = self.elapsed_ms(now_ms);
+        if let Some(target) = self.target_ms.as_mut() {
+            *target = target.saturating_sub(delta_ms).max(elapsed);
+        }
+    }
+
+    /// Snapshot of the full timer state:
+    /// state u8 + accumulated u64 + segment start u64 + has-target u8 +
+    /// target u64 + lap mark u64 + speed f32, all little-endian. A running
+    /// timer's segment start is a ticktimer reading, so it's only
+    /// meaningful within the same boot.
+    pub fn serialize(&self) -> [u8; TIMER_SNAPSHOT_LEN] {
+        let mut buf = [0u8; TIMER_SNAPSHOT_LEN];
+        buf[0] = self.state.to_byte();
+        buf[1..9].copy_from_slice(&self.accumulated_ms.to_le_bytes());
+        buf[9..17].copy_from_slice(&self.segment_start_ms.to_le_bytes());
+        if let Some(target) = self.target_ms {
+            buf[17] = 1;
+            buf[18..26].copy_from_slice(&target.to_le_bytes());
+        }
+        buf[26..34].copy_from_slice(&self.lap_mark_ms.to_le_bytes());
+        buf[34..38].copy_from_slice(&self.speed.to_le_bytes());
+        buf
+    }
+
+    /// Rebuild a timer from `serialize` output. Returns None for short
+    /// input, unknown state/flag bytes or an unusable speed. Snapshots
+    /// from before the speed field (`TIMER_SNAPSHOT_MIN_LEN` bytes) run at
+    /// normal speed.
+    pub fn deserialize(bytes: &[u8]) -> Option<TimerCore> {
+        let speed = match bytes.len() {
+            TIMER_SNAPSHOT_MIN_LEN => 1.0,
+            len if len >= TIMER_SNAPSHOT_LEN => f32::from_le_bytes(bytes[34..38].try_into().ok()?),
+            _ => return None,
+        };
+        if !valid_speed(speed) {
+            return None;
+        }
+        let state = TimerState::from_byte(bytes[0])?;
+        let target_ms = match bytes[17] {
+            0 => None,
+            1 => Some(u64::from_le_bytes(bytes[18..26].try_into().ok()?)),
+            _ => return None,
+        };
+        Some(TimerCore {
+            state,
+            accumulated_ms: u64::from_le_bytes(bytes[1..9].try_into().ok()?),
+            segment_start_ms: u64::from_le_bytes(bytes[9..17].try_into().ok()?),
+            target_ms,
+            last_seen_ms: 0,
+            lap_mark_ms: u64::from_le_bytes(bytes[26..34].try_into().ok()?),
+            speed,
+        })
+    }
+
+    /// The timer's state as plain fields. Like `serialize`, a running
+    /// timer's segment start only means something within the same boot.
+    pub fn snapshot(&self) -> TimerSnapshot {
+        TimerSnapshot {
+            state: self.state,
+            accumulated_ms: self.accumulated_ms,
+            segment_start_ms: self.segment_start_ms,
+            target_ms: self.target_ms,
+            lap_mark_ms: self.lap_mark_ms,
+            speed: self.speed,
+        }
+    }
+
+    /// Rebuild a timer from `snapshot` output. An unusable speed falls
+    /// back to normal speed.
+    pub fn from_snapshot(snapshot: TimerSnapshot) -> TimerCore {
+        TimerCore {
+            state: snapshot.state,
+            accumulated_ms: snapshot.accumulated_ms,
+            segment_start_ms: snapshot.segment_start_ms,
+            target_ms: snapshot.target_ms,
+            last_seen_ms: 0,
+            lap_mark_ms: snapshot.lap_mark_ms,
+            speed: if valid_speed(snapshot.speed) { snapshot.speed } else { 1.0 },
+        }
+    }
+
+    /// All readings at `now_ms`, computed from a single elapsed value.
+    pub fn frame(&self, now_ms: u64) -> TimerFrame {
+        let elapsed_ms = self.elapsed_ms(now_ms);
+        TimerFrame {
+            elapsed_ms,
+            remaining_ms: self.target_ms.map(|target| target.saturating_sub(elapsed_ms)),
+            expired: self.target_ms.map(|target| elapsed_ms >= target).unwrap_or(false),
+            permille: self.permille_of(elapsed_ms),
+        }
+    }
+
+    /// Progress toward the target in thousandths (0-1000), using integer
+    /// math so bar widths track the displayed seconds exactly.
+    /// Stopwatches (no target) report 0; a zero target reports 1000.
+    pub fn elapsed_permille(&self, now_ms: u64) -> u16 {
+        self.permille_of(self.elapsed_ms(now_ms))
+    }
+
+    /// Progress toward the target as a fraction in [0, 1], for callers
+    /// that want float math; same conventions as `elapsed_permille`.
+    pub fn progress_fraction(&self, now_ms: u64) -> f32 {
+        match self.target_ms {
+            Some(0) => 1.0,
+            Some(target) => {
+                let elapsed = self.elapsed_ms(now_ms).min(target);
+                (elapsed as f64 / target as f64) as f32
+            }
+            None => 0.0,
+        }
+    }
+
+    fn permille_of(&self, elapsed_ms: u64) -> u16 {
+        match self.target_ms {
+            Some(0) => 1000,
+            Some(target) => {
+                let elapsed = elapsed_ms.min(target);
+                (elapsed as u128 * 1000 / target as u128) as u16
+            }
+            None => 0,
+        }
+    }
 }
 
-/// Format milliseconds as "HH:MM:SS"
-pub fn format_hms(ms: u64) -> String {
+fn valid_speed(speed: f32) -> bool {
+    speed.is_finite() && speed > 0.0
+}
+
+/// Buffer size that fits any `format_hms*_into` output, up to u64::MAX ms.
+pub const FORMAT_BUF_LEN: usize = 24;
+
+/// `fmt::Write` into a fixed byte buffer. Fails instead of truncating.
+struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl fmt::Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        let dst = self.buf.get_mut(self.len..end).ok_or(fmt::Error)?;
+        dst.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Format into `buf`, returning the written part, or None if it doesn't fit.
+fn write_into<'a>(buf: &'a mut [u8], args: fmt::Arguments) -> Option<&'a str> {
+    let mut w = SliceWriter { buf, len: 0 };
+    fmt::write(&mut w, args).ok()?;
+    let SliceWriter { buf, len } = w;
+    let buf: &'a [u8] = buf;
+    core::str::from_utf8(&buf[..len]).ok()
+}
+
+/// `format_hms` into `buf` without allocating. None if `buf` is too small
+/// (`FORMAT_BUF_LEN` always fits), in which case its contents are undefined.
+pub fn format_hms_into(ms: u64, buf: &mut [u8]) -> Option<&str> {
     let total_secs = ms / 1000;
     let h = total_secs / 3600;
     let m = (total_secs % 3600) / 60;
     let s = total_secs % 60;
-    format!("{:02}:{:02}:{:02}", h, m, s)
+    write_into(buf, format_args!("{:02}:{:02}:{:02}", h, m, s))
 }
 
-/// Format milliseconds as "HH:MM:SS.cs" (centiseconds)
-pub fn format_hms_cs(ms: u64) -> String {
+/// `format_hms_cs` into `buf`; see `format_hms_into`.
+pub fn format_hms_cs_into(ms: u64, buf: &mut [u8]) -> Option<&str> {
     let total_secs = ms / 1000;
     let cs = (ms % 1000) / 10;
     let h = total_secs / 3600;
     let m = (total_secs % 3600) / 60;
     let s = total_secs % 60;
-    format!("{:02}:{:02}:{:02}.{:02}", h, m, s, cs)
+    write_into(buf, format_args!("{:02}:{:02}:{:02}.{:02}", h, m, s, cs))
+}
+
+/// `format_hms_millis` into `buf`; see `format_hms_into`.
+pub fn format_hms_millis_into(ms: u64, buf: &mut [u8]) -> Option<&str> {
+    let total_secs = ms / 1000;
+    let h = total_secs / 3600;
+    let m = (total_secs % 3600) / 60;
+    let s = total_secs % 60;
+    write_into(buf, format_args!("{:02}:{:02}:{:02}.{:03}", h, m, s, ms % 1000))
+}
+
+/// Format milliseconds as "HH:MM:SS". Past 99 hours the hours field
+/// widens ("100:00:00") rather than wrapping or switching to days, so the
+/// output always reads back through `parse_hms`; layouts that need the
+/// width ask `display_len`. The `_cs` and `_millis` variants do the same.
+pub fn format_hms(ms: u64) -> String {
+    let mut buf = [0u8; FORMAT_BUF_LEN];
+    format_hms_into(ms, &mut buf).unwrap_or_default().to_string()
+}
+
+/// Format milliseconds as "HH:MM:SS.cs" (centiseconds)
+pub fn format_hms_cs(ms: u64) -> String {
+    let mut buf = [0u8; FORMAT_BUF_LEN];
+    format_hms_cs_into(ms, &mut buf).unwrap_or_default().to_string()
+}
+
+/// Format milliseconds as "HH:MM:SS.mmm" (for recorded laps)
+pub fn format_hms_millis(ms: u64) -> String {
+    let mut buf = [0u8; FORMAT_BUF_LEN];
+    format_hms_millis_into(ms, &mut buf).unwrap_or_default().to_string()
 }
 
 /// Format milliseconds as "MM:SS" (for pomodoro/countdown)
@@ -122,24 +544,180 @@ pub fn format_ms(ms: u64) -> String {
     format!("{:02}:{:02}", m, s)
 }
 
+/// Format milliseconds as "MM:SS.cs" (for lap averages); minutes keep
+/// counting past an hour
+pub fn format_ms_cs(ms: u64) -> String {
+    let total_secs = ms / 1000;
+    let m = total_secs / 60;
+    let s = total_secs % 60;
+    format!("{:02}:{:02}.{:02}", m, s, (ms % 1000) / 10)
+}
+
+/// Format milliseconds as compactly as the value allows: "M:SS" under an
+/// hour (at least "0:SS"), "H:MM:SS" from an hour up.
+pub fn format_auto(ms: u64) -> String {
+    let total_secs = ms / 1000;
+    let h = total_secs / 3600;
+    let m = (total_secs % 3600) / 60;
+    let s = total_secs % 60;
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+/// The fixed-layout time formatters, for choosing one at runtime.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FormatKind {
+    /// `format_hms`
+    Hms,
+    /// `format_hms_cs`
+    HmsCs,
+    /// `format_hms_millis`
+    HmsMillis,
+    /// `format_ms`
+    Ms,
+    /// `format_auto`
+    Auto,
+}
+
+impl FormatKind {
+    pub fn format(self, ms: u64) -> String {
+        match self {
+            FormatKind::Hms => format_hms(ms),
+            FormatKind::HmsCs => format_hms_cs(ms),
+            FormatKind::HmsMillis => format_hms_millis(ms),
+            FormatKind::Ms => format_ms(ms),
+            FormatKind::Auto => format_auto(ms),
+        }
+    }
+}
+
+/// Decimal digits in `n`, zero-padded to at least `min`.
+fn digit_count(mut n: u64, min: usize) -> usize {
+    let mut digits = 1;
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+    digits.max(min)
+}
+
+/// Characters `kind.format(ms)` would produce, without building the
+/// string, so layout code can size a box before drawing into it.
+pub fn display_len(ms: u64, kind: FormatKind) -> usize {
+    let total_secs = ms / 1000;
+    let h = total_secs / 3600;
+    match kind {
+        // "HH" + ":MM:SS", then ".cs" or ".mmm"
+        FormatKind::Hms => digit_count(h, 2) + 6,
+        FormatKind::HmsCs => digit_count(h, 2) + 9,
+        FormatKind::HmsMillis => digit_count(h, 2) + 10,
+        // Minutes aren't wrapped into hours
+        FormatKind::Ms => digit_count(total_secs / 60, 2) + 3,
+        FormatKind::Auto if h > 0 => digit_count(h, 1) + 6,
+        FormatKind::Auto => digit_count(total_secs / 60, 1) + 3,
+    }
+}
+
+/// How `format_duration_long` spells its units.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DurationStyle {
+    /// "1h 2m"
+    Abbrev,
+    /// "1 hour 2 minutes"
+    Full,
+}
+
+/// Spellings of one time unit. Kept in a table rather than inline so a
+/// translation only has to swap the table.
+pub struct UnitNames {
+    pub abbrev: &'static str,
+    pub singular: &'static str,
+    pub plural: &'static str,
+}
+
+impl UnitNames {
+    fn render(&self, value: u64, style: DurationStyle) -> String {
+        match style {
+            DurationStyle::Abbrev => format!("{}{}", value, self.abbrev),
+            DurationStyle::Full if value == 1 => format!("1 {}", self.singular),
+            DurationStyle::Full => format!("{} {}", value, self.plural),
+        }
+    }
+}
+
+/// Hours, minutes, seconds.
+pub const UNIT_NAMES: [UnitNames; 3] = [
+    UnitNames { abbrev: "h", singular: "hour", plural: "hours" },
+    UnitNames { abbrev: "m", singular: "minute", plural: "minutes" },
+    UnitNames { abbrev: "s", singular: "second", plural: "seconds" },
+];
+
+/// Format milliseconds for reading rather than ticking, e.g. "1h 23m 4s"
+/// or "2 hours 5 minutes". Zero units are left out; zero itself is "0s"
+/// ("0 seconds").
+pub fn format_duration_long(ms: u64, style: DurationStyle) -> String {
+    let total_secs = ms / 1000;
+    let values = [total_secs / 3600, (total_secs % 3600) / 60, total_secs % 60];
+    let mut out = String::new();
+    for (value, names) in values.iter().zip(UNIT_NAMES.iter()).filter(|(value, _)| **value > 0) {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(&names.render(*value, style));
+    }
+    if out.is_empty() {
+        out = UNIT_NAMES[2].render(0, style);
+    }
+    out
+}
+
+/// Parse "SS", "MM:SS" or "HH:MM:SS" into milliseconds.
+/// The leading field may be any size ("90" is 90 seconds); later fields
+/// must be below 60. Returns None for anything malformed.
+pub fn parse_hms(s: &str) -> Option<u64> {
+    let mut fields = [0u64; 3];
+    let mut count = 0;
+    for part in s.trim().split(':') {
+        let part = part.trim();
+        if count == fields.len() || part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        fields[count] = part.parse().ok()?;
+        if count > 0 && fields[count] >= 60 {
+            return None;
+        }
+        count += 1;
+    }
+    let secs = fields[..count]
+        .iter()
+        .try_fold(0u64, |acc, &f| acc.checked_mul(60)?.checked_add(f))?;
+    secs.checked_mul(1000)
+}
+
 /// Serialize a u64 to 8 bytes (little-endian)
 pub fn serialize_u64(val: u64) -> [u8; 8] {
     val.to_le_bytes()
 }
 
-/// Deserialize a u64 from bytes (little-endian)
-pub fn deserialize_u64(bytes: &[u8]) -> u64 {
-    if bytes.len() < 8 {
-        return 0;
-    }
-    let mut buf = [0u8; 8];
-    buf.copy_from_slice(&bytes[..8]);
-    u64::from_le_bytes(buf)
+/// Deserialize a u64 from the first 8 bytes (little-endian).
+/// Returns None if there are fewer than 8, e.g. a truncated read.
+pub fn deserialize_u64(bytes: &[u8]) -> Option<u64> {
+    let buf: [u8; 8] = bytes.get(..8)?.try_into().ok()?;
+    Some(u64::from_le_bytes(buf))
+}
+
+/// `deserialize_u64`, falling back to `default` on short input.
+pub fn deserialize_u64_or(bytes: &[u8], default: u64) -> u64 {
+    deserialize_u64(bytes).unwrap_or(default)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     #[test]
     fn test_stopwatch_basic() {
@@ -178,6 +756,393 @@ mod tests {
         assert!(cd.is_expired(11_000));
     }
 
+    #[test]
+    fn test_tick_expires_countdown() {
+        let mut t = TimerCore::new_countdown(5000);
+        t.start(1000);
+        assert_eq!(t.tick(5000), TimerState::Running);
+        assert_eq!(t.tick(6000), TimerState::Expired);
+        assert_eq!(t.state, TimerState::Expired);
+        assert_eq!(t.remaining_ms(7000), Some(0));
+
+        // Start/pause don't leave the expired state; reset does
+        t.start(7000);
+        t.pause(7000);
+        assert_eq!(t.tick(8000), TimerState::Expired);
+        t.reset();
+        assert_eq!(t.tick(9000), TimerState::Stopped);
+    }
+
+    #[test]
+    fn test_tick_never_expires_stopwatch() {
+        let mut t = TimerCore::new_stopwatch();
+        t.start(0);
+        assert_eq!(t.tick(u64::MAX / 2), TimerState::Running);
+        t.pause(10);
+        assert_eq!(t.tick(20), TimerState::Paused);
+    }
+
+    #[test]
+    fn test_backwards_clock_never_rewinds() {
+        let mut t = TimerCore::new_stopwatch();
+        t.start(1000);
+        let mut last = 0;
+        for now in [5000, 3000, 4000, 6000, 2000, 7000] {
+            t.tick(now);
+            let elapsed = t.elapsed_ms(now);
+            assert!(elapsed >= last, "elapsed went back at {}", now);
+            last = elapsed;
+        }
+        assert_eq!(last, 6000);
+
+        // Pause with an earlier timestamp keeps the time already counted
+        t.pause(4000);
+        assert_eq!(t.elapsed_ms(8000), 6000);
+    }
+
+    #[test]
+    fn test_stray_read_does_not_poison_later_pause() {
+        let mut t = TimerCore::new_stopwatch();
+        t.start(1000);
+        assert_eq!(t.elapsed_ms(u64::MAX / 2), u64::MAX / 2 - 1000);
+        t.pause(4000);
+        assert_eq!(t.elapsed_ms(5000), 3000);
+        t.resume(6000);
+        assert_eq!(t.elapsed_ms(7000), 4000);
+    }
+
+    #[test]
+    fn test_frame_matches_accessors() {
+        let mut cd = TimerCore::new_countdown(10_000);
+        cd.start(1000);
+        for now in [1000, 4500, 11_000, 20_000] {
+            let f = cd.frame(now);
+            assert_eq!(f.elapsed_ms, cd.elapsed_ms(now));
+            assert_eq!(f.remaining_ms, cd.remaining_ms(now));
+            assert_eq!(f.expired, cd.is_expired(now));
+            assert_eq!(f.permille, cd.elapsed_permille(now));
+        }
+
+        let mut sw = TimerCore::new_stopwatch();
+        sw.start(0);
+        let f = sw.frame(5000);
+        assert_eq!(f, TimerFrame { elapsed_ms: 5000, remaining_ms: None, expired: false, permille: 0 });
+
+        let zero = TimerCore::new_countdown(0);
+        assert_eq!(zero.frame(0).permille, zero.elapsed_permille(0));
+        assert!(zero.frame(0).expired);
+    }
+
+    fn round_trip(t: &TimerCore) -> TimerCore {
+        TimerCore::deserialize(&t.serialize()).expect("snapshot should decode")
+    }
+
+    #[test]
+    fn test_snapshot_each_state() {
+        let stopped = TimerCore::new_countdown(60_000);
+        let r = round_trip(&stopped);
+        assert_eq!(r.state, TimerState::Stopped);
+        assert_eq!(r.elapsed_ms(5000), 0);
+
+        let mut running = TimerCore::new_countdown(60_000);
+        running.start(1000);
+        running.pause(3000);
+        running.resume(10_000);
+        let r = round_trip(&running);
+        assert_eq!(r.state, TimerState::Running);
+        assert_eq!(r.elapsed_ms(12_000), running.elapsed_ms(12_000));
+
+        let mut paused = TimerCore::new_stopwatch();
+        paused.start(0);
+        paused.lap(1000);
+        paused.pause(4500);
+        let r = round_trip(&paused);
+        assert_eq!(r.state, TimerState::Paused);
+        assert_eq!(r.elapsed_ms(99_000), 4500);
+        assert_eq!(r.lap_elapsed_ms(99_000), 3500);
+
+        let mut expired = TimerCore::new_countdown(5000);
+        expired.start(0);
+        expired.tick(6000);
+        let r = round_trip(&expired);
+        assert_eq!(r.state, TimerState::Expired);
+        assert_eq!(r.overtime_ms(7000), Some(2000));
+    }
+
+    #[test]
+    fn test_snapshot_keeps_timer_kind() {
+        let sw = round_trip(&TimerCore::new_stopwatch());
+        assert_eq!(sw.target_ms(), None);
+        let cd = round_trip(&TimerCore::new_countdown(0));
+        assert_eq!(cd.target_ms(), Some(0));
+    }
+
+    #[test]
+    fn test_snapshot_fields_round_trip() {
+        let mut paused = TimerCore::new_countdown(60_000);
+        paused.start(1000);
+        paused.pause(16_000);
+        let snap = paused.snapshot();
+        assert_eq!(snap.state, TimerState::Paused);
+        assert_eq!(snap.accumulated_ms, 15_000);
+        assert_eq!(snap.target_ms, Some(60_000));
+
+        let r = TimerCore::from_snapshot(snap);
+        assert_eq!(r.snapshot(), snap);
+        assert_eq!(r.remaining_ms(99_000), Some(45_000));
+        // Both snapshot forms describe the same timer
+        assert_eq!(round_trip(&paused).snapshot(), snap);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snapshot_json_round_trip() {
+        let mut paused = TimerCore::new_countdown(60_000);
+        paused.start(1000);
+        paused.pause(16_000);
+        let json = serde_json::to_string(&paused.snapshot()).unwrap();
+        assert!(json.contains("\"state\":\"Paused\""));
+        assert!(json.contains("\"target_ms\":60000"));
+
+        let snap: TimerSnapshot = serde_json::from_str(&json).unwrap();
+        let r = TimerCore::from_snapshot(snap);
+        assert_eq!(r.state, TimerState::Paused);
+        assert_eq!(r.remaining_ms(99_000), Some(45_000));
+        assert_eq!(r.serialize(), paused.serialize());
+    }
+
+    #[test]
+    fn test_snapshot_rejects_bad_input() {
+        let snap = TimerCore::new_countdown(1000).serialize();
+        assert!(TimerCore::deserialize(&snap[..TIMER_SNAPSHOT_LEN - 1]).is_none());
+        let mut bad_state = snap;
+        bad_state[0] = 9;
+        assert!(TimerCore::deserialize(&bad_state).is_none());
+        let mut bad_flag = snap;
+        bad_flag[17] = 2;
+        assert!(TimerCore::deserialize(&bad_flag).is_none());
+        for speed in [0.0f32, -1.0, f32::NAN, f32::INFINITY] {
+            let mut bad_speed = snap;
+            bad_speed[34..38].copy_from_slice(&speed.to_le_bytes());
+            assert!(TimerCore::deserialize(&bad_speed).is_none(), "speed {}", speed);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_keeps_speed() {
+        // A 2:1 countdown, running, then paused
+        let mut cd = TimerCore::new_countdown(60_000);
+        cd.set_speed(2.0, 0);
+        cd.start(1000);
+        let r = round_trip(&cd);
+        assert_eq!(r.speed(), 2.0);
+        assert_eq!(r.elapsed_ms(6000), 10_000);
+        assert_eq!(r.elapsed_ms(6000), cd.elapsed_ms(6000));
+
+        cd.pause(11_000);
+        let r = round_trip(&cd);
+        assert_eq!(r.elapsed_ms(99_000), 20_000);
+        assert_eq!(TimerCore::from_snapshot(cd.snapshot()).speed(), 2.0);
+
+        // Older, shorter snapshots run at normal speed
+        let legacy = TimerCore::deserialize(&cd.serialize()[..TIMER_SNAPSHOT_MIN_LEN]).unwrap();
+        assert_eq!(legacy.speed(), 1.0);
+        assert_eq!(legacy.elapsed_ms(99_000), 20_000);
+    }
+
+    #[test]
+    fn test_scaled_running_countdown_round_trip() {
+        // A minute at 2:1, saved while running; 10 s of real time later
+        // 20 s have run
+        let mut cd = TimerCore::new_countdown(60_000);
+        cd.set_speed(2.0, 0);
+        cd.start(0);
+        let saved = TimerCore::deserialize(&cd.serialize()).unwrap();
+        assert_eq!(saved.state, TimerState::Running);
+        assert_eq!(saved.remaining_ms(10_000), Some(40_000));
+
+        // Another 10 s of real time takes 20 s more off
+        let mut restored = saved;
+        assert_eq!(restored.remaining_ms(20_000), Some(20_000));
+        assert_eq!(restored.tick(29_999), TimerState::Running);
+        assert_eq!(restored.tick(30_000), TimerState::Expired);
+    }
+
+    #[test]
+    fn test_speed_scales_elapsed() {
+        let mut sw = TimerCore::new_stopwatch();
+        assert!(sw.set_speed(2.0, 0));
+        sw.start(0);
+        assert_eq!(sw.elapsed_ms(1000), 2000);
+
+        // Accumulated time keeps its scaling across pause and resume
+        sw.pause(1000);
+        assert_eq!(sw.elapsed_ms(50_000), 2000);
+        sw.resume(50_000);
+        assert_eq!(sw.elapsed_ms(50_500), 3000);
+        sw.pause(51_000);
+        assert_eq!(sw.elapsed_ms(60_000), 4000);
+
+        // Countdowns expire on virtual time
+        let mut cd = TimerCore::new_countdown(10_000);
+        cd.set_speed(10.0, 0);
+        cd.start(0);
+        assert_eq!(cd.tick(999), TimerState::Running);
+        assert_eq!(cd.tick(1000), TimerState::Expired);
+    }
+
+    #[test]
+    fn test_set_speed_while_running() {
+        let mut sw = TimerCore::new_stopwatch();
+        sw.start(0);
+        // The first second ran at normal speed and stays that way
+        assert!(sw.set_speed(10.0, 1000));
+        assert_eq!(sw.elapsed_ms(1000), 1000);
+        assert_eq!(sw.elapsed_ms(2000), 11_000);
+        assert!(sw.set_speed(0.5, 2000));
+        assert_eq!(sw.elapsed_ms(4000), 12_000);
+
+        for bad in [0.0, -2.0, f32::NAN, f32::INFINITY] {
+            assert!(!sw.set_speed(bad, 4000));
+        }
+        assert_eq!(sw.speed(), 0.5);
+
+        // Reset zeroes the time but keeps the speed
+        sw.reset();
+        assert_eq!(sw.speed(), 0.5);
+        assert_eq!(sw.elapsed_ms(5000), 0);
+    }
+
+    #[test]
+    fn test_try_new_countdown() {
+        assert!(TimerCore::try_new_countdown(0).is_none());
+        let cd = TimerCore::try_new_countdown(1).unwrap();
+        assert_eq!(cd.target_ms(), Some(1));
+        assert_eq!(cd.state, TimerState::Stopped);
+        assert_eq!(TimerCore::try_new_countdown(90_000).unwrap().target_ms(), Some(90_000));
+    }
+
+    #[test]
+    fn test_restart() {
+        let mut cd = TimerCore::new_countdown(60_000);
+        cd.start(0);
+        cd.pause(20_000);
+        cd.restart(30_000);
+        assert_eq!(cd.state, TimerState::Running);
+        assert_eq!(cd.elapsed_ms(30_000), 0);
+        assert_eq!(cd.remaining_ms(40_000), Some(50_000));
+
+        let mut sw = TimerCore::new_stopwatch();
+        sw.start(0);
+        sw.lap(4000);
+        sw.restart(5000);
+        assert_eq!(sw.elapsed_ms(5000), 0);
+        assert_eq!(sw.lap_elapsed_ms(6000), 1000);
+        assert_eq!(sw.target_ms(), None);
+
+        // Expired countdowns run again from the top
+        let mut done = TimerCore::new_countdown(1000);
+        done.start(0);
+        done.tick(2000);
+        done.restart(3000);
+        assert_eq!(done.tick(3500), TimerState::Running);
+        assert_eq!(done.remaining_ms(3500), Some(500));
+    }
+
+    #[test]
+    fn test_extend_near_expiry_keeps_running() {
+        let mut t = TimerCore::new_countdown(60_000);
+        t.start(0);
+        assert_eq!(t.tick(59_000), TimerState::Running);
+        t.extend_ms(60_000);
+        assert_eq!(t.tick(61_000), TimerState::Running);
+        assert_eq!(t.remaining_ms(61_000), Some(59_000));
+    }
+
+    #[test]
+    fn test_shrink_past_elapsed_expires() {
+        let mut t = TimerCore::new_countdown(60_000);
+        t.start(0);
+        t.shrink_ms(10_000, 20_000);
+        assert_eq!(t.target_ms(), Some(50_000));
+        t.shrink_ms(60_000, 30_000);
+        assert_eq!(t.target_ms(), Some(30_000));
+        assert_eq!(t.overtime_ms(30_000), Some(0));
+        assert_eq!(t.tick(30_000), TimerState::Expired);
+    }
+
+    #[test]
+    fn test_adjust_stopwatch_is_noop() {
+        let mut t = TimerCore::new_stopwatch();
+        t.extend_ms(1000);
+        t.shrink_ms(1000, 0);
+        assert_eq!(t.target_ms(), None);
+    }
+
+    #[test]
+    fn test_state_predicates_and_names() {
+        let states = [TimerState::Stopped, TimerState::Running, TimerState::Paused, TimerState::Expired];
+        assert_eq!(states.map(|s| s.is_active()), [false, true, false, false]);
+        assert_eq!(states.map(|s| s.is_finished()), [false, false, false, true]);
+        assert_eq!(states.map(|s| s.label()), ["Stopped", "Running", "Paused", "Done"]);
+    }
+
+    #[test]
+    fn test_whole_seconds() {
+        let mut t = TimerCore::new_countdown(10_000);
+        assert_eq!(t.elapsed_secs(0), 0);
+        assert_eq!(t.remaining_secs(0), Some(10));
+        t.start(0);
+        assert_eq!(t.elapsed_secs(5001), 5);
+        assert_eq!(t.remaining_secs(5001), Some(4));
+        // Agrees with the formatted display
+        assert_eq!(format_ms(t.remaining_ms(5001).unwrap()), "00:04");
+        assert_eq!(t.remaining_secs(9999), Some(0));
+        assert_eq!(t.remaining_secs(12_000), Some(0));
+
+        let mut sw = TimerCore::new_stopwatch();
+        sw.start(0);
+        assert_eq!(sw.remaining_secs(4999), None);
+        assert_eq!(sw.elapsed_secs(4999), 4);
+    }
+
+    #[test]
+    fn test_overtime() {
+        let mut t = TimerCore::new_countdown(5000);
+        t.start(1000);
+        assert_eq!(t.overtime_ms(5999), None);
+        assert_eq!(t.overtime_ms(6000), Some(0));
+        assert_eq!(t.overtime_ms(18_000), Some(12_000));
+        t.tick(18_000);
+        assert_eq!(t.overtime_ms(20_000), Some(14_000));
+
+        let mut sw = TimerCore::new_stopwatch();
+        sw.start(0);
+        assert_eq!(sw.overtime_ms(100_000), None);
+    }
+
+    #[test]
+    fn test_resume_from_stopped_is_noop() {
+        let mut t = TimerCore::new_countdown(10_000);
+        t.resume(1000);
+        assert_eq!(t.state, TimerState::Stopped);
+        assert_eq!(t.elapsed_ms(5000), 0);
+    }
+
+    #[test]
+    fn test_resume_from_paused() {
+        let mut t = TimerCore::new_stopwatch();
+        t.start(1000);
+        t.pause(3000);
+        t.resume(10_000);
+        assert_eq!(t.state, TimerState::Running);
+        assert_eq!(t.elapsed_ms(11_000), 3000);
+
+        // Resuming while running doesn't restart the segment
+        t.resume(12_000);
+        assert_eq!(t.elapsed_ms(13_000), 5000);
+    }
+
     #[test]
     fn test_lap() {
         let mut sw = TimerCore::new_stopwatch();
@@ -185,17 +1150,66 @@ mod tests {
 
         let lap1 = sw.lap(5000);
         assert_eq!(lap1, 5000);
-        assert_eq!(sw.elapsed_ms(5000), 0); // Reset after lap
+        assert_eq!(sw.lap_elapsed_ms(5000), 0); // Split restarts
+        assert_eq!(sw.elapsed_ms(5000), 5000); // Total keeps going
 
         let lap2 = sw.lap(8000);
         assert_eq!(lap2, 3000);
+        assert_eq!(sw.elapsed_ms(8000), 8000);
+        assert_eq!(sw.lap_elapsed_ms(9500), 1500);
+
+        // Pausing holds both
+        sw.pause(10_000);
+        assert_eq!(sw.elapsed_ms(20_000), 10_000);
+        assert_eq!(sw.lap_elapsed_ms(20_000), 2000);
+        assert_eq!(sw.lap(20_000), 0);
+
+        sw.reset();
+        assert_eq!(sw.lap_elapsed_ms(0), 0);
+    }
+
+    #[test]
+    fn test_elapsed_permille() {
+        let mut cd = TimerCore::new_countdown(3000);
+        assert_eq!(cd.elapsed_permille(0), 0);
+        cd.start(0);
+        assert_eq!(cd.elapsed_permille(1000), 333);
+        assert_eq!(cd.elapsed_permille(1500), 500);
+        assert_eq!(cd.elapsed_permille(2000), 666);
+        assert_eq!(cd.elapsed_permille(3000), 1000);
+        assert_eq!(cd.elapsed_permille(9000), 1000); // Clamped past expiry
+
+        let big = TimerCore::new_countdown(u64::MAX);
+        assert_eq!(big.elapsed_permille(0), 0);
+
+        let mut sw = TimerCore::new_stopwatch();
+        sw.start(0);
+        assert_eq!(sw.elapsed_permille(5000), 0);
+
+        assert_eq!(TimerCore::new_countdown(0).elapsed_permille(0), 1000);
+    }
+
+    #[test]
+    fn test_progress_fraction() {
+        let mut cd = TimerCore::new_countdown(4000);
+        assert_eq!(cd.progress_fraction(0), 0.0);
+        cd.start(0);
+        assert_eq!(cd.progress_fraction(2000), 0.5);
+        assert_eq!(cd.progress_fraction(4000), 1.0);
+        assert_eq!(cd.progress_fraction(9000), 1.0); // Clamped past expiry
+
+        let mut sw = TimerCore::new_stopwatch();
+        sw.start(0);
+        assert_eq!(sw.progress_fraction(5000), 0.0);
+
+        assert_eq!(TimerCore::new_countdown(0).progress_fraction(0), 1.0);
     }
 
     #[test]
     fn test_format_hms() {
         assert_eq!(format_hms(0), "00:00:00");
         assert_eq!(format_hms(61_000), "00:01:01");
-        assert_eq!(format_hms(3661_000), "01:01:01");
+        assert_eq!(format_hms(3_661_000), "01:01:01");
     }
 
     #[test]
@@ -204,6 +1218,37 @@ mod tests {
         assert_eq!(format_hms_cs(12_340), "00:00:12.34");
     }
 
+    #[test]
+    fn test_format_hms_millis() {
+        assert_eq!(format_hms_millis(12_345), "00:00:12.345");
+        assert_eq!(format_hms_millis(1_000), "00:00:01.000");
+        assert_eq!(format_hms_millis(3_661_007), "01:01:01.007");
+    }
+
+    #[test]
+    fn test_format_into_matches_string_versions() {
+        let mut buf = [0u8; FORMAT_BUF_LEN];
+        for ms in [0, 999, 12_345, 59_999, 3_599_999, 3_661_007, 360_000_000, u64::MAX] {
+            assert_eq!(format_hms_into(ms, &mut buf), Some(format_hms(ms).as_str()));
+            assert_eq!(format_hms_cs_into(ms, &mut buf), Some(format_hms_cs(ms).as_str()));
+            assert_eq!(format_hms_millis_into(ms, &mut buf), Some(format_hms_millis(ms).as_str()));
+        }
+    }
+
+    #[test]
+    fn test_format_into_small_buffer() {
+        // "00:01:01" needs exactly 8 bytes
+        let mut buf = [0u8; 8];
+        assert_eq!(format_hms_into(61_000, &mut buf), Some("00:01:01"));
+        assert_eq!(format_hms_cs_into(61_000, &mut buf), None);
+        let mut short = [0u8; 7];
+        assert_eq!(format_hms_into(61_000, &mut short), None);
+        assert_eq!(format_hms_into(0, &mut []), None);
+        // Three-digit hours need one more byte
+        let mut buf = [0u8; 8];
+        assert_eq!(format_hms_into(360_000_000, &mut buf), None);
+    }
+
     #[test]
     fn test_format_ms() {
         assert_eq!(format_ms(0), "00:00");
@@ -211,10 +1256,165 @@ mod tests {
         assert_eq!(format_ms(300_000), "05:00");
     }
 
+    #[test]
+    fn test_format_ms_cs() {
+        assert_eq!(format_ms_cs(0), "00:00.00");
+        assert_eq!(format_ms_cs(83_459), "01:23.45");
+        assert_eq!(format_ms_cs(3_600_000), "60:00.00");
+    }
+
+    #[test]
+    fn test_format_auto() {
+        assert_eq!(format_auto(0), "0:00");
+        assert_eq!(format_auto(59_000), "0:59");
+        assert_eq!(format_auto(60_000), "1:00");
+        assert_eq!(format_auto(3_599_000), "59:59");
+        assert_eq!(format_auto(3_600_000), "1:00:00");
+        assert_eq!(format_auto(36_005_000), "10:00:05");
+    }
+
+    #[test]
+    fn test_format_duration_long() {
+        let abbrev = |ms| format_duration_long(ms, DurationStyle::Abbrev);
+        assert_eq!(abbrev(0), "0s");
+        assert_eq!(abbrev(999), "0s");
+        assert_eq!(abbrev(45_000), "45s");
+        assert_eq!(abbrev(3_600_000), "1h");
+        assert_eq!(abbrev(3_665_000), "1h 1m 5s");
+        assert_eq!(abbrev(7_500_000), "2h 5m");
+    }
+
+    // Formatters at zero, just below each rollover, and u64::MAX. Only
+    // division and remainder are involved, so none of this should panic;
+    // these pin down what the extremes look like.
+
+    #[test]
+    fn test_formatters_at_zero() {
+        assert_eq!(format_hms(0), "00:00:00");
+        assert_eq!(format_hms_cs(0), "00:00:00.00");
+        assert_eq!(format_hms_millis(0), "00:00:00.000");
+        assert_eq!(format_ms(0), "00:00");
+        assert_eq!(format_auto(0), "0:00");
+        assert_eq!(format_duration_long(0, DurationStyle::Abbrev), "0s");
+        assert_eq!(format_duration_long(0, DurationStyle::Full), "0 seconds");
+    }
+
+    #[test]
+    fn test_formatters_below_rollover() {
+        // Last millisecond of a second, minute, hour and of two-digit hours
+        assert_eq!(format_hms(999), "00:00:00");
+        assert_eq!(format_hms_cs(999), "00:00:00.99");
+        assert_eq!(format_hms_millis(59_999), "00:00:59.999");
+        assert_eq!(format_hms_cs(3_599_999), "00:59:59.99");
+        assert_eq!(format_hms(3_599_999), "00:59:59");
+        assert_eq!(format_hms(3_600_000), "01:00:00");
+        assert_eq!(format_hms(359_999_999), "99:59:59");
+        assert_eq!(format_hms(360_000_000), "100:00:00");
+        assert_eq!(format_ms(5_999_999), "99:59");
+        assert_eq!(format_ms(6_000_000), "100:00");
+        assert_eq!(format_auto(3_599_999), "59:59");
+        assert_eq!(format_auto(3_600_000), "1:00:00");
+        assert_eq!(format_duration_long(3_599_999, DurationStyle::Abbrev), "59m 59s");
+    }
+
+    #[test]
+    fn test_format_hms_past_99_hours() {
+        const HOUR: u64 = 3_600_000;
+        assert_eq!(format_hms(99 * HOUR), "99:00:00");
+        assert_eq!(format_hms(100 * HOUR), "100:00:00");
+        assert_eq!(format_hms_cs(100 * HOUR + 10), "100:00:00.01");
+        assert_eq!(format_hms_millis(100 * HOUR + 7), "100:00:00.007");
+        // 4 days 3:02:01 is still two hour digits; a week is three
+        assert_eq!(format_hms(4 * 24 * HOUR + 3 * HOUR + 2 * 60_000 + 1000), "99:02:01");
+        assert_eq!(format_hms(7 * 24 * HOUR + 5_000), "168:00:05");
+        assert_eq!(display_len(7 * 24 * HOUR, FormatKind::Hms), 9);
+        // Wide hours still parse back
+        assert_eq!(parse_hms("168:00:05"), Some(7 * 24 * HOUR + 5_000));
+    }
+
+    #[test]
+    fn test_formatters_at_u64_max() {
+        let ms = u64::MAX;
+        assert_eq!(format_hms(ms), "5124095576030:25:51");
+        assert_eq!(format_hms_cs(ms), "5124095576030:25:51.61");
+        assert_eq!(format_hms_millis(ms), "5124095576030:25:51.615");
+        assert_eq!(format_ms(ms), "307445734561825:51");
+        assert_eq!(format_auto(ms), "5124095576030:25:51");
+        assert_eq!(format_duration_long(ms, DurationStyle::Abbrev), "5124095576030h 25m 51s");
+        assert_eq!(format_duration_long(ms, DurationStyle::Full), "5124095576030 hours 25 minutes 51 seconds");
+        // Large values round-trip through the parser's checked arithmetic
+        assert_eq!(parse_hms(&format_hms(ms)), Some(ms / 1000 * 1000));
+    }
+
+    #[test]
+    fn test_display_len_matches_formatters() {
+        let kinds = [FormatKind::Hms, FormatKind::HmsCs, FormatKind::HmsMillis, FormatKind::Ms, FormatKind::Auto];
+        let mut values = vec![0, 999, 9_999, 59_999, 60_000, 599_999, 600_000, 3_599_999, 3_600_000,
+            35_999_999, 36_000_000, 359_999_999, 360_000_000, u64::MAX];
+        // Every power of ten and its neighbours
+        let mut p = 1u64;
+        while let Some(next) = p.checked_mul(10) {
+            values.extend([p - 1, p, p + 1]);
+            p = next;
+        }
+        for &ms in &values {
+            for kind in kinds {
+                assert_eq!(display_len(ms, kind), kind.format(ms).len(), "{:?} at {} ms", kind, ms);
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_duration_long_full() {
+        let full = |ms| format_duration_long(ms, DurationStyle::Full);
+        assert_eq!(full(0), "0 seconds");
+        assert_eq!(full(1_000), "1 second");
+        assert_eq!(full(45_000), "45 seconds");
+        assert_eq!(full(3_600_000), "1 hour");
+        assert_eq!(full(3_665_000), "1 hour 1 minute 5 seconds");
+        assert_eq!(full(7_500_000), "2 hours 5 minutes");
+        assert_eq!(full(180_000), "3 minutes");
+    }
+
+    #[test]
+    fn test_parse_hms() {
+        assert_eq!(parse_hms("90"), Some(90_000));
+        assert_eq!(parse_hms("1:30"), Some(90_000));
+        assert_eq!(parse_hms("1:05:00"), Some(3_900_000));
+        assert_eq!(parse_hms(" 120:00 "), Some(7_200_000));
+        assert_eq!(parse_hms("0"), Some(0));
+
+        assert_eq!(parse_hms("ab:cd"), None);
+        assert_eq!(parse_hms(""), None);
+        assert_eq!(parse_hms("1:"), None);
+        assert_eq!(parse_hms("1:75"), None);
+        assert_eq!(parse_hms("-5"), None);
+        assert_eq!(parse_hms("1:2:3:4"), None);
+        assert_eq!(parse_hms("99999999999999999999"), None);
+    }
+
     #[test]
     fn test_serialize_deserialize() {
         let val = 123456789u64;
         let bytes = serialize_u64(val);
-        assert_eq!(deserialize_u64(&bytes), val);
+        assert_eq!(deserialize_u64(&bytes), Some(val));
+    }
+
+    #[test]
+    fn test_deserialize_u64_lengths() {
+        let bytes = serialize_u64(0x0102_0304_0506_0708);
+        assert_eq!(deserialize_u64(&bytes[..7]), None);
+        assert_eq!(deserialize_u64(&bytes), Some(0x0102_0304_0506_0708));
+
+        // Only the first 8 bytes are read
+        let mut long = bytes.to_vec();
+        long.extend_from_slice(&serialize_u64(u64::MAX));
+        assert_eq!(long.len(), 16);
+        assert_eq!(deserialize_u64(&long), Some(0x0102_0304_0506_0708));
+
+        // A stored zero is distinguishable from a short read
+        assert_eq!(deserialize_u64(&[0u8; 8]), Some(0));
+        assert_eq!(deserialize_u64_or(&bytes[..7], 42), 42);
+        assert_eq!(deserialize_u64_or(&[0u8; 8], 42), 0);
     }
 }